@@ -7,7 +7,13 @@ use colorful::Colorful;
 use raydium_amm_v3::instruction;
 use raydium_amm_v3::instructions::*;
 use raydium_amm_v3::states::*;
+
+use super::utils::{
+    amount_to_ui_amount, deserialize_anchor_account, get_ui_amount_multiplier,
+    sqrt_price_x64_to_ui_price,
+};
 use regex::Regex;
+use solana_client::rpc_client::RpcClient;
 use solana_transaction_status::{
     option_serializer::OptionSerializer, EncodedTransaction, UiTransactionStatusMeta,
 };
@@ -21,9 +27,17 @@ pub enum InstructionDecodeType {
     Base58,
 }
 
+/// The rpc client and the block time of the events to convert the raw amounts of the decoded events into ui amounts
+pub struct UiAmountContext<'a> {
+    pub rpc_client: &'a RpcClient,
+    pub program_id: Pubkey,
+    pub unix_timestamp: i64,
+}
+
 pub fn parse_program_event(
     self_program_str: &str,
     meta: Option<UiTransactionStatusMeta>,
+    ui_amount_context: Option<&UiAmountContext>,
 ) -> Result<(), ClientError> {
     let logs: Vec<String> = if let Some(meta_data) = meta {
         let log_messages = if let OptionSerializer::Some(log_messages) = meta_data.log_messages {
@@ -41,10 +55,11 @@ pub fn parse_program_event(
             for l in logs {
                 let (new_program, did_pop) =
                     if !execution.is_empty() && self_program_str == execution.program() {
-                        handle_program_log(self_program_str, &l, true).unwrap_or_else(|e| {
-                            println!("Unable to parse log: {e}");
-                            std::process::exit(1);
-                        })
+                        handle_program_log(self_program_str, &l, true, ui_amount_context)
+                            .unwrap_or_else(|e| {
+                                println!("Unable to parse log: {e}");
+                                std::process::exit(1);
+                            })
                     } else {
                         let (program, did_pop) = handle_system_log(self_program_str, l);
                         (program, did_pop)
@@ -111,6 +126,7 @@ pub fn handle_program_log(
     self_program_str: &str,
     l: &str,
     with_prefix: bool,
+    ui_amount_context: Option<&UiAmountContext>,
 ) -> Result<(Option<String>, bool), ClientError> {
     // Log emitted from the current program.
     if let Some(log) = if with_prefix {
@@ -143,29 +159,136 @@ pub fn handle_program_log(
             ConfigChangeEvent::DISCRIMINATOR => {
                 println!("{:#?}", decode_event::<ConfigChangeEvent>(&mut slice)?);
             }
-            CollectPersonalFeeEvent::DISCRIMINATOR => {
+            PoolFeeOverrideChangeEvent::DISCRIMINATOR => {
                 println!(
                     "{:#?}",
-                    decode_event::<CollectPersonalFeeEvent>(&mut slice)?
+                    decode_event::<PoolFeeOverrideChangeEvent>(&mut slice)?
                 );
             }
-            CollectProtocolFeeEvent::DISCRIMINATOR => {
+            PoolOpenTimeChangeEvent::DISCRIMINATOR => {
                 println!(
                     "{:#?}",
-                    decode_event::<CollectProtocolFeeEvent>(&mut slice)?
+                    decode_event::<PoolOpenTimeChangeEvent>(&mut slice)?
                 );
             }
-            CreatePersonalPositionEvent::DISCRIMINATOR => {
+            RecycleRewardEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<RecycleRewardEvent>(&mut slice)?);
+            }
+            AddRewardFundsEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<AddRewardFundsEvent>(&mut slice)?);
+            }
+            RefundRewardFundsEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<RefundRewardFundsEvent>(&mut slice)?);
+            }
+            PoolPermissionChangeEvent::DISCRIMINATOR => {
                 println!(
                     "{:#?}",
-                    decode_event::<CreatePersonalPositionEvent>(&mut slice)?
+                    decode_event::<PoolPermissionChangeEvent>(&mut slice)?
                 );
             }
+            CollectPersonalFeeEvent::DISCRIMINATOR => {
+                let event = decode_event::<CollectPersonalFeeEvent>(&mut slice)?;
+                println!("{:#?}", event);
+                print_ui_amounts(ui_amount_context, |ctx| {
+                    let pool_id = get_position_pool_id(ctx, &event.position_nft_mint)?;
+                    print_pool_ui_amounts(
+                        ctx,
+                        &pool_id,
+                        &[("amount", event.amount_0, event.amount_1)],
+                        None,
+                    )
+                });
+            }
+            CollectPersonalRewardEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<CollectPersonalRewardEvent>(&mut slice)?
+                );
+            }
+            CollectProtocolFeeEvent::DISCRIMINATOR => {
+                let event = decode_event::<CollectProtocolFeeEvent>(&mut slice)?;
+                println!("{:#?}", event);
+                print_ui_amounts(ui_amount_context, |ctx| {
+                    print_pool_ui_amounts(
+                        ctx,
+                        &event.pool_state,
+                        &[("amount", event.amount_0, event.amount_1)],
+                        None,
+                    )
+                });
+            }
+            CreatePersonalPositionEvent::DISCRIMINATOR => {
+                let event = decode_event::<CreatePersonalPositionEvent>(&mut slice)?;
+                println!("{:#?}", event);
+                print_ui_amounts(ui_amount_context, |ctx| {
+                    print_pool_ui_amounts(
+                        ctx,
+                        &event.pool_state,
+                        &[(
+                            "deposit_amount",
+                            event.deposit_amount_0,
+                            event.deposit_amount_1,
+                        )],
+                        None,
+                    )
+                });
+            }
             DecreaseLiquidityEvent::DISCRIMINATOR => {
-                println!("{:#?}", decode_event::<DecreaseLiquidityEvent>(&mut slice)?);
+                let event = decode_event::<DecreaseLiquidityEvent>(&mut slice)?;
+                println!("{:#?}", event);
+                print_ui_amounts(ui_amount_context, |ctx| {
+                    let pool_id = get_position_pool_id(ctx, &event.position_nft_mint)?;
+                    print_pool_ui_amounts(
+                        ctx,
+                        &pool_id,
+                        &[
+                            (
+                                "decrease_amount",
+                                event.decrease_amount_0,
+                                event.decrease_amount_1,
+                            ),
+                            ("fee_amount", event.fee_amount_0, event.fee_amount_1),
+                        ],
+                        None,
+                    )
+                });
             }
             IncreaseLiquidityEvent::DISCRIMINATOR => {
-                println!("{:#?}", decode_event::<IncreaseLiquidityEvent>(&mut slice)?);
+                let event = decode_event::<IncreaseLiquidityEvent>(&mut slice)?;
+                println!("{:#?}", event);
+                print_ui_amounts(ui_amount_context, |ctx| {
+                    let pool_id = get_position_pool_id(ctx, &event.position_nft_mint)?;
+                    print_pool_ui_amounts(
+                        ctx,
+                        &pool_id,
+                        &[("amount", event.amount_0, event.amount_1)],
+                        None,
+                    )
+                });
+            }
+            RepositionEvent::DISCRIMINATOR => {
+                let event = decode_event::<RepositionEvent>(&mut slice)?;
+                println!("{:#?}", event);
+                print_ui_amounts(ui_amount_context, |ctx| {
+                    print_pool_ui_amounts(
+                        ctx,
+                        &event.pool_state,
+                        &[
+                            (
+                                "decrease_amount",
+                                event.decrease_amount_0,
+                                event.decrease_amount_1,
+                            ),
+                            ("fee_amount", event.fee_amount_0, event.fee_amount_1),
+                            (
+                                "deposit_amount",
+                                event.deposit_amount_0,
+                                event.deposit_amount_1,
+                            ),
+                        ],
+                        None,
+                    )
+                });
             }
             LiquidityCalculateEvent::DISCRIMINATOR => {
                 println!(
@@ -180,11 +303,94 @@ pub fn handle_program_log(
             //     println!("{:#?}", decode_event::<PriceChangeEvent>(&mut slice)?);
             // }
             SwapEvent::DISCRIMINATOR => {
-                println!("{:#?}", decode_event::<SwapEvent>(&mut slice)?);
+                let event = decode_event::<SwapEvent>(&mut slice)?;
+                println!("{:#?}", event);
+                print_ui_amounts(ui_amount_context, |ctx| {
+                    print_pool_ui_amounts(
+                        ctx,
+                        &event.pool_state,
+                        &[("amount", event.amount_0, event.amount_1)],
+                        Some(event.sqrt_price_x64),
+                    )
+                });
+            }
+            SwapRouterEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<SwapRouterEvent>(&mut slice)?);
+            }
+            FlashEvent::DISCRIMINATOR => {
+                let event = decode_event::<FlashEvent>(&mut slice)?;
+                println!("{:#?}", event);
+                print_ui_amounts(ui_amount_context, |ctx| {
+                    print_pool_ui_amounts(
+                        ctx,
+                        &event.pool_state,
+                        &[
+                            ("amount", event.amount_0, event.amount_1),
+                            ("paid", event.paid_0, event.paid_1),
+                        ],
+                        None,
+                    )
+                });
             }
             PoolCreatedEvent::DISCRIMINATOR => {
                 println!("{:#?}", decode_event::<PoolCreatedEvent>(&mut slice)?);
             }
+            OpenLimitOrderEvent::DISCRIMINATOR => {
+                let event = decode_event::<OpenLimitOrderEvent>(&mut slice)?;
+                println!("{:#?}", event);
+                print_ui_amounts(ui_amount_context, |ctx| {
+                    let (amount_0, amount_1) = if event.zero_for_one {
+                        (event.amount_in, 0)
+                    } else {
+                        (0, event.amount_in)
+                    };
+                    print_pool_ui_amounts(
+                        ctx,
+                        &event.pool_state,
+                        &[("amount_in", amount_0, amount_1)],
+                        None,
+                    )
+                });
+            }
+            CloseLimitOrderEvent::DISCRIMINATOR => {
+                let event = decode_event::<CloseLimitOrderEvent>(&mut slice)?;
+                println!("{:#?}", event);
+                print_ui_amounts(ui_amount_context, |ctx| {
+                    print_pool_ui_amounts(
+                        ctx,
+                        &event.pool_state,
+                        &[
+                            ("amount", event.amount_0, event.amount_1),
+                            ("fee_amount", event.fee_amount_0, event.fee_amount_1),
+                        ],
+                        None,
+                    )
+                });
+            }
+            UpdateOffchainRewardMerkleRootEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<UpdateOffchainRewardMerkleRootEvent>(&mut slice)?
+                );
+            }
+            DepositOffchainRewardEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<DepositOffchainRewardEvent>(&mut slice)?
+                );
+            }
+            WithdrawOffchainRewardEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<WithdrawOffchainRewardEvent>(&mut slice)?
+                );
+            }
+            ClaimOffchainRewardEvent::DISCRIMINATOR => {
+                println!(
+                    "{:#?}",
+                    decode_event::<ClaimOffchainRewardEvent>(&mut slice)?
+                );
+            }
             _ => {
                 println!("unknow event: {}", l);
             }
@@ -211,6 +417,81 @@ fn handle_system_log(this_program_str: &str, log: &str) -> (Option<String>, bool
     }
 }
 
+fn print_ui_amounts(
+    ui_amount_context: Option<&UiAmountContext>,
+    print: impl FnOnce(&UiAmountContext) -> Result<()>,
+) {
+    if let Some(ui_amount_context) = ui_amount_context {
+        if let Err(e) = print(ui_amount_context) {
+            println!("Unable to convert ui amounts: {e}");
+        }
+    }
+}
+
+/// The pool of the position, the position must not be closed yet
+fn get_position_pool_id(ui_amount_context: &UiAmountContext, nft_mint: &Pubkey) -> Result<Pubkey> {
+    let (personal_position_key, _) = Pubkey::find_program_address(
+        &[POSITION_SEED.as_bytes(), nft_mint.as_ref()],
+        &ui_amount_context.program_id,
+    );
+    let personal_position = deserialize_anchor_account::<PersonalPositionState>(
+        &ui_amount_context
+            .rpc_client
+            .get_account(&personal_position_key)?,
+    )?;
+    Ok(personal_position.pool_id)
+}
+
+/// Prints the ui amounts of the pool token amounts of an event, each entry is the name with the amounts
+/// of token_0 and token_1, and the ui price if the event has the price of the pool
+fn print_pool_ui_amounts(
+    ui_amount_context: &UiAmountContext,
+    pool_id: &Pubkey,
+    amounts: &[(&str, u64, u64)],
+    sqrt_price_x64: Option<u128>,
+) -> Result<()> {
+    let rpc_client = ui_amount_context.rpc_client;
+    let pool_state = deserialize_anchor_account::<PoolState>(&rpc_client.get_account(pool_id)?)?;
+    let ui_amount_multiplier_0 = get_ui_amount_multiplier(
+        &rpc_client.get_account(&pool_state.token_mint_0)?,
+        ui_amount_context.unix_timestamp,
+    )?;
+    let ui_amount_multiplier_1 = get_ui_amount_multiplier(
+        &rpc_client.get_account(&pool_state.token_mint_1)?,
+        ui_amount_context.unix_timestamp,
+    )?;
+    for (name, amount_0, amount_1) in amounts {
+        println!(
+            "ui_{}_0:{}, ui_{}_1:{}",
+            name,
+            amount_to_ui_amount(
+                *amount_0,
+                pool_state.mint_decimals_0,
+                ui_amount_multiplier_0
+            ),
+            name,
+            amount_to_ui_amount(
+                *amount_1,
+                pool_state.mint_decimals_1,
+                ui_amount_multiplier_1
+            ),
+        );
+    }
+    if let Some(sqrt_price_x64) = sqrt_price_x64 {
+        println!(
+            "ui_price:{}",
+            sqrt_price_x64_to_ui_price(
+                sqrt_price_x64,
+                pool_state.mint_decimals_0,
+                pool_state.mint_decimals_1,
+                ui_amount_multiplier_0,
+                ui_amount_multiplier_1,
+            )
+        );
+    }
+    Ok(())
+}
+
 fn decode_event<T: anchor_lang::Event + anchor_lang::AnchorDeserialize>(
     slice: &mut &[u8],
 ) -> Result<T, ClientError> {
@@ -409,6 +690,26 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", CreatePool::from(ix));
         }
+        instruction::CreatePoolWithLaunchFee::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::CreatePoolWithLaunchFee>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct CreatePoolWithLaunchFee {
+                pub sqrt_price_x64: u128,
+                pub open_time: u64,
+                pub launch_fee_param: LaunchFeeParam,
+            }
+            impl From<instruction::CreatePoolWithLaunchFee> for CreatePoolWithLaunchFee {
+                fn from(instr: instruction::CreatePoolWithLaunchFee) -> CreatePoolWithLaunchFee {
+                    CreatePoolWithLaunchFee {
+                        sqrt_price_x64: instr.sqrt_price_x64,
+                        open_time: instr.open_time,
+                        launch_fee_param: instr.launch_fee_param,
+                    }
+                }
+            }
+            println!("{:#?}", CreatePoolWithLaunchFee::from(ix));
+        }
         instruction::UpdatePoolStatus::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::UpdatePoolStatus>(&mut ix_data).unwrap();
             #[derive(Debug)]
@@ -454,6 +755,85 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", UpdateOperationAccount::from(ix));
         }
+        instruction::CreatePoolPermission::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::CreatePoolPermission>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct CreatePoolPermission {
+                pub gating_mint: Pubkey,
+            }
+            impl From<instruction::CreatePoolPermission> for CreatePoolPermission {
+                fn from(instr: instruction::CreatePoolPermission) -> CreatePoolPermission {
+                    CreatePoolPermission {
+                        gating_mint: instr.gating_mint,
+                    }
+                }
+            }
+            println!("{:#?}", CreatePoolPermission::from(ix));
+        }
+        instruction::UpdatePoolPermission::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::UpdatePoolPermission>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct UpdatePoolPermission {
+                pub param: u8,
+                pub keys: Vec<Pubkey>,
+            }
+            impl From<instruction::UpdatePoolPermission> for UpdatePoolPermission {
+                fn from(instr: instruction::UpdatePoolPermission) -> UpdatePoolPermission {
+                    UpdatePoolPermission {
+                        param: instr.param,
+                        keys: instr.keys,
+                    }
+                }
+            }
+            println!("{:#?}", UpdatePoolPermission::from(ix));
+        }
+        instruction::DepositOffchainReward::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::DepositOffchainReward>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct DepositOffchainReward {
+                pub amount: u64,
+            }
+            impl From<instruction::DepositOffchainReward> for DepositOffchainReward {
+                fn from(instr: instruction::DepositOffchainReward) -> DepositOffchainReward {
+                    DepositOffchainReward {
+                        amount: instr.amount,
+                    }
+                }
+            }
+            println!("{:#?}", DepositOffchainReward::from(ix));
+        }
+        instruction::ClaimOffchainReward::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::ClaimOffchainReward>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct ClaimOffchainReward {
+                pub amount: u64,
+            }
+            impl From<instruction::ClaimOffchainReward> for ClaimOffchainReward {
+                fn from(instr: instruction::ClaimOffchainReward) -> ClaimOffchainReward {
+                    ClaimOffchainReward {
+                        amount: instr.amount,
+                    }
+                }
+            }
+            println!("{:#?}", ClaimOffchainReward::from(ix));
+        }
+        instruction::WithdrawOffchainReward::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::WithdrawOffchainReward>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct WithdrawOffchainReward {
+                pub amount: u64,
+            }
+            impl From<instruction::WithdrawOffchainReward> for WithdrawOffchainReward {
+                fn from(instr: instruction::WithdrawOffchainReward) -> WithdrawOffchainReward {
+                    WithdrawOffchainReward {
+                        amount: instr.amount,
+                    }
+                }
+            }
+            println!("{:#?}", WithdrawOffchainReward::from(ix));
+        }
         instruction::TransferRewardOwner::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::TransferRewardOwner>(&mut ix_data).unwrap();
             #[derive(Debug)]
@@ -482,6 +862,26 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", InitializeReward::from(ix));
         }
+        instruction::InitializeRewardWithSchedule::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::InitializeRewardWithSchedule>(&mut ix_data)
+                .unwrap();
+            #[derive(Debug)]
+            pub struct InitializeRewardWithSchedule {
+                pub param: InitializeRewardParam,
+                pub phases: Vec<RewardSchedulePhase>,
+            }
+            impl From<instruction::InitializeRewardWithSchedule> for InitializeRewardWithSchedule {
+                fn from(
+                    instr: instruction::InitializeRewardWithSchedule,
+                ) -> InitializeRewardWithSchedule {
+                    InitializeRewardWithSchedule {
+                        param: instr.param,
+                        phases: instr.phases,
+                    }
+                }
+            }
+            println!("{:#?}", InitializeRewardWithSchedule::from(ix));
+        }
         instruction::CollectRemainingRewards::DISCRIMINATOR => {
             let ix =
                 decode_instruction::<instruction::CollectRemainingRewards>(&mut ix_data).unwrap();
@@ -498,6 +898,57 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", CollectRemainingRewards::from(ix));
         }
+        instruction::RecycleReward::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::RecycleReward>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct RecycleReward {
+                pub reward_index: u8,
+            }
+            impl From<instruction::RecycleReward> for RecycleReward {
+                fn from(instr: instruction::RecycleReward) -> RecycleReward {
+                    RecycleReward {
+                        reward_index: instr.reward_index,
+                    }
+                }
+            }
+            println!("{:#?}", RecycleReward::from(ix));
+        }
+        instruction::AddRewardFunds::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::AddRewardFunds>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct AddRewardFunds {
+                pub reward_index: u8,
+                pub amount: u64,
+            }
+            impl From<instruction::AddRewardFunds> for AddRewardFunds {
+                fn from(instr: instruction::AddRewardFunds) -> AddRewardFunds {
+                    AddRewardFunds {
+                        reward_index: instr.reward_index,
+                        amount: instr.amount,
+                    }
+                }
+            }
+            println!("{:#?}", AddRewardFunds::from(ix));
+        }
+        instruction::IncreaseObservationCardinality::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::IncreaseObservationCardinality>(&mut ix_data)
+                    .unwrap();
+            #[derive(Debug)]
+            pub struct IncreaseObservationCardinality {
+                pub observation_cardinality_next: u16,
+            }
+            impl From<instruction::IncreaseObservationCardinality> for IncreaseObservationCardinality {
+                fn from(
+                    instr: instruction::IncreaseObservationCardinality,
+                ) -> IncreaseObservationCardinality {
+                    IncreaseObservationCardinality {
+                        observation_cardinality_next: instr.observation_cardinality_next,
+                    }
+                }
+            }
+            println!("{:#?}", IncreaseObservationCardinality::from(ix));
+        }
         instruction::UpdateRewardInfos::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::UpdateRewardInfos>(&mut ix_data).unwrap();
             #[derive(Debug)]
@@ -622,6 +1073,39 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", OpenPositionV2::from(ix));
         }
+        instruction::OpenPositionV3::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::OpenPositionV3>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct OpenPositionV3 {
+                pub tick_lower_index: i32,
+                pub tick_upper_index: i32,
+                pub tick_array_lower_start_index: i32,
+                pub tick_array_upper_start_index: i32,
+                pub liquidity: u128,
+                pub amount_0_max: u64,
+                pub amount_1_max: u64,
+                pub base_flag: Option<bool>,
+                pub with_metadata: bool,
+                pub deadline: i64,
+            }
+            impl From<instruction::OpenPositionV3> for OpenPositionV3 {
+                fn from(instr: instruction::OpenPositionV3) -> OpenPositionV3 {
+                    OpenPositionV3 {
+                        tick_lower_index: instr.tick_lower_index,
+                        tick_upper_index: instr.tick_upper_index,
+                        tick_array_lower_start_index: instr.tick_array_lower_start_index,
+                        tick_array_upper_start_index: instr.tick_array_upper_start_index,
+                        liquidity: instr.liquidity,
+                        amount_0_max: instr.amount_0_max,
+                        amount_1_max: instr.amount_1_max,
+                        base_flag: instr.base_flag,
+                        with_metadata: instr.with_metadata,
+                        deadline: instr.deadline,
+                    }
+                }
+            }
+            println!("{:#?}", OpenPositionV3::from(ix));
+        }
         instruction::ClosePosition::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::ClosePosition>(&mut ix_data).unwrap();
             #[derive(Debug)]
@@ -673,6 +1157,29 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", IncreaseLiquidityV2::from(ix));
         }
+        instruction::IncreaseLiquidityV3::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::IncreaseLiquidityV3>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct IncreaseLiquidityV3 {
+                pub liquidity: u128,
+                pub amount_0_max: u64,
+                pub amount_1_max: u64,
+                pub base_flag: Option<bool>,
+                pub deadline: i64,
+            }
+            impl From<instruction::IncreaseLiquidityV3> for IncreaseLiquidityV3 {
+                fn from(instr: instruction::IncreaseLiquidityV3) -> IncreaseLiquidityV3 {
+                    IncreaseLiquidityV3 {
+                        liquidity: instr.liquidity,
+                        amount_0_max: instr.amount_0_max,
+                        amount_1_max: instr.amount_1_max,
+                        base_flag: instr.base_flag,
+                        deadline: instr.deadline,
+                    }
+                }
+            }
+            println!("{:#?}", IncreaseLiquidityV3::from(ix));
+        }
         instruction::DecreaseLiquidity::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::DecreaseLiquidity>(&mut ix_data).unwrap();
             #[derive(Debug)]
@@ -711,6 +1218,27 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", DecreaseLiquidityV2::from(ix));
         }
+        instruction::DecreaseLiquidityV3::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::DecreaseLiquidityV3>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct DecreaseLiquidityV3 {
+                pub liquidity: u128,
+                pub amount_0_min: u64,
+                pub amount_1_min: u64,
+                pub deadline: i64,
+            }
+            impl From<instruction::DecreaseLiquidityV3> for DecreaseLiquidityV3 {
+                fn from(instr: instruction::DecreaseLiquidityV3) -> DecreaseLiquidityV3 {
+                    DecreaseLiquidityV3 {
+                        liquidity: instr.liquidity,
+                        amount_0_min: instr.amount_0_min,
+                        amount_1_min: instr.amount_1_min,
+                        deadline: instr.deadline,
+                    }
+                }
+            }
+            println!("{:#?}", DecreaseLiquidityV3::from(ix));
+        }
         instruction::Swap::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::Swap>(&mut ix_data).unwrap();
             #[derive(Debug)]
@@ -753,23 +1281,71 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", SwapV2::from(ix));
         }
+        instruction::SwapV3::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::SwapV3>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct SwapV3 {
+                pub amount: u64,
+                pub other_amount_threshold: u64,
+                pub sqrt_price_limit_x64: u128,
+                pub is_base_input: bool,
+                pub deadline: i64,
+            }
+            impl From<instruction::SwapV3> for SwapV3 {
+                fn from(instr: instruction::SwapV3) -> SwapV3 {
+                    SwapV3 {
+                        amount: instr.amount,
+                        other_amount_threshold: instr.other_amount_threshold,
+                        sqrt_price_limit_x64: instr.sqrt_price_limit_x64,
+                        is_base_input: instr.is_base_input,
+                        deadline: instr.deadline,
+                    }
+                }
+            }
+            println!("{:#?}", SwapV3::from(ix));
+        }
         instruction::SwapRouterBaseIn::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::SwapRouterBaseIn>(&mut ix_data).unwrap();
             #[derive(Debug)]
             pub struct SwapRouterBaseIn {
                 pub amount_in: u64,
                 pub amount_out_minimum: u64,
+                pub deadline: Option<i64>,
+                pub hop_account_counts: Vec<u8>,
             }
             impl From<instruction::SwapRouterBaseIn> for SwapRouterBaseIn {
                 fn from(instr: instruction::SwapRouterBaseIn) -> SwapRouterBaseIn {
                     SwapRouterBaseIn {
                         amount_in: instr.amount_in,
                         amount_out_minimum: instr.amount_out_minimum,
+                        deadline: instr.deadline,
+                        hop_account_counts: instr.hop_account_counts,
                     }
                 }
             }
             println!("{:#?}", SwapRouterBaseIn::from(ix));
         }
+        instruction::SwapRouterBaseOut::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::SwapRouterBaseOut>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct SwapRouterBaseOut {
+                pub amount_out: u64,
+                pub amount_in_maximum: u64,
+                pub deadline: Option<i64>,
+                pub hop_account_counts: Vec<u8>,
+            }
+            impl From<instruction::SwapRouterBaseOut> for SwapRouterBaseOut {
+                fn from(instr: instruction::SwapRouterBaseOut) -> SwapRouterBaseOut {
+                    SwapRouterBaseOut {
+                        amount_out: instr.amount_out,
+                        amount_in_maximum: instr.amount_in_maximum,
+                        deadline: instr.deadline,
+                        hop_account_counts: instr.hop_account_counts,
+                    }
+                }
+            }
+            println!("{:#?}", SwapRouterBaseOut::from(ix));
+        }
         _ => {
             println!("unknow instruction: {}", instr_data);
         }
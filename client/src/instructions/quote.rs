@@ -0,0 +1,434 @@
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, Result};
+use raydium_amm_v3::error::ErrorCode;
+use raydium_amm_v3::instructions::swap_internal;
+use raydium_amm_v3::libraries::{check_current_tick_array_is_initialized, tick_math, U1024};
+use raydium_amm_v3::states::*;
+use solana_client::rpc_client::RpcClient;
+use spl_token_2022::extension::{
+    transfer_fee::{TransferFeeConfig, MAX_FEE_BASIS_POINTS},
+    BaseStateWithExtensions, StateWithExtensions,
+};
+use spl_token_2022::state::Mint;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
+
+use super::utils::deserialize_anchor_account;
+
+/// The max number of accounts fetched by one `get_multiple_accounts` request
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// Snapshots of the accounts read by a swap, quotes are calculated by the on-chain
+/// `swap_internal` against copies of them, so they match the swap results exactly
+/// as long as the snapshots are not stale.
+#[derive(Clone)]
+pub struct PoolSnapshot {
+    pub program_id: Pubkey,
+    pub pool_id: Pubkey,
+    pub amm_config: AmmConfig,
+    pub pool_state: PoolState,
+    pub tickarray_bitmap_extension: Option<TickArrayBitmapExtension>,
+    /// The initialized tick arrays of the pool by start index
+    pub tick_arrays: BTreeMap<i32, TickArrayState>,
+    /// The transfer fee config of token_0 and token_1 if it is a token 2022 mint with the extension
+    pub transfer_fee_config_0: Option<TransferFeeConfig>,
+    pub transfer_fee_config_1: Option<TransferFeeConfig>,
+    /// The epoch to calculate the transfer fee
+    pub epoch: u64,
+}
+
+/// The result of a swap calculated against a `PoolSnapshot`
+#[derive(Clone, Debug, Default)]
+pub struct SwapQuote {
+    pub zero_for_one: bool,
+    /// The amount paid by the user, include the trade fee and the transfer fee of the input token
+    pub amount_in: u64,
+    /// The amount received by the user, the transfer fee of the output token is deducted
+    pub amount_out: u64,
+    /// The trade fee paid in the input token, include the protocol fee and the fund fee
+    pub fee_amount: u64,
+    /// The trade fee rate at the start of the swap, the rate of every step grows with the volatility
+    /// of the price moved by the swap in the dynamic fee mode
+    pub trade_fee_rate: u32,
+    /// The transfer fee of the input and output token charged by the token 2022 extension
+    pub transfer_fee_in: u64,
+    pub transfer_fee_out: u64,
+    pub sqrt_price_x64_before: u128,
+    pub sqrt_price_x64_after: u128,
+    pub tick_after: i32,
+    /// The relative change of the pool price made by the swap
+    pub price_impact: f64,
+    /// The start indexes of the tick arrays crossed by the swap, in swap order
+    pub tick_array_start_indexes: Vec<i32>,
+    /// The remaining accounts of the swap instruction: the tickarray_bitmap_extension
+    /// if it is required by the swap, followed by the tick arrays in swap order
+    pub remaining_accounts: Vec<Pubkey>,
+}
+
+impl PoolSnapshot {
+    /// Fetches the pool, its amm config, tickarray_bitmap_extension, mints and all initialized tick arrays
+    pub fn load(rpc_client: &RpcClient, program_id: &Pubkey, pool_id: Pubkey) -> Result<Self> {
+        let pool_account = rpc_client.get_account(&pool_id)?;
+        let pool_state = deserialize_anchor_account::<PoolState>(&pool_account)?;
+        let (tickarray_bitmap_extension_key, _) = Pubkey::find_program_address(
+            &[
+                POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+                pool_id.to_bytes().as_ref(),
+            ],
+            program_id,
+        );
+        let rsps = rpc_client.get_multiple_accounts(&[
+            pool_state.amm_config,
+            tickarray_bitmap_extension_key,
+            pool_state.token_mint_0,
+            pool_state.token_mint_1,
+        ])?;
+        let amm_config = deserialize_anchor_account::<AmmConfig>(
+            rsps[0].as_ref().ok_or(anyhow!("load amm config error"))?,
+        )?;
+        let tickarray_bitmap_extension = rsps[1]
+            .as_ref()
+            .map(deserialize_anchor_account::<TickArrayBitmapExtension>)
+            .transpose()?;
+        let transfer_fee_configs = rsps[2..4]
+            .iter()
+            .map(|account| {
+                let account = account.as_ref().ok_or(anyhow!("load mint error"))?;
+                let mint = StateWithExtensions::<Mint>::unpack(&account.data)?;
+                Ok(mint.get_extension::<TransferFeeConfig>().ok().copied())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut snapshot = PoolSnapshot {
+            program_id: *program_id,
+            pool_id,
+            amm_config,
+            pool_state,
+            tickarray_bitmap_extension,
+            tick_arrays: BTreeMap::new(),
+            transfer_fee_config_0: transfer_fee_configs[0],
+            transfer_fee_config_1: transfer_fee_configs[1],
+            epoch: rpc_client.get_epoch_info()?.epoch,
+        };
+        let tick_array_keys = snapshot
+            .initialized_tick_array_start_indexes()?
+            .into_iter()
+            .map(|start_index| {
+                Pubkey::find_program_address(
+                    &[
+                        TICK_ARRAY_SEED.as_bytes(),
+                        pool_id.to_bytes().as_ref(),
+                        &start_index.to_be_bytes(),
+                    ],
+                    program_id,
+                )
+                .0
+            })
+            .collect::<Vec<_>>();
+        for keys in tick_array_keys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            for account in rpc_client.get_multiple_accounts(keys)?.iter().flatten() {
+                let tick_array = deserialize_anchor_account::<TickArrayState>(account)?;
+                snapshot
+                    .tick_arrays
+                    .insert(tick_array.start_tick_index, tick_array);
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// The start indexes of all initialized tick arrays of the pool, from the pool bitmap
+    /// and the tickarray_bitmap_extension
+    pub fn initialized_tick_array_start_indexes(&self) -> Result<Vec<i32>> {
+        let tick_array_start_index = TickArrayState::get_array_start_index(
+            self.pool_state.tick_current,
+            self.pool_state.tick_spacing,
+        );
+        let mut start_indexes = Vec::new();
+        if self.is_tick_array_initialized(tick_array_start_index)? {
+            start_indexes.push(tick_array_start_index);
+        }
+        for zero_for_one in [true, false] {
+            let mut last_start_index = tick_array_start_index;
+            while let Some(start_index) = self
+                .pool_state
+                .next_initialized_tick_array_start_index(
+                    &self.tickarray_bitmap_extension,
+                    last_start_index,
+                    zero_for_one,
+                )
+                .map_err(|e| anyhow!("{}", e))?
+            {
+                start_indexes.push(start_index);
+                last_start_index = start_index;
+            }
+        }
+        start_indexes.sort();
+        Ok(start_indexes)
+    }
+
+    fn is_tick_array_initialized(&self, start_index: i32) -> Result<bool> {
+        let pool_state = &self.pool_state;
+        let (is_initialized, _) =
+            if pool_state.is_overflow_default_tickarray_bitmap(vec![start_index]) {
+                match self.tickarray_bitmap_extension {
+                    Some(extension) => extension
+                        .check_tick_array_is_initialized(start_index, pool_state.tick_spacing)
+                        .map_err(|e| anyhow!("{}", e))?,
+                    None => (false, start_index),
+                }
+            } else {
+                check_current_tick_array_is_initialized(
+                    U1024(pool_state.tick_array_bitmap),
+                    start_index,
+                    pool_state.tick_spacing.into(),
+                )
+                .map_err(|e| anyhow!("{}", e))?
+            };
+        Ok(is_initialized)
+    }
+
+    /// Quotes a swap like `swap_v2`, the amount includes the transfer fee as input and
+    /// excludes the transfer fee as output.
+    ///
+    /// * `amount` - The exact input amount if `is_base_input`, otherwise the exact output amount
+    /// * `sqrt_price_limit_x64` - The price limit of the swap, no limit if `None`
+    /// * `block_timestamp` - The timestamp the swap is expected to be executed at
+    pub fn quote(
+        &self,
+        amount: u64,
+        sqrt_price_limit_x64: Option<u128>,
+        zero_for_one: bool,
+        is_base_input: bool,
+        block_timestamp: u64,
+    ) -> Result<SwapQuote> {
+        let (transfer_fee_config_in, transfer_fee_config_out) = if zero_for_one {
+            (&self.transfer_fee_config_0, &self.transfer_fee_config_1)
+        } else {
+            (&self.transfer_fee_config_1, &self.transfer_fee_config_0)
+        };
+        // same as swap_compute_v2, the specified amount includes the transfer fee as input
+        // and excludes the transfer fee as output
+        let amount_calculate_specified = if is_base_input {
+            amount - get_transfer_fee(transfer_fee_config_in, self.epoch, amount)?
+        } else {
+            amount + get_transfer_inverse_fee(transfer_fee_config_out, self.epoch, amount)?
+        };
+        let sqrt_price_limit_x64 = sqrt_price_limit_x64.unwrap_or(if zero_for_one {
+            tick_math::MIN_SQRT_PRICE_X64 + 1
+        } else {
+            tick_math::MAX_SQRT_PRICE_X64 - 1
+        });
+
+        // the tickarray_bitmap_extension is only required if the swap searches out of the default bitmap
+        let need_extension = self
+            .pool_state
+            .is_overflow_default_tickarray_bitmap(vec![self.pool_state.tick_current]);
+        let mut result = self.swap(
+            need_extension,
+            amount_calculate_specified,
+            sqrt_price_limit_x64,
+            zero_for_one,
+            is_base_input,
+            block_timestamp,
+        );
+        let mut need_extension = need_extension;
+        if let Err(ref e) = result {
+            if !need_extension
+                && *e == ErrorCode::MissingTickArrayBitmapExtensionAccount.into()
+            {
+                need_extension = true;
+                result = self.swap(
+                    need_extension,
+                    amount_calculate_specified,
+                    sqrt_price_limit_x64,
+                    zero_for_one,
+                    is_base_input,
+                    block_timestamp,
+                );
+            }
+        }
+        let (pool_state_after, amount_0, amount_1, tick_array_start_indexes) =
+            result.map_err(|e| anyhow!("{}", e))?;
+
+        let (pool_amount_in, pool_amount_out) = if zero_for_one {
+            (amount_0, amount_1)
+        } else {
+            (amount_1, amount_0)
+        };
+        // the transfer fee is calculated again for the amounts actually swapped, same as swap_compute_v2
+        let transfer_fee_in = if is_base_input && pool_amount_in == amount_calculate_specified {
+            amount - amount_calculate_specified
+        } else {
+            get_transfer_inverse_fee(transfer_fee_config_in, self.epoch, pool_amount_in)?
+        };
+        let transfer_fee_out = get_transfer_fee(transfer_fee_config_out, self.epoch, pool_amount_out)?;
+
+        let pool_state = &self.pool_state;
+        let fee_amount = if zero_for_one {
+            pool_state_after.total_fees_token_0 - pool_state.total_fees_token_0
+                + pool_state_after.protocol_fees_token_0
+                - pool_state.protocol_fees_token_0
+                + pool_state_after.fund_fees_token_0
+                - pool_state.fund_fees_token_0
+        } else {
+            pool_state_after.total_fees_token_1 - pool_state.total_fees_token_1
+                + pool_state_after.protocol_fees_token_1
+                - pool_state.protocol_fees_token_1
+                + pool_state_after.fund_fees_token_1
+                - pool_state.fund_fees_token_1
+        };
+        let sqrt_price_x64_before = pool_state.sqrt_price_x64;
+        let sqrt_price_x64_after = pool_state_after.sqrt_price_x64;
+        let price_before = (sqrt_price_x64_before as f64).powi(2);
+        let price_after = (sqrt_price_x64_after as f64).powi(2);
+
+        let mut remaining_accounts = Vec::new();
+        if need_extension {
+            remaining_accounts.push(
+                Pubkey::find_program_address(
+                    &[
+                        POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+                        self.pool_id.to_bytes().as_ref(),
+                    ],
+                    &self.program_id,
+                )
+                .0,
+            );
+        }
+        remaining_accounts.extend(tick_array_start_indexes.iter().map(|start_index| {
+            Pubkey::find_program_address(
+                &[
+                    TICK_ARRAY_SEED.as_bytes(),
+                    self.pool_id.to_bytes().as_ref(),
+                    &start_index.to_be_bytes(),
+                ],
+                &self.program_id,
+            )
+            .0
+        }));
+
+        Ok(SwapQuote {
+            zero_for_one,
+            amount_in: pool_amount_in + transfer_fee_in,
+            amount_out: pool_amount_out - transfer_fee_out,
+            fee_amount,
+            trade_fee_rate: pool_state
+                .get_trade_fee_rate(self.amm_config.trade_fee_rate, block_timestamp),
+            transfer_fee_in,
+            transfer_fee_out,
+            sqrt_price_x64_before,
+            sqrt_price_x64_after,
+            tick_after: pool_state_after.tick_current,
+            price_impact: ((price_after - price_before) / price_before).abs(),
+            tick_array_start_indexes,
+            remaining_accounts,
+        })
+    }
+
+    /// Runs `swap_internal` against copies of the snapshots, returns the pool state after the swap,
+    /// the swapped amount_0 and amount_1 and the start indexes of the tick arrays used.
+    fn swap(
+        &self,
+        with_extension: bool,
+        amount_specified: u64,
+        sqrt_price_limit_x64: u128,
+        zero_for_one: bool,
+        is_base_input: bool,
+        block_timestamp: u64,
+    ) -> anchor_lang::Result<(PoolState, u64, u64, Vec<i32>)> {
+        let tickarray_bitmap_extension = if with_extension {
+            self.tickarray_bitmap_extension
+        } else {
+            None
+        };
+        // the tick arrays in swap order, starting from the first initialized tick array
+        let (_, first_start_index) = self
+            .pool_state
+            .get_first_initialized_tick_array(&tickarray_bitmap_extension, zero_for_one)?;
+        let mut start_indexes = Vec::new();
+        let mut start_index = Some(first_start_index);
+        while let Some(index) = start_index {
+            if !self.tick_arrays.contains_key(&index) {
+                break;
+            }
+            start_indexes.push(index);
+            start_index = self.pool_state.next_initialized_tick_array_start_index(
+                &tickarray_bitmap_extension,
+                index,
+                zero_for_one,
+            )?;
+        }
+        let tick_array_cells = start_indexes
+            .iter()
+            .map(|index| RefCell::new(self.tick_arrays[index]))
+            .collect::<Vec<_>>();
+        let mut tick_array_states = tick_array_cells
+            .iter()
+            .map(|cell| cell.borrow_mut())
+            .collect::<VecDeque<_>>();
+
+        let pool_state_cell = RefCell::new(self.pool_state);
+        let observation_state_cell = RefCell::new(ObservationState {
+            pool_id: self.pool_id,
+            ..Default::default()
+        });
+        let (amount_0, amount_1) = swap_internal(
+            &self.amm_config,
+            &mut pool_state_cell.borrow_mut(),
+            &mut tick_array_states,
+            &mut observation_state_cell.borrow_mut(),
+            &tickarray_bitmap_extension,
+            amount_specified,
+            sqrt_price_limit_x64,
+            zero_for_one,
+            is_base_input,
+            block_timestamp as u32,
+        )?;
+        // the tick arrays left in the queue are not reached by the swap
+        let used = start_indexes.len() - tick_array_states.len();
+        drop(tick_array_states);
+        start_indexes.truncate(used);
+        let pool_state_after = *pool_state_cell.borrow();
+        Ok((pool_state_after, amount_0, amount_1, start_indexes))
+    }
+}
+
+/// Calculate the transfer fee for input amount, same as `util::get_transfer_fee` on-chain
+fn get_transfer_fee(
+    transfer_fee_config: &Option<TransferFeeConfig>,
+    epoch: u64,
+    pre_fee_amount: u64,
+) -> Result<u64> {
+    match transfer_fee_config {
+        Some(transfer_fee_config) => transfer_fee_config
+            .calculate_epoch_fee(epoch, pre_fee_amount)
+            .ok_or(anyhow!("transfer fee calculate error")),
+        None => Ok(0),
+    }
+}
+
+/// Calculate the transfer fee for output amount, same as `util::get_transfer_inverse_fee` on-chain
+fn get_transfer_inverse_fee(
+    transfer_fee_config: &Option<TransferFeeConfig>,
+    epoch: u64,
+    post_fee_amount: u64,
+) -> Result<u64> {
+    let transfer_fee_config = match transfer_fee_config {
+        Some(transfer_fee_config) => transfer_fee_config,
+        None => return Ok(0),
+    };
+    let transfer_fee = transfer_fee_config.get_epoch_fee(epoch);
+    if u16::from(transfer_fee.transfer_fee_basis_points) == MAX_FEE_BASIS_POINTS {
+        return Ok(u64::from(transfer_fee.maximum_fee));
+    }
+    let fee = transfer_fee_config
+        .calculate_inverse_epoch_fee(epoch, post_fee_amount)
+        .ok_or(anyhow!("transfer fee calculate error"))?;
+    let fee_for_check = transfer_fee_config
+        .calculate_epoch_fee(epoch, post_fee_amount + fee)
+        .ok_or(anyhow!("transfer fee calculate error"))?;
+    if fee != fee_for_check {
+        return Err(anyhow!("transfer fee calculate not match"));
+    }
+    Ok(fee)
+}
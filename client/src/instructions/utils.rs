@@ -5,7 +5,7 @@ use raydium_amm_v3::libraries::*;
 use raydium_amm_v3::states::*;
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::program_pack::Pack;
-use solana_sdk::{account::Account, pubkey::Pubkey};
+use solana_sdk::{account::Account, instruction::AccountMeta, pubkey::Pubkey};
 use spl_token_2022::{
     extension::{
         confidential_transfer::{ConfidentialTransferAccount, ConfidentialTransferMint},
@@ -18,10 +18,13 @@ use spl_token_2022::{
         non_transferable::{NonTransferable, NonTransferableAccount},
         permanent_delegate::PermanentDelegate,
         transfer_fee::{TransferFeeAmount, TransferFeeConfig, MAX_FEE_BASIS_POINTS},
-        BaseState, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+        transfer_hook, BaseState, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
     },
     state::Mint,
 };
+use spl_transfer_hook_interface::{
+    error::TransferHookError, offchain::add_extra_account_metas_for_execute,
+};
 use std::collections::VecDeque;
 use std::ops::{DerefMut, Mul, Neg};
 
@@ -233,6 +236,116 @@ pub fn get_account_extensions<'data, S: BaseState + Pack>(
     extensions
 }
 
+/// Resolves the accounts required by the transfer hook program of the mint to transfer `amount` from
+/// `source` to `destination`, which must be passed in the remaining accounts of the instruction.
+/// Returns no account if the mint has no transfer hook.
+pub fn get_transfer_hook_accounts(
+    rpc_client: &RpcClient,
+    mint: &Pubkey,
+    source: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+) -> Result<Vec<AccountMeta>> {
+    let mint_account = rpc_client.get_account(mint)?;
+    if mint_account.owner != spl_token_2022::id() {
+        return Ok(Vec::new());
+    }
+    let mint_state = StateWithExtensions::<Mint>::unpack(&mint_account.data)?;
+    let Some(program_id) = transfer_hook::get_program_id(&mint_state) else {
+        return Ok(Vec::new());
+    };
+    let mut transfer_instruction = spl_token_2022::instruction::transfer_checked(
+        &spl_token_2022::id(),
+        source,
+        mint,
+        destination,
+        authority,
+        &[],
+        amount,
+        mint_state.base.decimals,
+    )?;
+    let accounts_len = transfer_instruction.accounts.len();
+    // the account data is fetched by the blocking rpc client, so the future is always ready
+    futures::executor::block_on(add_extra_account_metas_for_execute(
+        &mut transfer_instruction,
+        &program_id,
+        source,
+        mint,
+        destination,
+        authority,
+        amount,
+        |address| {
+            std::future::ready(
+                rpc_client
+                    .get_account_with_commitment(&address, rpc_client.commitment())
+                    .map(|response| response.value.map(|account| account.data))
+                    .map_err(|_| TransferHookError::IncorrectAccount.into()),
+            )
+        },
+    ))
+    .map_err(|err| anyhow::format_err!("resolve transfer hook accounts failed: {}", err))?;
+    Ok(transfer_instruction.accounts.split_off(accounts_len))
+}
+
+/// Resolves the transfer hook accounts of a transfer between a pool vault and the token account of
+/// `user`, a deposit to the vault if `deposit`, otherwise a withdrawal signed by the pool.
+pub fn get_vault_transfer_hook_accounts(
+    rpc_client: &RpcClient,
+    pool_id: &Pubkey,
+    mint: &Pubkey,
+    vault: &Pubkey,
+    user_token_account: &Pubkey,
+    user: &Pubkey,
+    amount: u64,
+    deposit: bool,
+) -> Result<Vec<AccountMeta>> {
+    if deposit {
+        get_transfer_hook_accounts(rpc_client, mint, user_token_account, vault, user, amount)
+    } else {
+        get_transfer_hook_accounts(rpc_client, mint, vault, user_token_account, pool_id, amount)
+    }
+}
+
+/// Resolves the transfer hook accounts of both pool tokens, transferred between the pool vaults and
+/// the associated token accounts of `user`, deposits to the vaults if `deposit`, otherwise withdrawals.
+pub fn get_pool_transfer_hook_accounts(
+    rpc_client: &RpcClient,
+    pool_id: &Pubkey,
+    pool: &PoolState,
+    token_programs: [Pubkey; 2],
+    user: &Pubkey,
+    amounts: [u64; 2],
+    deposit: bool,
+) -> Result<Vec<AccountMeta>> {
+    let mut accounts = Vec::new();
+    for (i, (mint, vault)) in [
+        (pool.token_mint_0, pool.token_vault_0),
+        (pool.token_mint_1, pool.token_vault_1),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let user_token_account =
+            spl_associated_token_account::get_associated_token_address_with_program_id(
+                user,
+                &mint,
+                &token_programs[i],
+            );
+        accounts.append(&mut get_vault_transfer_hook_accounts(
+            rpc_client,
+            pool_id,
+            &mint,
+            &vault,
+            &user_token_account,
+            user,
+            amounts[i],
+            deposit,
+        )?);
+    }
+    Ok(accounts)
+}
+
 pub const Q_RATIO: f64 = 1.0001;
 
 pub fn tick_to_price(tick: i32) -> f64 {
@@ -276,6 +389,139 @@ pub fn sqrt_price_x64_to_price(price: u128, decimals_0: u8, decimals_1: u8) -> f
     from_x64_price(price).powi(2) * multipler(decimals_0) / multipler(decimals_1)
 }
 
+/// The extension type of the scaled ui amount config, which is only known by spl-token-2022 8.0 and later.
+/// spl-token-2022 8.0 depends on solana 2.2, so the config is read from the tlv data of the mint until the
+/// client moves to solana 2.2 and can use `spl_token_2022::extension::scaled_ui_amount::ScaledUiAmountConfig`.
+const SCALED_UI_AMOUNT_EXTENSION_TYPE: u16 = 25;
+/// The seconds of a year used by the interest-bearing extension
+const SECONDS_PER_YEAR: f64 = 60.0 * 60.0 * 24.0 * 365.24;
+const ONE_IN_BASIS_POINTS: f64 = 10_000.0;
+
+/// The layout of `ScaledUiAmountConfig` of spl-token-2022
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScaledUiAmountConfig {
+    pub authority: Pubkey,
+    /// The multiplier before `new_multiplier_effective_timestamp`
+    pub multiplier: f64,
+    pub new_multiplier_effective_timestamp: i64,
+    /// The multiplier from `new_multiplier_effective_timestamp` on
+    pub new_multiplier: f64,
+}
+
+impl ScaledUiAmountConfig {
+    pub const LEN: usize = 32 + 8 + 8 + 8;
+
+    fn unpack(data: &[u8]) -> Option<Self> {
+        let data = data.get(..Self::LEN)?;
+        Some(Self {
+            authority: Pubkey::new_from_array(data[0..32].try_into().ok()?),
+            multiplier: f64::from_le_bytes(data[32..40].try_into().ok()?),
+            new_multiplier_effective_timestamp: i64::from_le_bytes(data[40..48].try_into().ok()?),
+            new_multiplier: f64::from_le_bytes(data[48..56].try_into().ok()?),
+        })
+    }
+
+    /// The multiplier in effect at the timestamp
+    pub fn multiplier(&self, unix_timestamp: i64) -> f64 {
+        if unix_timestamp >= self.new_multiplier_effective_timestamp {
+            self.new_multiplier
+        } else {
+            self.multiplier
+        }
+    }
+}
+
+/// The extension of a mint which makes its ui amounts drift from the raw amounts
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UiAmountConfig {
+    None,
+    InterestBearing(InterestBearingConfig),
+    ScaledUiAmount(ScaledUiAmountConfig),
+}
+
+impl UiAmountConfig {
+    pub fn from_mint_account(mint_account: &Account) -> Result<Self> {
+        if mint_account.owner != spl_token_2022::id() {
+            return Ok(Self::None);
+        }
+        let mint_state = StateWithExtensions::<Mint>::unpack(&mint_account.data)?;
+        if let Ok(config) = mint_state.get_extension::<InterestBearingConfig>() {
+            return Ok(Self::InterestBearing(*config));
+        }
+        // the tlv entries are the extension type (u16), the length (u16) and the value
+        let tlv_data = mint_state.get_tlv_data();
+        let mut offset = 0;
+        while let (Some(extension_type), Some(length)) = (
+            tlv_data.get(offset..offset + 2),
+            tlv_data.get(offset + 2..offset + 4),
+        ) {
+            let extension_type = u16::from_le_bytes(extension_type.try_into()?);
+            let length = usize::from(u16::from_le_bytes(length.try_into()?));
+            if extension_type == SCALED_UI_AMOUNT_EXTENSION_TYPE {
+                return tlv_data
+                    .get(offset + 4..offset + 4 + length)
+                    .and_then(ScaledUiAmountConfig::unpack)
+                    .map(Self::ScaledUiAmount)
+                    .ok_or_else(|| anyhow::format_err!("invalid scaled ui amount config"));
+            }
+            offset += 4 + length;
+        }
+        Ok(Self::None)
+    }
+
+    /// Returns the factor converting the raw amounts of the mint into ui amounts at the timestamp, excluding the decimals.
+    /// The interest accrued before the last rate update is only known as an average, so the factor is
+    /// approximated at the average rate for a timestamp before the last rate update.
+    pub fn multiplier(&self, unix_timestamp: i64) -> f64 {
+        match self {
+            Self::None => 1.0,
+            Self::InterestBearing(config) => {
+                let initialization_timestamp = i64::from(config.initialization_timestamp);
+                let last_update_timestamp = i64::from(config.last_update_timestamp);
+                let pre_update_average_rate = i16::from(config.pre_update_average_rate);
+                let current_rate = i16::from(config.current_rate);
+                let pre_update_exp = (f64::from(pre_update_average_rate)
+                    * (unix_timestamp.min(last_update_timestamp) - initialization_timestamp)
+                        as f64
+                    / SECONDS_PER_YEAR
+                    / ONE_IN_BASIS_POINTS)
+                    .exp();
+                let post_update_exp = (f64::from(current_rate)
+                    * (unix_timestamp - last_update_timestamp).max(0) as f64
+                    / SECONDS_PER_YEAR
+                    / ONE_IN_BASIS_POINTS)
+                    .exp();
+                pre_update_exp * post_update_exp
+            }
+            Self::ScaledUiAmount(config) => config.multiplier(unix_timestamp),
+        }
+    }
+}
+
+/// Returns the factor converting the raw amounts of the mint into ui amounts at the timestamp,
+/// excluding the decimals. It is 1 unless the mint has the interest-bearing or the scaled ui amount extension.
+pub fn get_ui_amount_multiplier(mint_account: &Account, unix_timestamp: i64) -> Result<f64> {
+    Ok(UiAmountConfig::from_mint_account(mint_account)?.multiplier(unix_timestamp))
+}
+
+/// Converts the raw amount into the ui amount with the multiplier from `get_ui_amount_multiplier`
+pub fn amount_to_ui_amount(amount: u64, decimals: u8, ui_amount_multiplier: f64) -> f64 {
+    amount as f64 * ui_amount_multiplier / multipler(decimals)
+}
+
+/// The price of token_0 in token_1 in ui amounts, which drifts from `sqrt_price_x64_to_price`
+/// for the interest-bearing and scaled ui amount mints
+pub fn sqrt_price_x64_to_ui_price(
+    price: u128,
+    decimals_0: u8,
+    decimals_1: u8,
+    ui_amount_multiplier_0: f64,
+    ui_amount_multiplier_1: f64,
+) -> f64 {
+    sqrt_price_x64_to_price(price, decimals_0, decimals_1) * ui_amount_multiplier_1
+        / ui_amount_multiplier_0
+}
+
 // the top level state of the swap, the results of which are recorded in storage at the end
 #[derive(Debug)]
 pub struct SwapState {
@@ -326,7 +572,13 @@ pub fn get_out_put_amount_and_remaining_accounts(
         zero_for_one,
         is_base_input,
         is_pool_current_tick_array,
-        pool_config.trade_fee_rate,
+        pool_state.get_trade_fee_rate(
+            pool_config.trade_fee_rate,
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+        ),
         input_amount,
         current_vaild_tick_array_start_index,
         sqrt_price_limit_x64.unwrap_or(0),
@@ -525,3 +777,98 @@ fn swap_compute(
 
     Ok((state.amount_calculated, tick_array_start_index_vec))
 }
+
+#[cfg(test)]
+mod ui_amount_test {
+    use super::*;
+    use solana_sdk::program_option::COption;
+    use spl_token_2022::extension::AccountType;
+
+    fn build_scaled_ui_amount_mint_account(config: &ScaledUiAmountConfig) -> Account {
+        let mut data = vec![0u8; spl_token_2022::state::Account::LEN];
+        Mint {
+            mint_authority: COption::None,
+            supply: 0,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        }
+        .pack_into_slice(&mut data[..Mint::LEN]);
+        data.push(AccountType::Mint as u8);
+        data.extend_from_slice(&SCALED_UI_AMOUNT_EXTENSION_TYPE.to_le_bytes());
+        data.extend_from_slice(&(ScaledUiAmountConfig::LEN as u16).to_le_bytes());
+        data.extend_from_slice(config.authority.as_ref());
+        data.extend_from_slice(&config.multiplier.to_le_bytes());
+        data.extend_from_slice(&config.new_multiplier_effective_timestamp.to_le_bytes());
+        data.extend_from_slice(&config.new_multiplier.to_le_bytes());
+        Account {
+            lamports: 0,
+            data,
+            owner: spl_token_2022::id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn scaled_ui_amount_multiplier_test() {
+        let config = ScaledUiAmountConfig {
+            authority: Pubkey::new_unique(),
+            multiplier: 1.5,
+            new_multiplier_effective_timestamp: 1000,
+            new_multiplier: 3.0,
+        };
+        let mint_account = build_scaled_ui_amount_mint_account(&config);
+        let ui_amount_config = UiAmountConfig::from_mint_account(&mint_account).unwrap();
+        assert_eq!(ui_amount_config, UiAmountConfig::ScaledUiAmount(config));
+        // the multiplier in effect at the event time
+        assert_eq!(get_ui_amount_multiplier(&mint_account, 999).unwrap(), 1.5);
+        assert_eq!(get_ui_amount_multiplier(&mint_account, 1000).unwrap(), 3.0);
+    }
+
+    #[test]
+    fn interest_bearing_multiplier_test() {
+        let config = InterestBearingConfig {
+            initialization_timestamp: 0.into(),
+            pre_update_average_rate: 1000.into(),
+            last_update_timestamp: (SECONDS_PER_YEAR as i64).into(),
+            current_rate: 2000.into(),
+            ..Default::default()
+        };
+        let ui_amount_config = UiAmountConfig::InterestBearing(config);
+        let one_year = SECONDS_PER_YEAR as i64;
+        assert_eq!(ui_amount_config.multiplier(0), 1.0);
+        assert!((ui_amount_config.multiplier(one_year) - 0.1f64.exp()).abs() < 1e-9);
+        assert!((ui_amount_config.multiplier(one_year / 2) - 0.05f64.exp()).abs() < 1e-9);
+        assert!((ui_amount_config.multiplier(one_year * 2) - 0.3f64.exp()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn standard_mint_multiplier_test() {
+        let mint_account = Account {
+            lamports: 0,
+            data: vec![0u8; Mint::LEN],
+            owner: spl_token::id(),
+            executable: false,
+            rent_epoch: 0,
+        };
+        assert_eq!(get_ui_amount_multiplier(&mint_account, 0).unwrap(), 1.0);
+    }
+
+    #[test]
+    fn ui_amount_and_ui_price_test() {
+        assert_eq!(amount_to_ui_amount(1_500_000, 6, 1.0), 1.5);
+        assert_eq!(amount_to_ui_amount(1_500_000, 6, 2.0), 3.0);
+        // 1 raw token_0 for 1 raw token_1, the ui amount of token_0 is doubled
+        let sqrt_price_x64 = fixed_point_64::Q64;
+        assert_eq!(sqrt_price_x64_to_price(sqrt_price_x64, 6, 6), 1.0);
+        assert_eq!(
+            sqrt_price_x64_to_ui_price(sqrt_price_x64, 6, 6, 2.0, 1.0),
+            0.5
+        );
+        assert_eq!(
+            sqrt_price_x64_to_ui_price(sqrt_price_x64, 6, 6, 1.0, 1.5),
+            1.5
+        );
+    }
+}
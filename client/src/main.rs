@@ -34,6 +34,7 @@ mod instructions;
 use bincode::serialize;
 use instructions::amm_instructions::*;
 use instructions::events_instructions_parse::*;
+use instructions::quote::PoolSnapshot;
 use instructions::rpc::*;
 use instructions::token_instructions::*;
 use instructions::utils::*;
@@ -470,6 +471,14 @@ pub enum CommandsName {
         amount: u64,
         limit_price: Option<f64>,
     },
+    QuoteSwap {
+        #[arg(short, long)]
+        zero_for_one: bool,
+        #[arg(short, long)]
+        base_in: bool,
+        amount: u64,
+        limit_price: Option<f64>,
+    },
     PPositionByOwner {
         user_wallet: Pubkey,
     },
@@ -537,6 +546,9 @@ pub enum CommandsName {
     },
     DecodeEvent {
         log_event: String,
+        /// The block time of the event, the ui amounts are only printed if it's known
+        #[arg(short, long)]
+        unix_timestamp: Option<i64>,
     },
     DecodeTxLog {
         tx_id: String,
@@ -792,6 +804,7 @@ fn main() -> Result<()> {
                 Some(0) => update_value = value,
                 Some(1) => update_value = value,
                 Some(2) => update_value = value,
+                Some(5) => update_value = value,
                 Some(3) => {
                     let remaining_key = remaining.unwrap();
                     remaing_accounts.push(AccountMeta::new_readonly(remaining_key, false));
@@ -1192,6 +1205,18 @@ fn main() -> Result<()> {
                     pool_config.tickarray_bitmap_extension.unwrap(),
                     false,
                 ));
+                // the amm config to show the fee tier in the position NFT metadata
+                remaining_accounts.push(AccountMeta::new_readonly(pool.amm_config, false));
+                // the transfer hook accounts of the pool tokens
+                remaining_accounts.append(&mut get_pool_transfer_hook_accounts(
+                    &rpc_client,
+                    &pool_config.pool_id_account.unwrap(),
+                    &pool,
+                    [transfer_fee.0.owner, transfer_fee.1.owner],
+                    &payer.pubkey(),
+                    [amount_0_max, amount_1_max],
+                    true,
+                )?);
 
                 let mut instructions = Vec::new();
                 let request_inits_instr =
@@ -1383,6 +1408,16 @@ fn main() -> Result<()> {
                     pool_config.tickarray_bitmap_extension.unwrap(),
                     false,
                 ));
+                // the transfer hook accounts of the pool tokens
+                remaining_accounts.append(&mut get_pool_transfer_hook_accounts(
+                    &rpc_client,
+                    &pool_config.pool_id_account.unwrap(),
+                    &pool,
+                    [transfer_fee.0.owner, transfer_fee.1.owner],
+                    &payer.pubkey(),
+                    [amount_0_max, amount_1_max],
+                    true,
+                )?);
 
                 let increase_instr = increase_liquidity_instr(
                     &pool_config.clone(),
@@ -1540,6 +1575,16 @@ fn main() -> Result<()> {
                     .map(|item| AccountMeta::new(item, false))
                     .collect();
                 remaining_accounts.append(&mut accounts);
+                // the transfer hook accounts of the pool tokens follow the reward accounts
+                remaining_accounts.append(&mut get_pool_transfer_hook_accounts(
+                    &rpc_client,
+                    &pool_config.pool_id_account.unwrap(),
+                    &pool,
+                    [transfer_fee.0.owner, transfer_fee.1.owner],
+                    &payer.pubkey(),
+                    [amount_0_min, amount_1_min],
+                    false,
+                )?);
                 // personal position exist
                 let mut decrease_instr = decrease_liquidity_instr(
                     &pool_config.clone(),
@@ -1895,6 +1940,47 @@ fn main() -> Result<()> {
                 })
                 .collect();
             remaining_accounts.append(&mut accounts);
+            // the transfer hook accounts of the input and output tokens
+            let (input_vault, output_vault, input_mint, output_mint) = if zero_for_one {
+                (
+                    pool_state.token_vault_0,
+                    pool_state.token_vault_1,
+                    pool_state.token_mint_0,
+                    pool_state.token_mint_1,
+                )
+            } else {
+                (
+                    pool_state.token_vault_1,
+                    pool_state.token_vault_0,
+                    pool_state.token_mint_1,
+                    pool_state.token_mint_0,
+                )
+            };
+            let (input_amount, output_amount) = if base_in {
+                (amount, other_amount_threshold)
+            } else {
+                (other_amount_threshold, amount)
+            };
+            remaining_accounts.append(&mut get_vault_transfer_hook_accounts(
+                &rpc_client,
+                &pool_config.pool_id_account.unwrap(),
+                &input_mint,
+                &input_vault,
+                &input_token,
+                &payer.pubkey(),
+                input_amount,
+                true,
+            )?);
+            remaining_accounts.append(&mut get_vault_transfer_hook_accounts(
+                &rpc_client,
+                &pool_config.pool_id_account.unwrap(),
+                &output_mint,
+                &output_vault,
+                &output_token,
+                &payer.pubkey(),
+                output_amount,
+                false,
+            )?);
             let mut instructions = Vec::new();
             let request_inits_instr = ComputeBudgetInstruction::set_compute_unit_limit(1400_000u32);
             instructions.push(request_inits_instr);
@@ -1951,6 +2037,34 @@ fn main() -> Result<()> {
                 println!("{}", signature);
             }
         }
+        CommandsName::QuoteSwap {
+            zero_for_one,
+            base_in,
+            amount,
+            limit_price,
+        } => {
+            let snapshot = PoolSnapshot::load(
+                &rpc_client,
+                &pool_config.raydium_v3_program,
+                pool_config.pool_id_account.unwrap(),
+            )?;
+            let sqrt_price_limit_x64 = limit_price.map(|limit_price| {
+                price_to_sqrt_price_x64(
+                    limit_price,
+                    snapshot.pool_state.mint_decimals_0,
+                    snapshot.pool_state.mint_decimals_1,
+                )
+            });
+            let block_timestamp = rpc_client.get_block_time(rpc_client.get_slot()?)? as u64;
+            let quote = snapshot.quote(
+                amount,
+                sqrt_price_limit_x64,
+                zero_for_one,
+                base_in,
+                block_timestamp,
+            )?;
+            println!("{:#?}", quote);
+        }
         CommandsName::PPositionByOwner { user_wallet } => {
             // load position
             let position_nft_infos = get_all_nft_and_position_by_owner(
@@ -2219,7 +2333,11 @@ fn main() -> Result<()> {
                 RpcProgramAccountsConfig {
                     filters: Some(vec![
                         RpcFilterType::Memcmp(Memcmp::new_base58_encoded(8, &pool_id.to_bytes())),
-                        RpcFilterType::DataSize(raydium_amm_v3::states::TickArrayState::LEN as u64),
+                        // the tick arrays grow with the limit order fills stored after the account struct
+                        RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                            0,
+                            <raydium_amm_v3::states::TickArrayState as anchor_lang::Discriminator>::DISCRIMINATOR,
+                        )),
                     ]),
                     account_config: RpcAccountInfoConfig {
                         encoding: Some(UiAccountEncoding::Base64Zstd),
@@ -2258,6 +2376,30 @@ fn main() -> Result<()> {
             println!("pool_id:{}", pool_id);
             let pool_account: raydium_amm_v3::states::PoolState = program.account(pool_id)?;
             println!("{:#?}", pool_account);
+            let unix_timestamp = rpc_client.get_block_time(rpc_client.get_slot()?)?;
+            let ui_amount_multiplier_0 = get_ui_amount_multiplier(
+                &rpc_client.get_account(&pool_account.token_mint_0)?,
+                unix_timestamp,
+            )?;
+            let ui_amount_multiplier_1 = get_ui_amount_multiplier(
+                &rpc_client.get_account(&pool_account.token_mint_1)?,
+                unix_timestamp,
+            )?;
+            println!(
+                "price:{}, ui_price:{}",
+                sqrt_price_x64_to_price(
+                    pool_account.sqrt_price_x64,
+                    pool_account.mint_decimals_0,
+                    pool_account.mint_decimals_1
+                ),
+                sqrt_price_x64_to_ui_price(
+                    pool_account.sqrt_price_x64,
+                    pool_account.mint_decimals_0,
+                    pool_account.mint_decimals_1,
+                    ui_amount_multiplier_0,
+                    ui_amount_multiplier_1,
+                )
+            );
         }
         CommandsName::PBitmapExtension { bitmap_extension } => {
             let bitmap_extension = if let Some(bitmap_extension) = bitmap_extension {
@@ -2283,11 +2425,22 @@ fn main() -> Result<()> {
         CommandsName::DecodeInstruction { instr_hex_data } => {
             handle_program_instruction(&instr_hex_data, InstructionDecodeType::BaseHex)?;
         }
-        CommandsName::DecodeEvent { log_event } => {
+        CommandsName::DecodeEvent {
+            log_event,
+            unix_timestamp,
+        } => {
+            // the multipliers of the ui amounts change over time, so the ui amounts are converted
+            // with the multipliers in effect at the block time of the event
+            let ui_amount_context = unix_timestamp.map(|unix_timestamp| UiAmountContext {
+                rpc_client: &rpc_client,
+                program_id: pool_config.raydium_v3_program,
+                unix_timestamp,
+            });
             handle_program_log(
                 &pool_config.raydium_v3_program.to_string(),
                 &log_event,
                 false,
+                ui_amount_context.as_ref(),
             )?;
         }
         CommandsName::DecodeTxLog { tx_id } => {
@@ -2301,6 +2454,14 @@ fn main() -> Result<()> {
                 },
             )?;
             let transaction = tx.transaction;
+            // the amounts are converted into ui amounts at the block time of the transaction
+            let ui_amount_context = UiAmountContext {
+                rpc_client: &rpc_client,
+                program_id: pool_config.raydium_v3_program,
+                unix_timestamp: tx
+                    .block_time
+                    .ok_or_else(|| format_err!("block time of the transaction not found"))?,
+            };
             // get meta
             let meta = if transaction.meta.is_some() {
                 transaction.meta
@@ -2316,7 +2477,11 @@ fn main() -> Result<()> {
                 meta.clone(),
             )?;
             // decode logs
-            parse_program_event(&pool_config.raydium_v3_program.to_string(), meta.clone())?;
+            parse_program_event(
+                &pool_config.raydium_v3_program.to_string(),
+                meta.clone(),
+                Some(&ui_amount_context),
+            )?;
         }
     }
 
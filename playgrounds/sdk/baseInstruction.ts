@@ -168,6 +168,7 @@ export class BaseInstruction {
     amountMaxB: BN,
     withMetadata: "create" | "no-create",
 
+    ammConfigId: PublicKey,
     exTickArrayBitmap?: PublicKey
   ): Promise<TransactionInstruction> {
     const program = getAmmV3Program();
@@ -204,11 +205,19 @@ export class BaseInstruction {
         vault1Mint: tokenMintB,
       });
 
-    // 如果有额外的 tick array bitmap，添加为 remaining accounts
-    if (exTickArrayBitmap) {
-      instruction.remainingAccounts([
-        { pubkey: exTickArrayBitmap, isSigner: false, isWritable: true },
-      ]);
+    // 如果有额外的 tick array bitmap，添加为第一个 remaining account；
+    // 创建 metadata 时需要传入 pool 的 amm config，用于在 NFT metadata 中显示手续费档位
+    const remainingAccounts = [
+      ...(exTickArrayBitmap
+        ? [{ pubkey: exTickArrayBitmap, isSigner: false, isWritable: true }]
+        : []),
+      ...(withMetadata === "create"
+        ? [{ pubkey: ammConfigId, isSigner: false, isWritable: false }]
+        : []),
+    ];
+
+    if (remainingAccounts.length > 0) {
+      instruction.remainingAccounts(remainingAccounts);
     }
 
     return await instruction.instruction();
@@ -314,6 +323,7 @@ export class BaseInstruction {
     baseAmount: BN,
     otherAmountMax: BN,
 
+    ammConfigId: PublicKey,
     exTickArrayBitmap?: PublicKey
   ): Promise<TransactionInstruction> {
     const program = getAmmV3Program();
@@ -350,11 +360,19 @@ export class BaseInstruction {
         vault1Mint: tokenMintB,
       });
 
-    // 如果有额外的 tick array bitmap，添加为 remaining accounts
-    if (exTickArrayBitmap) {
-      instruction.remainingAccounts([
-        { pubkey: exTickArrayBitmap, isSigner: false, isWritable: true },
-      ]);
+    // 如果有额外的 tick array bitmap，添加为第一个 remaining account；
+    // 创建 metadata 时需要传入 pool 的 amm config，用于在 NFT metadata 中显示手续费档位
+    const remainingAccounts = [
+      ...(exTickArrayBitmap
+        ? [{ pubkey: exTickArrayBitmap, isSigner: false, isWritable: true }]
+        : []),
+      ...(withMetadata === "create"
+        ? [{ pubkey: ammConfigId, isSigner: false, isWritable: false }]
+        : []),
+    ];
+
+    if (remainingAccounts.length > 0) {
+      instruction.remainingAccounts(remainingAccounts);
     }
 
     return await instruction.instruction();
@@ -522,7 +540,7 @@ export class BaseInstruction {
     const program = getAmmV3Program();
 
     const instruction = program.methods
-      .decreaseLiquidityV2(liquidity, amountMinA, amountMinB)
+      .decreaseLiquidityV2(liquidity, amountMinA, amountMinB, null)
       .accountsPartial({
         nftOwner: positionNftOwner,
         nftAccount: positionNftAccount,
@@ -583,7 +601,7 @@ export class BaseInstruction {
     const program = getAmmV3Program();
 
     const instruction = program.methods
-      .swapV2(amount, otherAmountThreshold, sqrtPriceLimitX64, isBaseInput)
+      .swapV2(amount, otherAmountThreshold, sqrtPriceLimitX64, isBaseInput, null)
       .accounts({
         payer,
         ammConfig: ammConfigId,
@@ -648,6 +666,44 @@ export class BaseInstruction {
     return await instruction.instruction();
   }
 
+  // 初始化分阶段释放的奖励，phases 从 openTime 开始依次执行，总时长必须等于 endTime - openTime
+  // rate 为相对 emissionsPerSecondX64 的比例，单位 10^-6
+  static async initRewardWithScheduleInstruction(
+    rewardFunder: PublicKey,
+    funderTokenAccount: PublicKey,
+    ammConfigId: PublicKey,
+    poolId: PublicKey,
+    rewardMint: PublicKey,
+    rewardProgramId: PublicKey,
+
+    openTime: number,
+    endTime: number,
+    emissionsPerSecondX64: BN,
+    phases: { duration: number; rate: number }[]
+  ): Promise<TransactionInstruction> {
+    const program = getAmmV3Program();
+
+    const instruction = program.methods
+      .initializeRewardWithSchedule(
+        {
+          openTime: new BN(openTime),
+          endTime: new BN(endTime),
+          emissionsPerSecondX64,
+        },
+        phases
+      )
+      .accounts({
+        rewardFunder,
+        funderTokenAccount,
+        ammConfig: ammConfigId,
+        poolState: poolId,
+        rewardTokenMint: rewardMint,
+        rewardTokenProgram: rewardProgramId,
+      });
+
+    return await instruction.instruction();
+  }
+
   static async setRewardInstruction(
     authority: PublicKey,
     ammConfigId: PublicKey,
@@ -683,7 +739,8 @@ export class BaseInstruction {
     rewardVault: PublicKey,
     rewardMint: PublicKey,
 
-    rewardIndex: number
+    rewardIndex: number,
+    rewardFunderState?: PublicKey
   ): Promise<TransactionInstruction> {
     const program = getAmmV3Program();
 
@@ -697,6 +754,66 @@ export class BaseInstruction {
         rewardVaultMint: rewardMint,
       });
 
+    // 传入 rewardFunderState 时，按出资比例退还第三方出资人的剩余奖励
+    if (rewardFunderState) {
+      instruction.remainingAccounts([
+        { pubkey: rewardFunderState, isSigner: false, isWritable: true },
+      ]);
+    }
+
+    return await instruction.instruction();
+  }
+
+  // 领取期结束后取回奖励金库全部余额，并重置奖励槽位
+  static async recycleRewardInstruction(
+    rewardFunder: PublicKey,
+    funderTokenAccount: PublicKey,
+    poolId: PublicKey,
+    rewardVault: PublicKey,
+    rewardMint: PublicKey,
+
+    rewardIndex: number
+  ): Promise<TransactionInstruction> {
+    const program = getAmmV3Program();
+
+    const instruction = program.methods
+      .recycleReward(rewardIndex)
+      .accounts({
+        rewardFunder,
+        funderTokenAccount,
+        poolState: poolId,
+        rewardTokenVault: rewardVault,
+        rewardVaultMint: rewardMint,
+      });
+
+    return await instruction.instruction();
+  }
+
+  // 任意出资人为已有奖励追加资金，按当前释放速率延长 endTime
+  static async addRewardFundsInstruction(
+    funder: PublicKey,
+    funderTokenAccount: PublicKey,
+    poolId: PublicKey,
+    rewardVault: PublicKey,
+    rewardMint: PublicKey,
+    rewardProgramId: PublicKey,
+
+    rewardIndex: number,
+    amount: BN
+  ): Promise<TransactionInstruction> {
+    const program = getAmmV3Program();
+
+    const instruction = program.methods
+      .addRewardFunds(rewardIndex, amount)
+      .accountsPartial({
+        funder,
+        funderTokenAccount,
+        poolState: poolId,
+        rewardTokenVault: rewardVault,
+        rewardTokenMint: rewardMint,
+        rewardTokenProgram: rewardProgramId,
+      });
+
     return await instruction.instruction();
   }
 
@@ -836,6 +953,26 @@ export class BaseInstruction {
     return await instruction.instruction();
   }
 
+  // 扩容 observation 环形缓冲区，payer 支付新增 observation 的租金
+  static async increaseObservationCardinalityInstruction(
+    payer: PublicKey,
+    poolState: PublicKey,
+    observationState: PublicKey,
+    observationCardinalityNext: number
+  ): Promise<TransactionInstruction> {
+    const program = getAmmV3Program();
+
+    const instruction = program.methods
+      .increaseObservationCardinality(observationCardinalityNext)
+      .accountsPartial({
+        payer,
+        poolState,
+        observationState,
+      });
+
+    return await instruction.instruction();
+  }
+
   static async collectProtocolFeeInstruction(
     poolState: PublicKey,
     tokenVault0: PublicKey,
@@ -1053,7 +1190,7 @@ export class BaseInstruction {
 
   static async _legacy_swap(
     payer: PublicKey,
-    ammConfig: PublicKey,
+    ammConfigId: PublicKey,
     poolState: PublicKey,
     inputTokenAccount: PublicKey,
     outputTokenAccount: PublicKey,
@@ -1155,6 +1292,19 @@ export class BaseInstruction {
     return await instruction.instruction();
   }
 
+  // 更新许可池管理员
+  static async updatePermissionManagerInstruction(
+    permissionManager: PublicKey
+  ): Promise<TransactionInstruction> {
+    const program = getAmmV3Program();
+
+    const instruction = program.methods.updatePermissionManager(
+      permissionManager
+    );
+
+    return await instruction.instruction();
+  }
+
   // 新增的链下奖励相关指令
   static async depositOffchainRewardInstruction(
     poolId: PublicKey,
@@ -1188,7 +1338,8 @@ export class BaseInstruction {
     tokenMint: PublicKey,
     claimerTokenAccount: PublicKey,
     tokenProgram: PublicKey,
-    amount: BN
+    amount: BN,
+    claimStatus?: PublicKey
   ): Promise<TransactionInstruction> {
     const program = getAmmV3Program();
 
@@ -1203,6 +1354,13 @@ export class BaseInstruction {
         tokenProgram,
       });
 
+    // 传入 claimStatus 时，记录已领取数量，避免 merkle 领取时重复领取
+    if (claimStatus) {
+      instruction.remainingAccounts([
+        { pubkey: claimStatus, isSigner: false, isWritable: true },
+      ]);
+    }
+
     return await instruction.instruction();
   }
 
@@ -1228,4 +1386,49 @@ export class BaseInstruction {
 
     return await instruction.instruction();
   }
+
+  // 发布链下奖励的 merkle root
+  static async updateOffchainRewardMerkleRootInstruction(
+    poolId: PublicKey,
+    authority: PublicKey,
+    epoch: BN,
+    merkleRoot: number[]
+  ): Promise<TransactionInstruction> {
+    const program = getAmmV3Program();
+
+    const instruction = program.methods
+      .updateOffchainRewardMerkleRoot(epoch, merkleRoot)
+      .accountsPartial({
+        authority,
+        poolId,
+      });
+
+    return await instruction.instruction();
+  }
+
+  // 使用 merkle proof 领取链下奖励，无需 authority 签名
+  static async claimOffchainRewardWithProofInstruction(
+    poolId: PublicKey,
+    claimer: PublicKey,
+    tokenMint: PublicKey,
+    claimerTokenAccount: PublicKey,
+    tokenProgram: PublicKey,
+    epoch: BN,
+    cumulativeAmount: BN,
+    proof: number[][]
+  ): Promise<TransactionInstruction> {
+    const program = getAmmV3Program();
+
+    const instruction = program.methods
+      .claimOffchainRewardWithProof(epoch, cumulativeAmount, proof)
+      .accountsPartial({
+        claimer,
+        poolId,
+        tokenMint,
+        claimerTokenAccount,
+        tokenProgram,
+      });
+
+    return await instruction.instruction();
+  }
 }
@@ -112,4 +112,60 @@ pub enum ErrorCode {
     IllegalAccountOwner,
     #[msg("Invalid account")]
     InvalidAccount,
+    #[msg("Invalid dynamic fee param")]
+    InvalidDynamicFeeParam,
+    #[msg("The observation window is out of range")]
+    InvalidObservationWindow,
+    #[msg("Invalid observation update duration")]
+    InvalidObservationUpdateDuration,
+    #[msg("The tick is not initialized")]
+    TickNotInitialized,
+    #[msg("Invalid limit order tick")]
+    InvalidLimitOrderTick,
+    #[msg("The new position range is the same as the current range")]
+    SameRepositionRange,
+    #[msg("The flash loan is not repaid with fee")]
+    FlashLoanNotRepaid,
+    #[msg("Missing system program or temporary account to wrap or unwrap SOL")]
+    MissingNativeAccount,
+    #[msg("Invalid fee override rates")]
+    InvalidFeeOverride,
+    #[msg("The swap moves the price more than the max price impact")]
+    PriceImpactExceeded,
+    #[msg("The swap crosses more initialized ticks than the max")]
+    TooManyTicksCrossed,
+    #[msg("The pool is not open for trading yet")]
+    PoolNotOpen,
+    #[msg("The pool is already open for trading")]
+    PoolAlreadyOpen,
+    #[msg("Invalid launch fee param")]
+    InvalidLaunchFeeParam,
+    #[msg("Missing pool permission account of the permissioned pool")]
+    MissingPoolPermissionAccount,
+    #[msg("The signer is not allowed by the permissioned pool")]
+    SignerNotAllowed,
+    #[msg("The allow list reach to the max")]
+    AllowListFull,
+    #[msg("Missing amm config account of the pool")]
+    MissingAmmConfigAccount,
+    #[msg("The claim period of the reward is not ended")]
+    RewardClaimPeriodNotEnded,
+    #[msg("The epoch of the merkle root is invalid")]
+    InvalidMerkleRootEpoch,
+    #[msg("Invalid merkle proof of the offchain reward")]
+    InvalidMerkleProof,
+    #[msg("The offchain reward is already claimed")]
+    OffchainRewardAlreadyClaimed,
+    #[msg("Invalid reward schedule phases")]
+    InvalidRewardSchedule,
+    #[msg("The reward schedule can't be changed until the reward ends")]
+    RewardScheduleNotChangeable,
+    #[msg("The remaining reward is not refunded to the reward funders")]
+    RewardFundsNotRefunded,
+    #[msg("Invalid observation cardinality")]
+    InvalidObservationCardinality,
+    #[msg("A pool is repeated in the swap route")]
+    RepeatedPoolInRoute,
+    #[msg("The open time must be in the future")]
+    InvalidOpenTime,
 }
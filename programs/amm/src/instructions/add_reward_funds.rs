@@ -0,0 +1,147 @@
+use crate::error::ErrorCode;
+use crate::libraries::{fixed_point_64, full_math::MulDiv, U256};
+use crate::util::transfer_from_user_to_pool_vault;
+use crate::{states::*, util};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+#[instruction(reward_index: u8)]
+pub struct AddRewardFunds<'info> {
+    /// The funder deposit reward token to vault, also pays for the funder account
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    /// The funder's reward token account, the remaining reward is refunded to it
+    #[account(
+        mut,
+        token::mint = reward_token_mint
+    )]
+    pub funder_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Add reward funds for this pool
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The reward vault of the reward index
+    #[account(mut)]
+    pub reward_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Reward mint
+    #[account(
+        address = reward_token_vault.mint
+    )]
+    pub reward_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The amount added by the funder to the reward
+    #[account(
+        init_if_needed,
+        seeds = [
+            REWARD_FUNDER_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            funder.key().as_ref(),
+            &[reward_index],
+        ],
+        bump,
+        payer = funder,
+        space = RewardFunderState::LEN
+    )]
+    pub reward_funder_state: Box<Account<'info, RewardFunderState>>,
+
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Adds funds to an existing reward, the end time of the reward is extended at the current emission rate.
+/// The funder is refunded its share of the remaining reward through `collect_remaining_rewards`.
+pub fn add_reward_funds(ctx: Context<AddRewardFunds>, reward_index: u8, amount: u64) -> Result<()> {
+    require_gt!(
+        REWARD_NUM,
+        reward_index as usize,
+        ErrorCode::InvalidRewardIndex
+    );
+    let index = reward_index as usize;
+    let current_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.update_reward_infos(current_timestamp)?;
+
+    let reward_info = pool_state.reward_infos[index];
+    if !reward_info.initialized() {
+        return err!(ErrorCode::UnInitializedRewardInfo);
+    }
+    require_keys_eq!(
+        ctx.accounts.reward_token_vault.key(),
+        reward_info.token_vault
+    );
+    // the phases of a scheduled reward are committed up front
+    if pool_state.reward_schedules[index].is_enabled() {
+        return err!(ErrorCode::RewardScheduleNotChangeable);
+    }
+    // an ended reward can only be restarted by the reward authority
+    if reward_info.last_update_time == reward_info.end_time {
+        return err!(ErrorCode::NotApproveUpdateRewardEmissiones);
+    }
+
+    // the amount received by the vault extends the reward
+    let transfer_fee = util::get_transfer_fee(ctx.accounts.reward_token_mint.clone(), amount)?;
+    let amount_received = amount
+        .checked_sub(transfer_fee)
+        .ok_or(ErrorCode::CalculateOverflow)?;
+    let extend_period = U256::from(amount_received)
+        .mul_div_floor(
+            U256::from(fixed_point_64::Q64),
+            U256::from(reward_info.emissions_per_second_x64),
+        )
+        .ok_or(ErrorCode::CalculateOverflow)?;
+    if extend_period > U256::from(u64::MAX) {
+        return err!(ErrorCode::CalculateOverflow);
+    }
+    let extend_period = extend_period.as_u64();
+    require_gt!(extend_period, 0, ErrorCode::InvalidRewardDesiredAmount);
+    let end_time = reward_info
+        .end_time
+        .checked_add(extend_period)
+        .ok_or(ErrorCode::CalculateOverflow)?;
+
+    let reward_vault_amount = ctx.accounts.reward_token_vault.amount;
+    pool_state.reward_infos[index].end_time = end_time;
+    pool_state.add_reward_contribution(index, amount_received, reward_vault_amount)?;
+
+    let reward_funder_state = ctx.accounts.reward_funder_state.as_mut();
+    reward_funder_state.initialize_if_needed(
+        ctx.bumps.reward_funder_state,
+        ctx.accounts.pool_state.key(),
+        reward_index,
+        pool_state.reward_generations[index],
+        ctx.accounts.funder.key(),
+    );
+    reward_funder_state.funder_token_account = ctx.accounts.funder_token_account.key();
+    reward_funder_state.contributed_amount = reward_funder_state
+        .contributed_amount
+        .checked_add(amount_received)
+        .ok_or(ErrorCode::CalculateOverflow)?;
+    drop(pool_state);
+
+    transfer_from_user_to_pool_vault(
+        &ctx.accounts.funder,
+        &ctx.accounts.funder_token_account.to_account_info(),
+        &ctx.accounts.reward_token_vault.to_account_info(),
+        Some(ctx.accounts.reward_token_mint.clone()),
+        &ctx.accounts.reward_token_program.to_account_info(),
+        Some(ctx.accounts.reward_token_program.to_account_info()),
+        amount,
+        &[],
+    )?;
+
+    emit!(AddRewardFundsEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        reward_index,
+        funder: ctx.accounts.funder.key(),
+        amount: amount_received,
+        end_time,
+        contributed_amount: ctx.accounts.reward_funder_state.contributed_amount,
+    });
+
+    Ok(())
+}
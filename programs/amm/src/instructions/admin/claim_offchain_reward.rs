@@ -69,7 +69,10 @@ pub struct ClaimOffchainRewardAccounts<'info> {
 }
 
 /// Claim offchain reward into the reward vault.
-pub fn claim_offchain_reward(ctx: Context<ClaimOffchainRewardAccounts>, amount: u64) -> Result<()> {
+pub fn claim_offchain_reward<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ClaimOffchainRewardAccounts<'info>>,
+    amount: u64,
+) -> Result<()> {
     require_keys_eq!(
         *ctx.accounts.reward_config.to_account_info().owner,
         crate::id(),
@@ -112,5 +115,39 @@ pub fn claim_offchain_reward(ctx: Context<ClaimOffchainRewardAccounts>, amount:
         decimals,
     )?;
 
+    // the config is not writable by the claim, the amount is recorded from the vault by the next update
+    let mut totals =
+        OffchainRewardConfig::load_mint_totals(&ctx.accounts.reward_config.to_account_info())?;
+    let total_claimed = match OffchainRewardMintTotals::find_mut(
+        &mut totals,
+        &ctx.accounts.token_mint.key(),
+        ctx.accounts.reward_vault_token_account.amount,
+    ) {
+        Some(reward_mint_totals) => {
+            reward_mint_totals.add_claimed(amount)?;
+            reward_mint_totals.total_claimed
+        }
+        None => 0,
+    };
+
+    // the claim status of a claimer claiming with merkle proofs is passed as the trailing remaining account,
+    // the amount is deducted from the cumulative amount proved with the merkle root later
+    if let Some(claim_status_info) = ctx.remaining_accounts.last() {
+        let mut claim_status = Account::<OffchainRewardClaimStatus>::try_from(claim_status_info)?;
+        require_keys_eq!(claim_status.reward_config, ctx.accounts.reward_config.key());
+        require_keys_eq!(claim_status.claimer, ctx.accounts.claimer.key());
+        require_keys_eq!(claim_status.token_mint, ctx.accounts.token_mint.key());
+        claim_status.add_claimed(amount)?;
+        claim_status.exit(&crate::id())?;
+    }
+
+    emit!(ClaimOffchainRewardEvent {
+        pool_id: ctx.accounts.pool_id.key(),
+        claimer: ctx.accounts.claimer.key(),
+        token_mint: ctx.accounts.token_mint.key(),
+        amount,
+        epoch: 0,
+        total_claimed,
+    });
     Ok(())
 }
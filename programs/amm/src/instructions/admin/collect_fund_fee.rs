@@ -71,8 +71,8 @@ pub struct CollectFundFee<'info> {
     pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
 }
 
-pub fn collect_fund_fee(
-    ctx: Context<CollectFundFee>,
+pub fn collect_fund_fee<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CollectFundFee<'info>>,
     amount_0_requested: u64,
     amount_1_requested: u64,
 ) -> Result<()> {
@@ -97,6 +97,7 @@ pub fn collect_fund_fee(
         &ctx.accounts.token_program,
         Some(ctx.accounts.token_program_2022.to_account_info()),
         amount_0,
+        ctx.remaining_accounts,
     )?;
 
     transfer_from_pool_vault_to_user(
@@ -107,6 +108,7 @@ pub fn collect_fund_fee(
         &ctx.accounts.token_program,
         Some(ctx.accounts.token_program_2022.to_account_info()),
         amount_1,
+        ctx.remaining_accounts,
     )?;
 
     check_unclaimed_fees_and_vault(
@@ -0,0 +1,62 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreatePoolPermission<'info> {
+    /// Address to be set as pool permission owner.
+    #[account(
+        mut,
+        address = admin_group.permission_manager @ ErrorCode::NotApproved
+    )]
+    pub owner: Signer<'info>,
+
+    /// amm admin group account to store admin permissions.
+    #[account(
+        seeds = [
+            ADMIN_GROUP_SEED.as_bytes()
+        ],
+        bump,
+    )]
+    pub admin_group: Box<Account<'info, AmmAdminGroup>>,
+
+    /// The pool to be permissioned
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Initialize pool permission account to store the allow list and the gating mint of the pool.
+    #[account(
+        init,
+        seeds = [
+            POOL_PERMISSION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = PoolPermissionState::LEN
+    )]
+    pub pool_permission: AccountLoader<'info, PoolPermissionState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_pool_permission(
+    ctx: Context<CreatePoolPermission>,
+    gating_mint: Pubkey,
+) -> Result<()> {
+    let mut pool_permission = ctx.accounts.pool_permission.load_init()?;
+    pool_permission.initialize(
+        ctx.bumps.pool_permission,
+        ctx.accounts.pool_state.key(),
+        gating_mint,
+    );
+    ctx.accounts.pool_state.load_mut()?.permissioned = 1;
+
+    emit!(PoolPermissionChangeEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        permissioned: true,
+        gating_mint,
+        allow_list_len: 0,
+    });
+    Ok(())
+}
@@ -86,16 +86,24 @@ pub fn deposit_offchain_reward(
         ErrorCode::InvalidAccount
     );
 
-    // add reward mint to the config if not exists, before that, check if the config account has enough space
-    if reward_config.add_reward_mint(ctx.accounts.token_mint.key())? {
-        // reallocate the account if needed
-        OffchainRewardConfig::realloc_if_needed(
-            reward_config.to_account_info(),
-            reward_config.reward_mint_vec.len(),
-            ctx.accounts.payer.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-        )?;
-    }
+    // add reward mint and its totals to the config if not exists, reallocate the config account if needed
+    reward_config.add_reward_mint(ctx.accounts.token_mint.key())?;
+    let reward_config_info = reward_config.to_account_info();
+    let mut totals = OffchainRewardConfig::load_mint_totals(&reward_config_info)?;
+    let reward_mint_totals = OffchainRewardMintTotals::track(
+        &mut totals,
+        ctx.accounts.token_mint.key(),
+        ctx.accounts.reward_vault_token_account.amount,
+    );
+    reward_mint_totals.add_deposited(amount)?;
+    let total_deposited = reward_mint_totals.total_deposited;
+    OffchainRewardConfig::realloc_if_needed(
+        reward_config_info.clone(),
+        reward_config.real_len() + OffchainRewardConfig::mint_totals_len(totals.len()),
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    )?;
+    reward_config.save_mint_totals(&reward_config_info, &totals)?;
 
     // transfer the token to the reward vault
     let decimals = ctx.accounts.token_mint.decimals;
@@ -112,5 +120,12 @@ pub fn deposit_offchain_reward(
         decimals,
     )?;
 
+    emit!(DepositOffchainRewardEvent {
+        pool_id: ctx.accounts.pool_id.key(),
+        payer: ctx.accounts.payer.key(),
+        token_mint: ctx.accounts.token_mint.key(),
+        amount,
+        total_deposited,
+    });
     Ok(())
 }
@@ -76,6 +76,7 @@ pub fn init_amm_admin_group(
         pool_manager: admin_group.pool_manager,
         emergency_manager: admin_group.emergency_manager,
         normal_manager: admin_group.normal_manager,
+        permission_manager: admin_group.permission_manager,
     });
 
     Ok(())
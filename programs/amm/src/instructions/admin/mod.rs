@@ -4,6 +4,9 @@ pub use init_amm_admin_group::*;
 pub mod update_amm_admin_group;
 pub use update_amm_admin_group::*;
 
+pub mod update_permission_manager;
+pub use update_permission_manager::*;
+
 pub mod create_amm_config;
 pub use create_amm_config::*;
 
@@ -16,6 +19,9 @@ pub use claim_offchain_reward::*;
 pub mod withdraw_offchain_reward;
 pub use withdraw_offchain_reward::*;
 
+pub mod update_offchain_reward_merkle_root;
+pub use update_offchain_reward_merkle_root::*;
+
 pub mod update_amm_config;
 pub use update_amm_config::*;
 
@@ -39,3 +45,21 @@ pub use update_pool_status::*;
 
 pub mod create_support_mint_associated;
 pub use create_support_mint_associated::*;
+
+pub mod update_dynamic_fee_config;
+pub use update_dynamic_fee_config::*;
+
+pub mod update_observation_duration;
+pub use update_observation_duration::*;
+
+pub mod update_pool_fee_override;
+pub use update_pool_fee_override::*;
+
+pub mod update_pool_open_time;
+pub use update_pool_open_time::*;
+
+pub mod create_pool_permission;
+pub use create_pool_permission::*;
+
+pub mod update_pool_permission;
+pub use update_pool_permission::*;
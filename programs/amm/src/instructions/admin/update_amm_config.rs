@@ -37,6 +37,10 @@ pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u32) -
             let new_fund_owner = *ctx.remaining_accounts.iter().next().unwrap().key;
             set_new_fund_owner(amm_config, new_fund_owner);
         }
+        Some(5) => {
+            require_gte!(1, value, ErrorCode::InvalidUpdateConfigFlag);
+            amm_config.flash_enabled = value as u8;
+        }
         _ => return err!(ErrorCode::InvalidUpdateConfigFlag),
     }
 
@@ -0,0 +1,79 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateDynamicFeeConfig<'info> {
+    #[account(
+        address = admin_group.normal_manager @ ErrorCode::NotApproved
+    )]
+    pub authority: Signer<'info>,
+
+    /// amm admin group account to store admin permissions.
+    #[account(
+        seeds = [
+            ADMIN_GROUP_SEED.as_bytes()
+        ],
+        bump,
+    )]
+    pub admin_group: Box<Account<'info, AmmAdminGroup>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+#[derive(Copy, Clone, AnchorSerialize, AnchorDeserialize, Debug, PartialEq)]
+pub struct DynamicFeeParam {
+    /// Swaps within this period (seconds) share the same volatility references
+    pub filter_period: u16,
+    /// The volatility reference is reset if no swap happened within this period (seconds)
+    pub decay_period: u16,
+    /// Decay of the volatility reference, in units of 1/10000
+    pub reduction_factor: u16,
+    /// Scales the dynamic fee rate, 0 disables the dynamic fee mode
+    pub dynamic_fee_control: u32,
+    /// Upper bound of the volatility accumulator
+    pub max_volatility_accumulator: u32,
+}
+
+impl DynamicFeeParam {
+    pub fn check(&self) -> Result<()> {
+        if self.dynamic_fee_control == 0 {
+            return Ok(());
+        }
+        if self.filter_period >= self.decay_period
+            || self.reduction_factor > dynamic_fee_limit::REDUCTION_FACTOR_DENOMINATOR
+            || self.max_volatility_accumulator == 0
+        {
+            return err!(ErrorCode::InvalidDynamicFeeParam);
+        }
+        Ok(())
+    }
+}
+
+pub fn update_dynamic_fee_config(
+    ctx: Context<UpdateDynamicFeeConfig>,
+    param: DynamicFeeParam,
+) -> Result<()> {
+    param.check()?;
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.dynamic_fee_info = DynamicFeeInfo {
+        filter_period: param.filter_period,
+        decay_period: param.decay_period,
+        reduction_factor: param.reduction_factor,
+        dynamic_fee_control: param.dynamic_fee_control,
+        max_volatility_accumulator: param.max_volatility_accumulator,
+        ..Default::default()
+    };
+
+    emit!(DynamicFeeConfigChangeEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        filter_period: param.filter_period,
+        decay_period: param.decay_period,
+        reduction_factor: param.reduction_factor,
+        dynamic_fee_control: param.dynamic_fee_control,
+        max_volatility_accumulator: param.max_volatility_accumulator,
+    });
+
+    Ok(())
+}
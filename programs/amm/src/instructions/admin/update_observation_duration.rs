@@ -0,0 +1,42 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateObservationDuration<'info> {
+    #[account(
+        address = admin_group.normal_manager @ ErrorCode::NotApproved
+    )]
+    pub authority: Signer<'info>,
+
+    /// amm admin group account to store admin permissions.
+    #[account(
+        seeds = [
+            ADMIN_GROUP_SEED.as_bytes()
+        ],
+        bump,
+    )]
+    pub admin_group: Box<Account<'info, AmmAdminGroup>>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The observation account of the pool
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+/// Widen the time window covered by the observations, the duration can only grow so that
+/// the windows already used by integrators keep available.
+pub fn update_observation_duration(
+    ctx: Context<UpdateObservationDuration>,
+    update_duration: u32,
+) -> Result<()> {
+    let mut observation_state = ctx.accounts.observation_state.load_mut()?;
+    require!(
+        update_duration >= observation_state.get_update_duration()
+            && update_duration <= OBSERVATION_UPDATE_DURATION_MAX,
+        ErrorCode::InvalidObservationUpdateDuration
+    );
+    observation_state.update_duration = update_duration;
+    Ok(())
+}
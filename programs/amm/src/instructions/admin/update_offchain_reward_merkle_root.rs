@@ -0,0 +1,78 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateOffchainRewardMerkleRoot<'info> {
+    /// The authority publishes the merkle root of the offchain rewards, also pays for the root account.
+    #[account(
+        mut,
+        address = admin_group.reward_claim_manager @ ErrorCode::NotApproved
+    )]
+    pub authority: Signer<'info>,
+
+    /// Initialize amm admin group account to store admin permissions.
+    #[account(
+        seeds = [
+            ADMIN_GROUP_SEED.as_bytes()
+        ],
+        bump,
+    )]
+    pub admin_group: Account<'info, AmmAdminGroup>,
+
+    /// the pool id, which is the pool state account.
+    /// CHECK: only used to derive the reward config account.
+    pub pool_id: UncheckedAccount<'info>,
+
+    /// The offchain reward config account, it also is the reward vault account.
+    #[account(
+        seeds = [
+            OFFCHAIN_REWARD_SEED.as_bytes(),
+            pool_id.key().as_ref(),
+        ],
+        bump,
+        has_one = pool_id
+    )]
+    pub reward_config: Box<Account<'info, OffchainRewardConfig>>,
+
+    /// The merkle root of the offchain rewards, created when the first root is published
+    #[account(
+        init_if_needed,
+        seeds = [
+            OFFCHAIN_REWARD_MERKLE_ROOT_SEED.as_bytes(),
+            reward_config.key().as_ref(),
+        ],
+        bump,
+        payer = authority,
+        space = OffchainRewardMerkleRoot::LEN
+    )]
+    pub merkle_root: Box<Account<'info, OffchainRewardMerkleRoot>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Publishes the merkle root of the cumulative offchain rewards for a new epoch.
+pub fn update_offchain_reward_merkle_root(
+    ctx: Context<UpdateOffchainRewardMerkleRoot>,
+    epoch: u64,
+    merkle_root: [u8; 32],
+) -> Result<()> {
+    let merkle_root_state = ctx.accounts.merkle_root.as_mut();
+    require_gt!(
+        epoch,
+        merkle_root_state.epoch,
+        ErrorCode::InvalidMerkleRootEpoch
+    );
+    merkle_root_state.bump = ctx.bumps.merkle_root;
+    merkle_root_state.reward_config = ctx.accounts.reward_config.key();
+    merkle_root_state.epoch = epoch;
+    merkle_root_state.merkle_root = merkle_root;
+    merkle_root_state.update_time = Clock::get()?.unix_timestamp as u64;
+
+    emit!(UpdateOffchainRewardMerkleRootEvent {
+        pool_id: ctx.accounts.pool_id.key(),
+        epoch,
+        merkle_root,
+    });
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use std::ops::DerefMut;
+
+#[derive(Accounts)]
+pub struct UpdatePermissionManagerAccounts<'info> {
+    /// only super admin can update the permission manager
+    #[account(
+        address = crate::admin::ID @ ErrorCode::NotApproved
+    )]
+    pub payer: Signer<'info>,
+
+    /// amm admin group account to store admin permissions.
+    #[account(
+        mut,
+        seeds = [
+            ADMIN_GROUP_SEED.as_bytes()
+        ],
+        bump,
+    )]
+    pub admin_group: Account<'info, AmmAdminGroup>,
+}
+
+pub fn update_permission_manager(
+    ctx: Context<UpdatePermissionManagerAccounts>,
+    permission_manager: Pubkey,
+) -> Result<()> {
+    require_keys_neq!(
+        permission_manager,
+        Pubkey::default(),
+        ErrorCode::NotApproved
+    );
+    let admin_group = ctx.accounts.admin_group.deref_mut();
+    admin_group.permission_manager = permission_manager;
+
+    admin_group.validate()?;
+
+    emit!(ModifyAmmAdminGroupEvent {
+        fee_keeper: admin_group.fee_keeper,
+        reward_config_manager: admin_group.reward_config_manager,
+        reward_claim_manager: admin_group.reward_claim_manager,
+        pool_manager: admin_group.pool_manager,
+        emergency_manager: admin_group.emergency_manager,
+        normal_manager: admin_group.normal_manager,
+        permission_manager: admin_group.permission_manager,
+    });
+
+    Ok(())
+}
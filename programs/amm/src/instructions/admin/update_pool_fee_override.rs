@@ -0,0 +1,62 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolFeeOverride<'info> {
+    #[account(
+        address = admin_group.normal_manager @ ErrorCode::NotApproved
+    )]
+    pub authority: Signer<'info>,
+
+    /// amm admin group account to store admin permissions.
+    #[account(
+        seeds = [
+            ADMIN_GROUP_SEED.as_bytes()
+        ],
+        bump,
+    )]
+    pub admin_group: Box<Account<'info, AmmAdminGroup>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn update_pool_fee_override(
+    ctx: Context<UpdatePoolFeeOverride>,
+    enabled: bool,
+    trade_fee_rate: u32,
+    protocol_fee_rate: u32,
+    fund_fee_rate: u32,
+) -> Result<()> {
+    // the rates are cleared if the fee override is disabled
+    let fee_override = if enabled {
+        require!(
+            trade_fee_rate < FEE_RATE_DENOMINATOR_VALUE
+                && protocol_fee_rate
+                    .checked_add(fund_fee_rate)
+                    .is_some_and(|rate| rate <= FEE_RATE_DENOMINATOR_VALUE),
+            ErrorCode::InvalidFeeOverride
+        );
+        FeeOverride {
+            enabled: 1,
+            trade_fee_rate,
+            protocol_fee_rate,
+            fund_fee_rate,
+            ..Default::default()
+        }
+    } else {
+        FeeOverride::default()
+    };
+    ctx.accounts.pool_state.load_mut()?.fee_override = fee_override;
+
+    emit!(PoolFeeOverrideChangeEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        enabled,
+        trade_fee_rate: fee_override.trade_fee_rate,
+        protocol_fee_rate: fee_override.protocol_fee_rate,
+        fund_fee_rate: fee_override.fund_fee_rate,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolOpenTime<'info> {
+    #[account(
+        address = admin_group.pool_manager @ ErrorCode::NotApproved
+    )]
+    pub authority: Signer<'info>,
+
+    /// amm admin group account to store admin permissions.
+    #[account(
+        seeds = [
+            ADMIN_GROUP_SEED.as_bytes()
+        ],
+        bump,
+    )]
+    pub admin_group: Box<Account<'info, AmmAdminGroup>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn update_pool_open_time(ctx: Context<UpdatePoolOpenTime>, open_time: u64) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    // the open time can't be changed once the trading is started
+    require_gte!(
+        pool_state.open_time,
+        block_timestamp,
+        ErrorCode::PoolAlreadyOpen
+    );
+    // the pool can't be opened immediately by moving the open time into the past
+    require_gt!(open_time, block_timestamp, ErrorCode::InvalidOpenTime);
+    let open_time_before = pool_state.open_time;
+    pool_state.open_time = open_time;
+
+    emit!(PoolOpenTimeChangeEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        open_time_before,
+        open_time,
+    });
+    Ok(())
+}
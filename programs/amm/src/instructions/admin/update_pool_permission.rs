@@ -0,0 +1,62 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolPermission<'info> {
+    #[account(
+        address = admin_group.permission_manager @ ErrorCode::NotApproved
+    )]
+    pub owner: Signer<'info>,
+
+    /// amm admin group account to store admin permissions.
+    #[account(
+        seeds = [
+            ADMIN_GROUP_SEED.as_bytes()
+        ],
+        bump,
+    )]
+    pub admin_group: Box<Account<'info, AmmAdminGroup>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_PERMISSION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub pool_permission: AccountLoader<'info, PoolPermissionState>,
+}
+
+pub fn update_pool_permission(
+    ctx: Context<UpdatePoolPermission>,
+    param: u8,
+    keys: Vec<Pubkey>,
+) -> Result<()> {
+    let mut pool_permission = ctx.accounts.pool_permission.load_mut()?;
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    match param {
+        0 => pool_permission.add_allow_list(keys)?,
+        1 => pool_permission.remove_allow_list(keys),
+        2 => pool_permission.gating_mint = keys.first().copied().unwrap_or_default(),
+        3 => pool_state.permissioned = 1,
+        4 => pool_state.permissioned = 0,
+        _ => return err!(ErrorCode::InvalidUpdateConfigFlag),
+    }
+
+    emit!(PoolPermissionChangeEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        permissioned: pool_state.is_permissioned(),
+        gating_mint: pool_permission.gating_mint,
+        allow_list_len: pool_permission
+            .allow_list
+            .iter()
+            .filter(|key| **key != Pubkey::default())
+            .count() as u32,
+    });
+    Ok(())
+}
@@ -102,6 +102,22 @@ pub fn withdraw_offchain_reward(
     };
     require_gt!(amount, 0);
 
+    // the totals of a mint not deposited since they are tracked are not recorded
+    let mut totals = OffchainRewardConfig::load_mint_totals(&reward_config_account_info)?;
+    let total_withdrawn = match OffchainRewardMintTotals::find_mut(
+        &mut totals,
+        &ctx.accounts.token_mint.key(),
+        ctx.accounts.reward_vault_token_account.amount,
+    ) {
+        Some(reward_mint_totals) => {
+            reward_mint_totals.add_withdrawn(amount)?;
+            let total_withdrawn = reward_mint_totals.total_withdrawn;
+            reward_config.save_mint_totals(&reward_config_account_info, &totals)?;
+            total_withdrawn
+        }
+        None => 0,
+    };
+
     // transfer the token to the claimer's token account
     let decimals = ctx.accounts.token_mint.decimals;
     let seeds = reward_config.seeds();
@@ -123,5 +139,12 @@ pub fn withdraw_offchain_reward(
         decimals,
     )?;
 
+    emit!(WithdrawOffchainRewardEvent {
+        pool_id: ctx.accounts.pool_id.key(),
+        receiver_token_account: ctx.accounts.receiver_token_account.key(),
+        token_mint: ctx.accounts.token_mint.key(),
+        amount,
+        total_withdrawn,
+    });
     Ok(())
 }
@@ -0,0 +1,178 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct ClaimOffchainRewardWithProof<'info> {
+    /// the address who claim the offchain reward, also pays for the claim status account.
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+
+    /// the pool id, which is the pool state account.
+    /// CHECK: only used to derive the reward config account.
+    pub pool_id: UncheckedAccount<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = claimer,
+        token::token_program = token_program,
+    )]
+    pub claimer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = reward_config,
+        associated_token::token_program = token_program,
+    )]
+    pub reward_vault_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The offchain reward config account, it also is the reward vault account.
+    #[account(
+        mut,
+        seeds = [
+            OFFCHAIN_REWARD_SEED.as_bytes(),
+            pool_id.key().as_ref(),
+        ],
+        bump,
+        has_one = pool_id
+    )]
+    pub reward_config: Box<Account<'info, OffchainRewardConfig>>,
+
+    /// The latest merkle root published by the reward claim manager
+    #[account(
+        seeds = [
+            OFFCHAIN_REWARD_MERKLE_ROOT_SEED.as_bytes(),
+            reward_config.key().as_ref(),
+        ],
+        bump = merkle_root.bump,
+        has_one = reward_config
+    )]
+    pub merkle_root: Box<Account<'info, OffchainRewardMerkleRoot>>,
+
+    /// The amount claimed by the claimer of the reward mint
+    #[account(
+        init_if_needed,
+        seeds = [
+            OFFCHAIN_REWARD_CLAIM_STATUS_SEED.as_bytes(),
+            reward_config.key().as_ref(),
+            token_mint.key().as_ref(),
+            claimer.key().as_ref(),
+        ],
+        bump,
+        payer = claimer,
+        space = OffchainRewardClaimStatus::LEN
+    )]
+    pub claim_status: Box<Account<'info, OffchainRewardClaimStatus>>,
+
+    /// Spl token program or token program 2022
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Claims the offchain reward without the reward claim manager, the cumulative amount of the claimer
+/// is proved with the latest merkle root and the amount not claimed yet is transferred.
+pub fn claim_offchain_reward_with_proof(
+    ctx: Context<ClaimOffchainRewardWithProof>,
+    epoch: u64,
+    cumulative_amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let reward_config = ctx.accounts.reward_config.as_ref();
+    require_keys_eq!(
+        reward_config.reward_vault,
+        reward_config.key(),
+        ErrorCode::InvalidAccount
+    );
+    if !reward_config
+        .reward_mint_vec
+        .contains(&ctx.accounts.token_mint.key())
+    {
+        return err!(ErrorCode::NotSupportMint);
+    }
+
+    // the proof must be built from the latest root, the cumulative amount covers the previous epochs
+    let merkle_root = ctx.accounts.merkle_root.as_ref();
+    require_eq!(epoch, merkle_root.epoch, ErrorCode::InvalidMerkleRootEpoch);
+    let leaf = OffchainRewardMerkleRoot::get_leaf(
+        &ctx.accounts.claimer.key(),
+        &ctx.accounts.token_mint.key(),
+        cumulative_amount,
+    );
+    require!(
+        merkle_root.verify(&proof, leaf),
+        ErrorCode::InvalidMerkleProof
+    );
+
+    let claim_status = ctx.accounts.claim_status.as_mut();
+    claim_status.initialize_if_needed(
+        ctx.bumps.claim_status,
+        ctx.accounts.reward_config.key(),
+        ctx.accounts.claimer.key(),
+        ctx.accounts.token_mint.key(),
+    );
+    require_gt!(
+        cumulative_amount,
+        claim_status.claimed_amount,
+        ErrorCode::OffchainRewardAlreadyClaimed
+    );
+    let amount = cumulative_amount
+        .checked_sub(claim_status.claimed_amount)
+        .unwrap();
+    claim_status.claimed_amount = cumulative_amount;
+
+    // transfer the token to the claimer's token account
+    let seeds = ctx.accounts.reward_config.seeds();
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::TransferChecked {
+                from: ctx.accounts.reward_vault_token_account.to_account_info(),
+                to: ctx.accounts.claimer_token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                authority: ctx.accounts.reward_config.to_account_info(),
+            },
+            &[&seeds],
+        ),
+        amount,
+        ctx.accounts.token_mint.decimals,
+    )?;
+
+    let reward_config_info = ctx.accounts.reward_config.to_account_info();
+    let mut totals = OffchainRewardConfig::load_mint_totals(&reward_config_info)?;
+    let total_claimed = match OffchainRewardMintTotals::find_mut(
+        &mut totals,
+        &ctx.accounts.token_mint.key(),
+        ctx.accounts.reward_vault_token_account.amount,
+    ) {
+        Some(reward_mint_totals) => {
+            reward_mint_totals.add_claimed(amount)?;
+            let total_claimed = reward_mint_totals.total_claimed;
+            ctx.accounts
+                .reward_config
+                .save_mint_totals(&reward_config_info, &totals)?;
+            total_claimed
+        }
+        None => 0,
+    };
+
+    emit!(ClaimOffchainRewardEvent {
+        pool_id: ctx.accounts.pool_id.key(),
+        claimer: ctx.accounts.claimer.key(),
+        token_mint: ctx.accounts.token_mint.key(),
+        amount,
+        epoch,
+        total_claimed,
+    });
+    Ok(())
+}
@@ -0,0 +1,355 @@
+use super::{calculate_latest_token_fees, collect_rewards};
+use crate::error::ErrorCode;
+use crate::libraries::liquidity_math;
+use crate::states::*;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct CloseLimitOrder<'info> {
+    /// The owner of the order, receives the rent of the order account
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The pool of the order
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The order account to close
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner @ ErrorCode::NotApproved,
+        constraint = limit_order.pool_id == pool_state.key(),
+    )]
+    pub limit_order: Box<Account<'info, LimitOrderState>>,
+
+    /// The tick array contains both ticks of the order range
+    #[account(mut, constraint = tick_array.load()?.pool_id == pool_state.key())]
+    pub tick_array: AccountLoader<'info, TickArrayState>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for receive amount_0
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for receive amount_1
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+    // remaining account
+    // #[account(
+    //     seeds = [
+    //         POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    // the reward accounts of the initialized rewards: reward_token_vault, recipient_token_account, reward_vault_mint
+}
+
+/// Closes a limit order. A filled order claims the converted token, otherwise the order is cancelled
+/// and its liquidity is withdrawn at the current price.
+pub fn close_limit_order<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CloseLimitOrder<'info>>,
+) -> Result<()> {
+    let limit_order = &ctx.accounts.limit_order;
+    let liquidity = limit_order.liquidity;
+    let tick_lower_index = limit_order.tick_lower_index;
+    let tick_upper_index = limit_order.tick_upper_index;
+
+    let pool_state_key = ctx.accounts.pool_state.key();
+    let mut tickarray_bitmap_extension = None;
+    let mut remaining_collect_accounts = Vec::new();
+    for account_info in ctx.remaining_accounts.iter() {
+        if account_info.key() == TickArrayBitmapExtension::key(pool_state_key) {
+            tickarray_bitmap_extension = Some(account_info);
+            continue;
+        }
+        remaining_collect_accounts.push(account_info);
+    }
+
+    // the fees and rewards are accounted like a position with the order liquidity
+    let mut order_position = PersonalPositionState {
+        pool_id: pool_state_key,
+        liquidity,
+        reward_generations: limit_order.reward_generations,
+        ..Default::default()
+    };
+    for i in 0..REWARD_NUM {
+        order_position.reward_infos[i].growth_inside_last_x64 =
+            limit_order.reward_growths_inside_last_x64[i];
+    }
+
+    let filled;
+    let amount_0;
+    let amount_1;
+    let fee_amount_0;
+    let fee_amount_1;
+    {
+        let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity) {
+            return err!(ErrorCode::NotApproved);
+        }
+        // the order account is closed, so the fees of the order can't be kept for later
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee) {
+            return err!(ErrorCode::NotApproved);
+        }
+        let tick_spacing = pool_state.tick_spacing;
+        let (mut tick_array, mut fills) = load_tick_array_mut(ctx.accounts.tick_array.as_ref())?;
+        let mut tick_lower_state =
+            *tick_array.get_tick_state_mut(tick_lower_index, tick_spacing)?;
+        let mut tick_upper_state =
+            *tick_array.get_tick_state_mut(tick_upper_index, tick_spacing)?;
+
+        let timestamp = Clock::get()?.unix_timestamp as u64;
+        let updated_reward_infos = pool_state.update_reward_infos(timestamp)?;
+        tick_lower_state.sync_reward_generations(pool_state.reward_generations);
+        tick_upper_state.sync_reward_generations(pool_state.reward_generations);
+
+        let fill_tick_state = if limit_order.zero_for_one {
+            &tick_upper_state
+        } else {
+            &tick_lower_state
+        };
+        filled = limit_order.is_filled(fill_tick_state.order_fill_epoch);
+        let fill = LimitOrderFill::remove_order(
+            &mut fills,
+            limit_order.fill_tick_index(),
+            limit_order.fill_epoch,
+            liquidity,
+        )?
+        .ok_or(ErrorCode::InvalidLimitOrderTick)?;
+
+        // the growth inside of a filled order is the one recorded when it was filled
+        let (fee_growth_inside_0_x64, fee_growth_inside_1_x64, reward_growths_inside_x64) =
+            if filled {
+                (
+                    fill.fee_growth_inside_0_x64,
+                    fill.fee_growth_inside_1_x64,
+                    fill.reward_growths_inside_x64,
+                )
+            } else {
+                let (fee_growth_inside_0_x64, fee_growth_inside_1_x64) = get_fee_growth_inside(
+                    &tick_lower_state,
+                    &tick_upper_state,
+                    pool_state.tick_current,
+                    pool_state.fee_growth_global_0_x64,
+                    pool_state.fee_growth_global_1_x64,
+                );
+                (
+                    fee_growth_inside_0_x64,
+                    fee_growth_inside_1_x64,
+                    get_reward_growths_inside(
+                        &tick_lower_state,
+                        &tick_upper_state,
+                        pool_state.tick_current,
+                        &updated_reward_infos,
+                    ),
+                )
+            };
+        fee_amount_0 = calculate_latest_token_fees(
+            0,
+            limit_order.fee_growth_inside_0_last_x64,
+            fee_growth_inside_0_x64,
+            liquidity,
+        );
+        fee_amount_1 = calculate_latest_token_fees(
+            0,
+            limit_order.fee_growth_inside_1_last_x64,
+            fee_growth_inside_1_x64,
+            liquidity,
+        );
+        require_gte!(
+            pool_state.total_fees_token_0 - pool_state.total_fees_claimed_token_0,
+            fee_amount_0
+        );
+        require_gte!(
+            pool_state.total_fees_token_1 - pool_state.total_fees_claimed_token_1,
+            fee_amount_1
+        );
+        pool_state.total_fees_claimed_token_0 = pool_state
+            .total_fees_claimed_token_0
+            .checked_add(fee_amount_0)
+            .unwrap();
+        pool_state.total_fees_claimed_token_1 = pool_state
+            .total_fees_claimed_token_1
+            .checked_add(fee_amount_1)
+            .unwrap();
+        // the rewards of a recycled reward slot are forfeited like the rewards of a position
+        order_position.update_rewards(
+            reward_growths_inside_x64,
+            pool_state.reward_generations,
+            true,
+        )?;
+
+        let flip_tick_lower;
+        let flip_tick_upper;
+        if filled {
+            // the order liquidity has been removed from liquidity_net when it was filled
+            flip_tick_lower = tick_lower_state.release_filled_order(liquidity)?;
+            flip_tick_upper = tick_upper_state.release_filled_order(liquidity)?;
+            (amount_0, amount_1) = limit_order.get_filled_amounts()?;
+        } else {
+            let fill_tick_state = if limit_order.zero_for_one {
+                &mut tick_upper_state
+            } else {
+                &mut tick_lower_state
+            };
+            fill_tick_state.order_liquidity = fill_tick_state
+                .order_liquidity
+                .checked_sub(liquidity)
+                .ok_or(ErrorCode::CalculateOverflow)?;
+
+            pool_state.update_seconds_per_liquidity(timestamp);
+
+            let liquidity_delta = -i128::try_from(liquidity).unwrap();
+            flip_tick_lower = tick_lower_state.update(
+                pool_state.tick_current,
+                liquidity_delta,
+                pool_state.fee_growth_global_0_x64,
+                pool_state.fee_growth_global_1_x64,
+                false,
+                &updated_reward_infos,
+                pool_state.seconds_per_liquidity_global_x64,
+                timestamp,
+            )?;
+            flip_tick_upper = tick_upper_state.update(
+                pool_state.tick_current,
+                liquidity_delta,
+                pool_state.fee_growth_global_0_x64,
+                pool_state.fee_growth_global_1_x64,
+                true,
+                &updated_reward_infos,
+                pool_state.seconds_per_liquidity_global_x64,
+                timestamp,
+            )?;
+            if flip_tick_lower {
+                tick_lower_state.clear();
+            }
+            if flip_tick_upper {
+                tick_upper_state.clear();
+            }
+
+            // the order may be partially converted if the price is inside the range
+            (amount_0, amount_1) = liquidity_math::get_delta_amounts_signed(
+                pool_state.tick_current,
+                pool_state.sqrt_price_x64,
+                tick_lower_index,
+                tick_upper_index,
+                liquidity_delta,
+            )?;
+            if pool_state.tick_current >= tick_lower_index
+                && pool_state.tick_current < tick_upper_index
+            {
+                pool_state.liquidity =
+                    liquidity_math::add_delta(pool_state.liquidity, liquidity_delta)?;
+            }
+        }
+
+        tick_array.update_tick_state(tick_lower_index, tick_spacing, tick_lower_state)?;
+        tick_array.update_tick_state(tick_upper_index, tick_spacing, tick_upper_state)?;
+        if flip_tick_lower {
+            tick_array.update_initialized_tick_count(false)?;
+        }
+        if flip_tick_upper {
+            tick_array.update_initialized_tick_count(false)?;
+        }
+        if (flip_tick_lower || flip_tick_upper) && tick_array.initialized_tick_count == 0 {
+            let tick_array_start_index = tick_array.start_tick_index;
+            let use_tickarray_bitmap_extension =
+                pool_state.is_overflow_default_tickarray_bitmap(vec![tick_array_start_index]);
+            if use_tickarray_bitmap_extension {
+                require!(
+                    tickarray_bitmap_extension.is_some(),
+                    ErrorCode::MissingTickArrayBitmapExtensionAccount
+                );
+            }
+            pool_state.flip_tick_array_bit(tickarray_bitmap_extension, tick_array_start_index)?;
+        }
+    }
+
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_0 + fee_amount_0,
+        ctx.remaining_accounts,
+    )?;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_1 + fee_amount_1,
+        ctx.remaining_accounts,
+    )?;
+
+    let reward_amounts = collect_rewards(
+        &ctx.accounts.pool_state,
+        remaining_collect_accounts.as_slice(),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        &mut order_position,
+        true,
+        [u64::MAX; REWARD_NUM],
+    )?;
+
+    emit!(CloseLimitOrderEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        owner: ctx.accounts.owner.key(),
+        limit_order: ctx.accounts.limit_order.key(),
+        filled,
+        amount_0,
+        amount_1,
+        fee_amount_0,
+        fee_amount_1,
+        reward_amounts,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,246 @@
+use super::decrease_liquidity::check_unclaimed_fees_and_vault;
+use super::increase_liquidity::calculate_latest_token_fees;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::transfer_from_pool_vault_to_user;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct CollectFees<'info> {
+    /// The position owner or delegated authority
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+        token::authority = nft_owner,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Collect fees for this position
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Stores init state for the lower tick, only read
+    #[account(constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick, only read
+    #[account(constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for receive fee of token_0, can be owned by anyone
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for receive fee of token_1, can be owned by anyone
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+pub fn collect_fees<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CollectFees<'info>>,
+    amount_0_requested: u64,
+    amount_1_requested: u64,
+) -> Result<()> {
+    if !ctx
+        .accounts
+        .pool_state
+        .load()?
+        .get_status_by_bit(PoolStatusBitIndex::CollectFee)
+    {
+        return err!(ErrorCode::NotApproved);
+    }
+    update_fees_and_rewards_owed(
+        &ctx.accounts.pool_state,
+        &mut ctx.accounts.protocol_position,
+        &mut ctx.accounts.personal_position,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+    )?;
+
+    let personal_position = &mut ctx.accounts.personal_position;
+    let amount_0 = personal_position.token_fees_owed_0.min(amount_0_requested);
+    let amount_1 = personal_position.token_fees_owed_1.min(amount_1_requested);
+    {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        require_gte!(
+            pool_state.total_fees_token_0 - pool_state.total_fees_claimed_token_0,
+            amount_0
+        );
+        require_gte!(
+            pool_state.total_fees_token_1 - pool_state.total_fees_claimed_token_1,
+            amount_1
+        );
+        pool_state.total_fees_claimed_token_0 = pool_state
+            .total_fees_claimed_token_0
+            .checked_add(amount_0)
+            .unwrap();
+        pool_state.total_fees_claimed_token_1 = pool_state
+            .total_fees_claimed_token_1
+            .checked_add(amount_1)
+            .unwrap();
+    }
+    personal_position.token_fees_owed_0 -= amount_0;
+    personal_position.token_fees_owed_1 -= amount_1;
+
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_0,
+        ctx.remaining_accounts,
+    )?;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_1,
+        ctx.remaining_accounts,
+    )?;
+
+    check_unclaimed_fees_and_vault(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+    )?;
+
+    emit!(CollectPersonalFeeEvent {
+        position_nft_mint: ctx.accounts.personal_position.nft_mint,
+        recipient_token_account_0: ctx.accounts.recipient_token_account_0.key(),
+        recipient_token_account_1: ctx.accounts.recipient_token_account_1.key(),
+        amount_0,
+        amount_1,
+    });
+
+    Ok(())
+}
+
+/// Brings the fees and rewards owed of the position up to date without modifying liquidity,
+/// the tick states are only read.
+pub fn update_fees_and_rewards_owed<'info>(
+    pool_state_loader: &AccountLoader<'info, PoolState>,
+    protocol_position: &mut ProtocolPositionState,
+    personal_position: &mut PersonalPositionState,
+    tick_array_lower_loader: &AccountLoader<'info, TickArrayState>,
+    tick_array_upper_loader: &AccountLoader<'info, TickArrayState>,
+) -> Result<()> {
+    let mut pool_state = pool_state_loader.load_mut()?;
+    let updated_reward_infos =
+        pool_state.update_reward_infos(Clock::get()?.unix_timestamp as u64)?;
+
+    // the ticks are copied to reset the reward growth outside of the recycled reward slots
+    let tick_array_lower = tick_array_lower_loader.load()?;
+    let mut tick_lower_state = *tick_array_lower
+        .get_tick_state(personal_position.tick_lower_index, pool_state.tick_spacing)?;
+    tick_lower_state.sync_reward_generations(pool_state.reward_generations);
+    let tick_array_upper = tick_array_upper_loader.load()?;
+    let mut tick_upper_state = *tick_array_upper
+        .get_tick_state(personal_position.tick_upper_index, pool_state.tick_spacing)?;
+    tick_upper_state.sync_reward_generations(pool_state.reward_generations);
+
+    let (fee_growth_inside_0_x64, fee_growth_inside_1_x64) = tick_array::get_fee_growth_inside(
+        &tick_lower_state,
+        &tick_upper_state,
+        pool_state.tick_current,
+        pool_state.fee_growth_global_0_x64,
+        pool_state.fee_growth_global_1_x64,
+    );
+    let reward_growths_inside = tick_array::get_reward_growths_inside(
+        &tick_lower_state,
+        &tick_upper_state,
+        pool_state.tick_current,
+        &updated_reward_infos,
+    );
+    protocol_position.update(
+        personal_position.tick_lower_index,
+        personal_position.tick_upper_index,
+        0,
+        fee_growth_inside_0_x64,
+        fee_growth_inside_1_x64,
+        reward_growths_inside,
+    )?;
+
+    personal_position.token_fees_owed_0 = calculate_latest_token_fees(
+        personal_position.token_fees_owed_0,
+        personal_position.fee_growth_inside_0_last_x64,
+        protocol_position.fee_growth_inside_0_last_x64,
+        personal_position.liquidity,
+    );
+    personal_position.token_fees_owed_1 = calculate_latest_token_fees(
+        personal_position.token_fees_owed_1,
+        personal_position.fee_growth_inside_1_last_x64,
+        protocol_position.fee_growth_inside_1_last_x64,
+        personal_position.liquidity,
+    );
+    personal_position.fee_growth_inside_0_last_x64 = protocol_position.fee_growth_inside_0_last_x64;
+    personal_position.fee_growth_inside_1_last_x64 = protocol_position.fee_growth_inside_1_last_x64;
+    personal_position.update_rewards(
+        protocol_position.reward_growth_inside,
+        pool_state.reward_generations,
+        true,
+    )
+}
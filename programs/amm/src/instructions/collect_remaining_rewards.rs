@@ -47,8 +47,8 @@ pub struct CollectRemainingRewards<'info> {
     pub memo_program: Program<'info, Memo>,
 }
 
-pub fn collect_remaining_rewards(
-    ctx: Context<CollectRemainingRewards>,
+pub fn collect_remaining_rewards<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CollectRemainingRewards<'info>>,
     reward_index: u8,
 ) -> Result<()> {
     // invoke_memo_instruction(
@@ -56,18 +56,41 @@ pub fn collect_remaining_rewards(
     //     ctx.accounts.memo_program.to_account_info(),
     // )?;
 
+    let is_reward_manager = ctx.accounts.reward_funder.key()
+        == ctx.accounts.pool_state.load()?.owner
+        || ctx.accounts.admin_group.reward_config_manager == ctx.accounts.reward_funder.key();
+
+    // the share of a third-party funder is refunded with its funder account
+    if let Some(reward_funder_state_info) = ctx.remaining_accounts.first() {
+        return refund_reward_funds(
+            ctx.accounts,
+            reward_funder_state_info,
+            reward_index,
+            is_reward_manager,
+        );
+    }
+
     // only pool owner or reward-manager can collect remaining rewards
-    if ctx.accounts.reward_funder.key() != ctx.accounts.pool_state.load()?.owner
-        && ctx.accounts.admin_group.reward_config_manager != ctx.accounts.reward_funder.key()
-    {
+    if !is_reward_manager {
         return err!(ErrorCode::NotApproved);
     }
 
-    let amount_remaining = get_remaining_reward_amount(
+    let mut amount_remaining = get_remaining_reward_amount(
         &ctx.accounts.pool_state,
         &ctx.accounts.reward_token_vault,
         reward_index,
+        false,
     )?;
+    {
+        // the share of the third-party funders not refunded yet is reserved
+        let pool_state = ctx.accounts.pool_state.load()?;
+        let index = reward_index as usize;
+        amount_remaining -= pool_state.get_remaining_reward_share(
+            index,
+            amount_remaining,
+            pool_state.reward_pending_contributed[index],
+        );
+    }
 
     transfer_from_pool_vault_to_user(
         &ctx.accounts.pool_state,
@@ -77,15 +100,95 @@ pub fn collect_remaining_rewards(
         &ctx.accounts.token_program,
         Some(ctx.accounts.token_program_2022.to_account_info()),
         amount_remaining,
+        &[],
     )?;
 
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.reward_refunded[reward_index as usize] = pool_state.reward_refunded
+        [reward_index as usize]
+        .checked_add(amount_remaining)
+        .ok_or(ErrorCode::CalculateOverflow)?;
+
     Ok(())
 }
 
-fn get_remaining_reward_amount(
+/// Refunds the share of the remaining reward to the third-party funder, triggered by the funder
+/// or the reward manager, the share is always transferred to the token account of the funder.
+fn refund_reward_funds<'info>(
+    accounts: &CollectRemainingRewards<'info>,
+    reward_funder_state_info: &'info AccountInfo<'info>,
+    reward_index: u8,
+    is_reward_manager: bool,
+) -> Result<()> {
+    let mut reward_funder_state = Account::<RewardFunderState>::try_from(reward_funder_state_info)?;
+    require_keys_eq!(reward_funder_state.pool_state, accounts.pool_state.key());
+    require_eq!(reward_funder_state.reward_index, reward_index);
+    require!(
+        is_reward_manager || accounts.reward_funder.key() == reward_funder_state.funder,
+        ErrorCode::NotApproved
+    );
+    require_keys_eq!(
+        accounts.funder_token_account.key(),
+        reward_funder_state.funder_token_account
+    );
+    {
+        let pool_state = accounts.pool_state.load()?;
+        require_eq!(
+            reward_funder_state.reward_generation,
+            pool_state.reward_generations[reward_index as usize],
+            ErrorCode::InvalidRewardIndex
+        );
+    }
+
+    let amount_remaining = get_remaining_reward_amount(
+        &accounts.pool_state,
+        &accounts.reward_token_vault,
+        reward_index,
+        false,
+    )?;
+    let contributed_amount = reward_funder_state.contributed_amount;
+    let amount_refunded = {
+        let mut pool_state = accounts.pool_state.load_mut()?;
+        let index = reward_index as usize;
+        let amount_refunded =
+            pool_state.get_remaining_reward_share(index, amount_remaining, contributed_amount);
+        pool_state.reward_pending_contributed[index] = pool_state.reward_pending_contributed[index]
+            .checked_sub(contributed_amount)
+            .unwrap();
+        pool_state.reward_refunded[index] = pool_state.reward_refunded[index]
+            .checked_add(amount_refunded)
+            .ok_or(ErrorCode::CalculateOverflow)?;
+        amount_refunded
+    };
+    reward_funder_state.contributed_amount = 0;
+    reward_funder_state.exit(&crate::id())?;
+
+    transfer_from_pool_vault_to_user(
+        &accounts.pool_state,
+        &accounts.reward_token_vault.to_account_info(),
+        &accounts.funder_token_account.to_account_info(),
+        Some(accounts.reward_vault_mint.clone()),
+        &accounts.token_program,
+        Some(accounts.token_program_2022.to_account_info()),
+        amount_refunded,
+        &[],
+    )?;
+
+    emit!(RefundRewardFundsEvent {
+        pool_state: accounts.pool_state.key(),
+        reward_index,
+        funder: reward_funder_state.funder,
+        contributed_amount,
+        amount_refunded,
+    });
+    Ok(())
+}
+
+pub(crate) fn get_remaining_reward_amount(
     pool_state_loader: &AccountLoader<PoolState>,
     reward_token_vault: &InterfaceAccount<TokenAccount>,
     reward_index: u8,
+    recycle: bool,
 ) -> Result<u64> {
     let current_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
     let mut pool_state = pool_state_loader.load_mut()?;
@@ -102,6 +205,24 @@ fn get_remaining_reward_amount(
     );
     require_keys_eq!(reward_token_vault.key(), reward_info.token_vault);
 
+    if recycle {
+        // the reward owed and not claimed by the positions in the claim period is forfeited
+        require_gte!(
+            current_timestamp,
+            reward_info
+                .end_time
+                .checked_add(reward_period_limit::REWARD_CLAIM_PERIOD)
+                .unwrap(),
+            ErrorCode::RewardClaimPeriodNotEnded
+        );
+        require_eq!(
+            pool_state.reward_pending_contributed[reward_index as usize],
+            0,
+            ErrorCode::RewardFundsNotRefunded
+        );
+        return Ok(reward_token_vault.amount);
+    }
+
     let amount_remaining = reward_token_vault
         .amount
         .checked_sub(
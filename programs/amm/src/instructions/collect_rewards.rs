@@ -0,0 +1,96 @@
+use super::collect_fees::update_fees_and_rewards_owed;
+use super::decrease_liquidity::collect_rewards;
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct CollectRewards<'info> {
+    /// The position owner or delegated authority
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+        token::authority = nft_owner,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Collect rewards for this position
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Stores init state for the lower tick, only read
+    #[account(constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the upper tick, only read
+    #[account(constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+    // remaining accounts, for each initialized reward:
+    // reward_token_vault, recipient_token_account (can be owned by anyone), reward_vault_mint
+}
+
+pub fn collect_personal_rewards<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CollectRewards<'info>>,
+    amounts_requested: [u64; REWARD_NUM],
+) -> Result<()> {
+    if !ctx
+        .accounts
+        .pool_state
+        .load()?
+        .get_status_by_bit(PoolStatusBitIndex::CollectReward)
+    {
+        return err!(ErrorCode::NotApproved);
+    }
+    update_fees_and_rewards_owed(
+        &ctx.accounts.pool_state,
+        &mut ctx.accounts.protocol_position,
+        &mut ctx.accounts.personal_position,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+    )?;
+
+    let remaining_accounts = ctx.remaining_accounts.iter().collect::<Vec<_>>();
+    let reward_amounts = collect_rewards(
+        &ctx.accounts.pool_state,
+        remaining_accounts.as_slice(),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        &mut ctx.accounts.personal_position,
+        true,
+        amounts_requested,
+    )?;
+
+    emit!(CollectPersonalRewardEvent {
+        position_nft_mint: ctx.accounts.personal_position.nft_mint,
+        reward_amounts,
+    });
+
+    Ok(())
+}
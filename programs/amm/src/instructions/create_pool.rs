@@ -69,7 +69,7 @@ pub struct CreatePool<'info> {
     )]
     pub token_mint_1: Box<InterfaceAccount<'info, Mint>>,
 
-    /// Token_0 vault for the pool
+    /// Token_0 vault for the pool, public balance only for the confidential transfer mints
     #[account(
         init,
         seeds =[
@@ -85,7 +85,7 @@ pub struct CreatePool<'info> {
     )]
     pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Token_1 vault for the pool
+    /// Token_1 vault for the pool, public balance only for the confidential transfer mints
     #[account(
         init,
         seeds =[
@@ -155,7 +155,39 @@ pub struct CreatePool<'info> {
     // pub support_mint1_associated: Account<'info, SupportMintAssociated>,
 }
 
-pub fn create_pool(ctx: Context<CreatePool>, sqrt_price_x64: u128, open_time: u64) -> Result<()> {
+#[derive(Copy, Clone, AnchorSerialize, AnchorDeserialize, Debug, PartialEq)]
+pub struct LaunchFeeParam {
+    /// The trade fee rate at the open time, denominated in hundredths of a bip (10^-6)
+    pub start_fee_rate: u32,
+    /// The seconds after the open time until the fee is back to the normal fee rate
+    pub duration: u32,
+    /// 0: linear decay, 1: exponential decay with a half-life of `duration / LAUNCH_FEE_HALF_LIVES` seconds
+    pub decay_mode: u8,
+    /// The receiver of the fee above the normal fee rate, 0: liquidity providers, 1: fund fee, 2: protocol fee.
+    /// The liquidity providers get all of the excess fee, it's not split with the protocol and fund.
+    pub excess_fee_destination: u8,
+}
+
+impl LaunchFeeParam {
+    pub fn check(&self) -> Result<()> {
+        if self.start_fee_rate == 0
+            || self.start_fee_rate >= FEE_RATE_DENOMINATOR_VALUE
+            || self.duration == 0
+            || self.decay_mode > LaunchFeeSchedule::DECAY_MODE_EXPONENTIAL
+            || self.excess_fee_destination > LaunchFeeSchedule::DESTINATION_PROTOCOL_FEE
+        {
+            return err!(ErrorCode::InvalidLaunchFeeParam);
+        }
+        Ok(())
+    }
+}
+
+pub fn create_pool(
+    ctx: Context<CreatePool>,
+    sqrt_price_x64: u128,
+    open_time: u64,
+    launch_fee_param: Option<LaunchFeeParam>,
+) -> Result<()> {
     let mint0_associated_is_initialized = util::support_mint_associated_is_initialized(
         &ctx.remaining_accounts,
         &ctx.accounts.token_mint_0,
@@ -216,6 +248,16 @@ pub fn create_pool(ctx: Context<CreatePool>, sqrt_price_x64: u128, open_time: u6
         ctx.accounts.token_mint_1.as_ref(),
         ctx.accounts.observation_state.key(),
     )?;
+    if let Some(param) = launch_fee_param {
+        param.check()?;
+        pool_state.launch_fee = LaunchFeeSchedule {
+            start_fee_rate: param.start_fee_rate,
+            duration: param.duration,
+            decay_mode: param.decay_mode,
+            excess_fee_destination: param.excess_fee_destination,
+            ..Default::default()
+        };
+    }
 
     ctx.accounts
         .tick_array_bitmap
@@ -2,7 +2,7 @@ use super::calculate_latest_token_fees;
 use super::modify_position;
 use crate::error::ErrorCode;
 use crate::states::*;
-use crate::util::{self, transfer_from_pool_vault_to_user};
+use crate::util::{self, transfer_from_pool_vault_to_user, NativeAccounts};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 use anchor_spl::token_2022::spl_token_2022;
@@ -101,6 +101,7 @@ pub fn decrease_liquidity_v1<'a, 'b, 'c: 'info, 'info>(
     amount_1_min: u64,
 ) -> Result<()> {
     decrease_liquidity(
+        &ctx.accounts.nft_owner,
         &ctx.accounts.pool_state,
         &mut ctx.accounts.protocol_position,
         &mut ctx.accounts.personal_position,
@@ -123,6 +124,7 @@ pub fn decrease_liquidity_v1<'a, 'b, 'c: 'info, 'info>(
 }
 
 pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
+    nft_owner: &'b Signer<'info>,
     pool_state_loader: &'b AccountLoader<'info, PoolState>,
     protocol_position: &'b mut Box<Account<'info, ProtocolPositionState>>,
     personal_position: &'b mut Box<Account<'info, PersonalPositionState>>,
@@ -152,6 +154,7 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
     let pool_tick_current;
     let mut tickarray_bitmap_extension = None;
 
+    let native_accounts = NativeAccounts::load(nft_owner, None, remaining_accounts);
     let remaining_collect_accounts = &mut Vec::new();
     {
         let pool_state = pool_state_loader.load()?;
@@ -178,6 +181,9 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
                 tickarray_bitmap_extension = Some(account_info);
                 continue;
             }
+            if native_accounts.contains(account_info.key) {
+                continue;
+            }
             remaining_collect_accounts.push(account_info);
         }
         if use_tickarray_bitmap_extension {
@@ -250,7 +256,7 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
         token_2022_program_opt = Some(token_program_2022.clone().unwrap().to_account_info());
     }
 
-    transfer_from_pool_vault_to_user(
+    native_accounts.transfer_from_pool_vault_to_user(
         pool_state_loader,
         &token_vault_0.to_account_info(),
         recipient_token_account_0,
@@ -258,9 +264,10 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
         token_program,
         token_2022_program_opt.clone(),
         transfer_amount_0,
+        remaining_accounts,
     )?;
 
-    transfer_from_pool_vault_to_user(
+    native_accounts.transfer_from_pool_vault_to_user(
         pool_state_loader,
         &token_vault_1.to_account_info(),
         recipient_token_account_1,
@@ -268,6 +275,7 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
         token_program,
         token_2022_program_opt.clone(),
         transfer_amount_1,
+        remaining_accounts,
     )?;
 
     check_unclaimed_fees_and_vault(pool_state_loader, token_vault_0, token_vault_1)?;
@@ -283,6 +291,7 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
         } else {
             true
         },
+        [u64::MAX; REWARD_NUM],
     )?;
     emit!(DecreaseLiquidityEvent {
         position_nft_mint: personal_position.nft_mint,
@@ -341,7 +350,11 @@ pub fn decrease_liquidity_and_update_position<'a, 'b, 'c: 'info, 'info>(
             protocol_position.fee_growth_inside_1_last_x64;
 
         // update rewards, must update before decrease liquidity
-        personal_position.update_rewards(protocol_position.reward_growth_inside, true)?;
+        personal_position.update_rewards(
+            protocol_position.reward_growth_inside,
+            pool_state.reward_generations,
+            true,
+        )?;
         personal_position.liquidity = personal_position.liquidity.checked_sub(liquidity).unwrap();
     }
 
@@ -461,6 +474,7 @@ pub fn collect_rewards<'a, 'b, 'c, 'info>(
     token_program_2022: Option<AccountInfo<'info>>,
     personal_position_state: &mut PersonalPositionState,
     need_reward_mint: bool,
+    amounts_requested: [u64; REWARD_NUM],
 ) -> Result<[u64; REWARD_NUM]> {
     let mut reward_amounts: [u64; REWARD_NUM] = [0, 0, 0];
     if !pool_state_loader
@@ -473,15 +487,23 @@ pub fn collect_rewards<'a, 'b, 'c, 'info>(
     if !need_reward_mint {
         reward_group_account_num = reward_group_account_num - 1
     }
+    // the reward accounts can be followed by the transfer hook accounts of the pool tokens
     check_required_accounts_length(
         pool_state_loader,
         remaining_accounts,
         reward_group_account_num,
     )?;
 
-    let remaining_accounts_len = remaining_accounts.len();
+    let (reward_infos, reward_generations) = {
+        let pool_state = pool_state_loader.load()?;
+        (pool_state.reward_infos, pool_state.reward_generations)
+    };
     let mut remaining_accounts = remaining_accounts.iter();
-    for i in 0..remaining_accounts_len / reward_group_account_num {
+    for i in 0..REWARD_NUM {
+        // a recycled slot stays uninitialized until the next reward, no accounts are passed for it
+        if !reward_infos[i].initialized() {
+            continue;
+        }
         let reward_token_vault = InterfaceAccount::<token_interface::TokenAccount>::try_from(
             remaining_accounts.next().unwrap(),
         )?;
@@ -501,6 +523,10 @@ pub fn collect_rewards<'a, 'b, 'c, 'info>(
             pool_state_loader.load_mut()?.reward_infos[i].token_vault
         );
 
+        // the reward owed of a recycled slot belongs to the previous reward
+        if personal_position_state.reward_generations[i] != reward_generations[i] {
+            continue;
+        }
         let reward_amount_owed = personal_position_state.reward_infos[i].reward_amount_owed;
         if reward_amount_owed == 0 {
             continue;
@@ -513,7 +539,8 @@ pub fn collect_rewards<'a, 'b, 'c, 'info>(
             reward_token_vault.amount
         } else {
             reward_amount_owed
-        };
+        }
+        .min(amounts_requested[i]);
 
         if transfer_amount > 0 {
             msg!(
@@ -536,6 +563,7 @@ pub fn collect_rewards<'a, 'b, 'c, 'info>(
                 &token_program,
                 token_program_2022.clone(),
                 transfer_amount,
+                &[],
             )?;
         }
         reward_amounts[i] = transfer_amount
@@ -548,7 +576,7 @@ fn check_required_accounts_length(
     pool_state_loader: &AccountLoader<PoolState>,
     remaining_accounts: &[&AccountInfo],
     reward_group_account_num: usize,
-) -> Result<()> {
+) -> Result<usize> {
     let pool_state = pool_state_loader.load()?;
     let mut valid_reward_count = 0;
     for item in pool_state.reward_infos {
@@ -556,11 +584,11 @@ fn check_required_accounts_length(
             valid_reward_count = valid_reward_count + 1;
         }
     }
-    let remaining_accounts_len = remaining_accounts.len();
-    if remaining_accounts_len != valid_reward_count * reward_group_account_num {
+    let reward_accounts_len = valid_reward_count * reward_group_account_num;
+    if remaining_accounts.len() < reward_accounts_len {
         return err!(ErrorCode::InvalidRewardInputAccountNumber);
     }
-    Ok(())
+    Ok(reward_accounts_len)
 }
 
 pub fn check_unclaimed_fees_and_vault(
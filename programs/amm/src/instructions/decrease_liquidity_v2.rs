@@ -1,5 +1,6 @@
 use super::decrease_liquidity::decrease_liquidity;
 use crate::states::*;
+use crate::util::check_deadline;
 use anchor_lang::prelude::*;
 use anchor_spl::memo::spl_memo;
 use anchor_spl::token::Token;
@@ -60,19 +61,15 @@ pub struct DecreaseLiquidityV2<'info> {
     #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
     pub tick_array_upper: AccountLoader<'info, TickArrayState>,
 
-    /// The destination token account for receive amount_0
-    #[account(
-        mut,
-        token::mint = token_vault_0.mint
-    )]
-    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: The destination token account for receive amount_0, validated by the token program on transfer.
+    /// If token_0 is the native mint, it can be the nft_owner itself to unwrap SOL.
+    #[account(mut)]
+    pub recipient_token_account_0: UncheckedAccount<'info>,
 
-    /// The destination token account for receive amount_1
-    #[account(
-        mut,
-        token::mint = token_vault_1.mint
-    )]
-    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: The destination token account for receive amount_1, validated by the token program on transfer.
+    /// If token_1 is the native mint, it can be the nft_owner itself to unwrap SOL.
+    #[account(mut)]
+    pub recipient_token_account_1: UncheckedAccount<'info>,
 
     /// SPL program to transfer out tokens
     pub token_program: Program<'info, Token>,
@@ -106,6 +103,7 @@ pub struct DecreaseLiquidityV2<'info> {
     //     bump
     // )]
     // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    // system_program and native temp account: must add accounts if unwrap SOL regardless the sequence
 }
 
 pub fn decrease_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
@@ -113,8 +111,11 @@ pub fn decrease_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
     liquidity: u128,
     amount_0_min: u64,
     amount_1_min: u64,
+    deadline: Option<i64>,
 ) -> Result<()> {
+    check_deadline(deadline)?;
     decrease_liquidity(
+        &ctx.accounts.nft_owner,
         &ctx.accounts.pool_state,
         &mut ctx.accounts.protocol_position,
         &mut ctx.accounts.personal_position,
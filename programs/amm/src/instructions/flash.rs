@@ -0,0 +1,210 @@
+use crate::error::ErrorCode;
+use crate::libraries::{big_num::U128, full_math::MulDiv};
+use crate::states::*;
+use crate::util::transfer_from_pool_vault_to_user;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct Flash<'info> {
+    /// The user borrowing the tokens
+    pub payer: Signer<'info>,
+
+    /// The factory state to read the fee rates
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The pool to borrow from
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account to receive the borrowed token_0
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account to receive the borrowed token_1
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: The program invoked after the tokens are lent, it must repay the vaults with fee.
+    /// The remaining accounts are passed to it in order, with their signer and writable flags.
+    #[account(
+        executable,
+        constraint = callback_program.key() != crate::id() @ ErrorCode::InvalidAccount
+    )]
+    pub callback_program: UncheckedAccount<'info>,
+}
+
+pub fn flash<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, Flash<'info>>,
+    amount_0: u64,
+    amount_1: u64,
+    data: Vec<u8>,
+) -> Result<()> {
+    require!(amount_0 > 0 || amount_1 > 0, ErrorCode::ZeroAmountSpecified);
+    let trade_fee_rate;
+    {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        // flash loans are disabled unless enabled by the amm config, the pool status can disable them per pool
+        if ctx.accounts.amm_config.flash_enabled == 0
+            || !pool_state.get_status_by_bit(PoolStatusBitIndex::Flash)
+        {
+            return err!(ErrorCode::NotApproved);
+        }
+        // the borrowed tokens go to the owners of the recipient accounts
+        for recipient in [
+            ctx.accounts.recipient_token_account_0.owner,
+            ctx.accounts.recipient_token_account_1.owner,
+        ] {
+            check_pool_permission(
+                pool_state.is_permissioned(),
+                ctx.accounts.pool_state.key(),
+                &recipient,
+                ctx.remaining_accounts,
+            )?;
+        }
+        trade_fee_rate = pool_state.get_base_fee_rate(ctx.accounts.amm_config.trade_fee_rate);
+    }
+    let fee_0 = get_flash_fee(amount_0, trade_fee_rate);
+    let fee_1 = get_flash_fee(amount_1, trade_fee_rate);
+
+    let vault_0_balance_before = ctx.accounts.token_vault_0.amount;
+    let vault_1_balance_before = ctx.accounts.token_vault_1.amount;
+
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_0,
+        ctx.remaining_accounts,
+    )?;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_1,
+        ctx.remaining_accounts,
+    )?;
+
+    // the callback program can not reenter this program, it is not allowed by the runtime
+    let callback_instruction = Instruction {
+        program_id: ctx.accounts.callback_program.key(),
+        accounts: ctx
+            .remaining_accounts
+            .iter()
+            .map(|account_info| AccountMeta {
+                pubkey: account_info.key(),
+                is_signer: account_info.is_signer,
+                is_writable: account_info.is_writable,
+            })
+            .collect(),
+        data,
+    };
+    invoke(&callback_instruction, ctx.remaining_accounts)?;
+
+    ctx.accounts.token_vault_0.reload()?;
+    ctx.accounts.token_vault_1.reload()?;
+    let paid_0 = ctx
+        .accounts
+        .token_vault_0
+        .amount
+        .checked_sub(vault_0_balance_before)
+        .ok_or(ErrorCode::FlashLoanNotRepaid)?;
+    let paid_1 = ctx
+        .accounts
+        .token_vault_1
+        .amount
+        .checked_sub(vault_1_balance_before)
+        .ok_or(ErrorCode::FlashLoanNotRepaid)?;
+    require_gte!(paid_0, fee_0, ErrorCode::FlashLoanNotRepaid);
+    require_gte!(paid_1, fee_1, ErrorCode::FlashLoanNotRepaid);
+
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    pool_state.add_flash_fee(&ctx.accounts.amm_config, paid_0, true);
+    pool_state.add_flash_fee(&ctx.accounts.amm_config, paid_1, false);
+
+    emit!(FlashEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        sender: ctx.accounts.payer.key(),
+        amount_0,
+        amount_1,
+        paid_0,
+        paid_1,
+    });
+
+    Ok(())
+}
+
+/// The fee of a flash loan, rounded up
+pub fn get_flash_fee(amount: u64, trade_fee_rate: u32) -> u64 {
+    U128::from(amount)
+        .mul_div_ceil(
+            U128::from(trade_fee_rate),
+            U128::from(FEE_RATE_DENOMINATOR_VALUE),
+        )
+        .unwrap()
+        .as_u64()
+}
+
+#[cfg(test)]
+mod flash_test {
+    use super::*;
+
+    #[test]
+    fn get_flash_fee_round_up_test() {
+        assert_eq!(get_flash_fee(0, 2500), 0);
+        assert_eq!(get_flash_fee(1, 2500), 1);
+        assert_eq!(get_flash_fee(1_000_000, 2500), 2500);
+        assert_eq!(get_flash_fee(1_000_001, 2500), 2501);
+        assert_eq!(get_flash_fee(u64::MAX, 0), 0);
+    }
+}
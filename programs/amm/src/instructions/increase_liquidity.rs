@@ -146,6 +146,12 @@ pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
     if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity) {
         return err!(ErrorCode::NotApproved);
     }
+    check_pool_permission(
+        pool_state.is_permissioned(),
+        pool_state_loader.key(),
+        nft_owner.key,
+        remaining_accounts,
+    )?;
     let tick_lower = personal_position.tick_lower_index;
     let tick_upper = personal_position.tick_upper_index;
 
@@ -153,7 +159,7 @@ pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
         pool_state.is_overflow_default_tickarray_bitmap(vec![tick_lower, tick_upper]);
 
     let (amount_0, amount_1, amount_0_transfer_fee, amount_1_transfer_fee) = add_liquidity(
-        &nft_owner,
+        &NativeAccounts::load(nft_owner, None, remaining_accounts),
         token_account_0,
         token_account_1,
         token_vault_0,
@@ -181,6 +187,7 @@ pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
         tick_lower,
         tick_upper,
         base_flag,
+        remaining_accounts,
     )?;
 
     personal_position.token_fees_owed_0 = calculate_latest_token_fees(
@@ -200,7 +207,11 @@ pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
     personal_position.fee_growth_inside_1_last_x64 = protocol_position.fee_growth_inside_1_last_x64;
 
     // update rewards, must update before increase liquidity
-    personal_position.update_rewards(protocol_position.reward_growth_inside, true)?;
+    personal_position.update_rewards(
+        protocol_position.reward_growth_inside,
+        pool_state.reward_generations,
+        true,
+    )?;
     personal_position.liquidity = personal_position.liquidity.checked_add(liquidity).unwrap();
 
     emit!(IncreaseLiquidityEvent {
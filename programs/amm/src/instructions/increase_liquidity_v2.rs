@@ -1,5 +1,6 @@
 use super::increase_liquidity::increase_liquidity;
 use crate::states::*;
+use crate::util::check_deadline;
 use anchor_lang::prelude::*;
 use anchor_spl::token::Token;
 use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
@@ -45,19 +46,15 @@ pub struct IncreaseLiquidityV2<'info> {
     #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
     pub tick_array_upper: AccountLoader<'info, TickArrayState>,
 
-    /// The payer's token account for token_0
-    #[account(
-        mut,
-        token::mint = token_vault_0.mint
-    )]
-    pub token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: The payer's token account for token_0, validated by the token program on transfer.
+    /// If token_0 is the native mint, it can be the nft_owner itself to wrap SOL.
+    #[account(mut)]
+    pub token_account_0: UncheckedAccount<'info>,
 
-    /// The token account spending token_1 to mint the position
-    #[account(
-        mut,
-        token::mint = token_vault_1.mint
-    )]
-    pub token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: The token account spending token_1 to mint the position, validated by the token program on transfer.
+    /// If token_1 is the native mint, it can be the nft_owner itself to wrap SOL.
+    #[account(mut)]
+    pub token_account_1: UncheckedAccount<'info>,
 
     /// The address that holds pool tokens for token_0
     #[account(
@@ -99,6 +96,7 @@ pub struct IncreaseLiquidityV2<'info> {
     //     bump
     // )]
     // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    // system_program: must be added at any position if wrap SOL, it is found by key
 }
 
 pub fn increase_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
@@ -107,7 +105,9 @@ pub fn increase_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
     amount_0_max: u64,
     amount_1_max: u64,
     base_flag: Option<bool>,
+    deadline: Option<i64>,
 ) -> Result<()> {
+    check_deadline(deadline)?;
     increase_liquidity(
         &ctx.accounts.nft_owner,
         &ctx.accounts.pool_state,
@@ -0,0 +1,76 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+use anchor_lang::system_program;
+
+#[derive(Accounts)]
+pub struct IncreaseObservationCardinality<'info> {
+    /// Pays the rent of the grown observation account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The program account for the oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows the observation account of the pool to hold `observation_cardinality_next` observations,
+/// the ring buffer uses the new observations the next time it wraps around.
+pub fn increase_observation_cardinality(
+    ctx: Context<IncreaseObservationCardinality>,
+    observation_cardinality_next: u16,
+) -> Result<()> {
+    let cardinality_next = observation_cardinality_next as usize;
+    {
+        let mut observation_state = ctx.accounts.observation_state.load_mut()?;
+        require_keys_eq!(observation_state.pool_id, ctx.accounts.pool_state.key());
+        require_gt!(
+            cardinality_next,
+            observation_state.get_cardinality_next(),
+            ErrorCode::InvalidObservationCardinality
+        );
+        require_gte!(
+            OBSERVATION_CARDINALITY_MAX,
+            observation_cardinality_next,
+            ErrorCode::InvalidObservationCardinality
+        );
+        observation_state.observation_cardinality_next = observation_cardinality_next;
+    }
+
+    let observation_info = ctx.accounts.observation_state.to_account_info();
+    let current_account_size = observation_info.data_len();
+    let new_size = ObservationState::need_len(cardinality_next);
+    if current_account_size >= new_size {
+        return Ok(());
+    }
+    // the account can only grow by `MAX_PERMITTED_DATA_INCREASE` bytes in one instruction
+    require_gte!(
+        current_account_size + MAX_PERMITTED_DATA_INCREASE,
+        new_size,
+        ErrorCode::InvalidObservationCardinality
+    );
+    observation_info.realloc(new_size, true)?;
+
+    // If more lamports are needed, transfer them to the account.
+    let rent_exempt_lamports = Rent::get()?.minimum_balance(new_size);
+    let top_up_lamports = rent_exempt_lamports.saturating_sub(observation_info.lamports());
+    if top_up_lamports > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: observation_info,
+                },
+            ),
+            top_up_lamports,
+        )?;
+    }
+
+    Ok(())
+}
@@ -47,9 +47,9 @@ pub struct InitializeReward<'info> {
     /// Reward mint
     pub reward_token_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    /// A pda, reward vault
+    /// A pda, reward vault, reused if the reward mint was used by a recycled slot
     #[account(
-        init,
+        init_if_needed,
         seeds =[
             POOL_REWARD_VAULT_SEED.as_bytes(),
             pool_state.key().as_ref(),
@@ -104,21 +104,70 @@ impl InitializeRewardParam {
         }
         Ok(())
     }
+
+    /// Get the reward schedule of the phases, the phases must cover the reward period exactly
+    pub fn get_reward_schedule(&self, phases: &[RewardSchedulePhase]) -> Result<RewardSchedule> {
+        let mut reward_schedule = RewardSchedule::default();
+        if phases.is_empty() {
+            return Ok(reward_schedule);
+        }
+        require_gte!(
+            REWARD_SCHEDULE_PHASE_NUM,
+            phases.len(),
+            ErrorCode::InvalidRewardSchedule
+        );
+        if phases.iter().any(|phase| phase.duration == 0)
+            || phases.iter().all(|phase| phase.rate == 0)
+        {
+            return err!(ErrorCode::InvalidRewardSchedule);
+        }
+        reward_schedule.phase_count = phases.len() as u8;
+        for (i, phase) in phases.iter().enumerate() {
+            reward_schedule.phase_durations[i] = phase.duration;
+            reward_schedule.phase_rates[i] = phase.rate;
+        }
+        require_eq!(
+            reward_schedule.get_duration(),
+            self.end_time - self.open_time,
+            ErrorCode::InvalidRewardSchedule
+        );
+        Ok(reward_schedule)
+    }
+}
+
+#[derive(Copy, Clone, AnchorSerialize, AnchorDeserialize, Debug, PartialEq)]
+pub struct RewardSchedulePhase {
+    /// The seconds of the phase
+    pub duration: u32,
+    /// The emission rate of the phase relative to `emissions_per_second_x64`, denominated in 10^-6
+    pub rate: u32,
 }
 
 pub fn initialize_reward(
     ctx: Context<InitializeReward>,
     param: InitializeRewardParam,
+) -> Result<()> {
+    initialize_reward_with_schedule(ctx, param, Vec::new())
+}
+
+/// Initializes a reward emitting in phases, the whole schedule is funded at once.
+/// A reward without phases emits `emissions_per_second_x64` from the open time to the end time.
+pub fn initialize_reward_with_schedule(
+    ctx: Context<InitializeReward>,
+    param: InitializeRewardParam,
+    phases: Vec<RewardSchedulePhase>,
 ) -> Result<()> {
     let mint_associated_is_initialized = util::support_mint_associated_is_initialized(
         &ctx.remaining_accounts,
         &ctx.accounts.reward_token_mint,
     )?;
+    // the reward transfers don't carry the transfer hook accounts
     if !util::is_supported_mint(
         &ctx.accounts.reward_token_mint,
         mint_associated_is_initialized,
     )
     .unwrap()
+        || util::is_transfer_hook_mint(&ctx.accounts.reward_token_mint)?
     {
         return err!(ErrorCode::NotSupportMint);
     }
@@ -135,12 +184,16 @@ pub fn initialize_reward(
     #[cfg(feature = "enable-log")]
     msg!("current block timestamp:{}", clock.unix_timestamp);
     param.check(clock.unix_timestamp as u64)?;
-
-    let reward_amount = U256::from(param.end_time - param.open_time)
-        .mul_div_ceil(
-            U256::from(param.emissions_per_second_x64),
-            U256::from(fixed_point_64::Q64),
+    let reward_schedule = param.get_reward_schedule(&phases)?;
+
+    let reward_amount = reward_schedule
+        .get_emissions_x64(
+            param.emissions_per_second_x64,
+            param.open_time,
+            param.open_time,
+            param.end_time,
         )
+        .mul_div_ceil(U256::one(), U256::from(fixed_point_64::Q64))
         .unwrap()
         .as_u64();
     let transfer_fee =
@@ -153,10 +206,11 @@ pub fn initialize_reward(
     );
 
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
-    pool_state.initialize_reward(
+    let reward_index = pool_state.initialize_reward(
         param.open_time,
         param.end_time,
         param.emissions_per_second_x64,
+        reward_schedule,
         &ctx.accounts.reward_token_mint.key(),
         ctx.accounts.reward_token_mint.freeze_authority,
         &ctx.accounts.reward_token_vault.key(),
@@ -164,6 +218,7 @@ pub fn initialize_reward(
         ctx.accounts.reward_funder.key() == ctx.accounts.admin_group.reward_config_manager,
         &operation_state,
     )?;
+    pool_state.reward_total_funded[reward_index] = reward_amount;
 
     transfer_from_user_to_pool_vault(
         &ctx.accounts.reward_funder,
@@ -173,6 +228,7 @@ pub fn initialize_reward(
         &ctx.accounts.reward_token_program.to_account_info(),
         Some(ctx.accounts.reward_token_program.to_account_info()),
         reward_amount_with_transfer_fee,
+        &[],
     )?;
 
     Ok(())
@@ -25,6 +25,15 @@ pub use decrease_liquidity::*;
 pub mod decrease_liquidity_v2;
 pub use decrease_liquidity_v2::*;
 
+pub mod reposition;
+pub use reposition::*;
+
+pub mod collect_fees;
+pub use collect_fees::*;
+
+pub mod collect_rewards;
+pub use collect_rewards::*;
+
 pub mod swap;
 pub use swap::*;
 
@@ -34,6 +43,27 @@ pub use swap_v2::*;
 pub mod swap_router_base_in;
 pub use swap_router_base_in::*;
 
+pub mod swap_router_base_out;
+pub use swap_router_base_out::*;
+
+pub mod flash;
+pub use flash::*;
+
+pub mod open_limit_order;
+pub use open_limit_order::*;
+
+pub mod close_limit_order;
+pub use close_limit_order::*;
+
+pub mod observe_twap;
+pub use observe_twap::*;
+
+pub mod increase_observation_cardinality;
+pub use increase_observation_cardinality::*;
+
+pub mod snapshot_cumulatives_inside;
+pub use snapshot_cumulatives_inside::*;
+
 pub mod update_reward_info;
 pub use update_reward_info::*;
 
@@ -46,5 +76,14 @@ pub use set_reward_params::*;
 pub mod collect_remaining_rewards;
 pub use collect_remaining_rewards::*;
 
+pub mod recycle_reward;
+pub use recycle_reward::*;
+
+pub mod add_reward_funds;
+pub use add_reward_funds::*;
+
+pub mod claim_offchain_reward_with_proof;
+pub use claim_offchain_reward_with_proof::*;
+
 pub mod admin;
 pub use admin::*;
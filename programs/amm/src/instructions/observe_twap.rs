@@ -0,0 +1,48 @@
+use crate::libraries::tick_math;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ObserveTwap<'info> {
+    /// The pool to query the time weighted average price
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The program account for the oracle observation
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+#[derive(Copy, Clone, AnchorSerialize, AnchorDeserialize, Debug, PartialEq)]
+pub struct TwapResult {
+    /// The time weighted average tick of the window
+    pub tick: i32,
+    /// The sqrt price at the time weighted average tick, as a Q64.64
+    pub sqrt_price_x64: u128,
+    /// The start timestamp of the window
+    pub start_time: u32,
+    /// The end timestamp of the window
+    pub end_time: u32,
+}
+
+/// Returns the time weighted average tick and price of the pool over the last `seconds_ago` seconds
+pub fn observe_twap(ctx: Context<ObserveTwap>, seconds_ago: u32) -> Result<TwapResult> {
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let (observation_state, observation_extension) =
+        load_observation_state(&ctx.accounts.observation_state)?;
+    require_keys_eq!(observation_state.pool_id, ctx.accounts.pool_state.key());
+
+    let block_timestamp = oracle::block_timestamp();
+    let tick = observation_state.get_twap_tick(
+        &observation_extension,
+        block_timestamp,
+        seconds_ago,
+        pool_state.tick_current,
+    )?;
+
+    Ok(TwapResult {
+        tick,
+        sqrt_price_x64: tick_math::get_sqrt_price_at_tick(tick)?,
+        start_time: block_timestamp - seconds_ago,
+        end_time: block_timestamp,
+    })
+}
@@ -0,0 +1,352 @@
+use crate::error::ErrorCode;
+use crate::libraries::{liquidity_math, tick_math};
+use crate::states::*;
+use crate::util::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+#[instruction(tick_lower_index: i32, tick_array_start_index: i32, nonce: u64)]
+pub struct OpenLimitOrder<'info> {
+    /// The owner of the order, pays the sold token and the rent of the order account
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The pool to place the order
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The order account
+    #[account(
+        init,
+        seeds = [
+            LIMIT_ORDER_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+            &tick_lower_index.to_be_bytes(),
+            &nonce.to_be_bytes(),
+        ],
+        bump,
+        payer = owner,
+        space = LimitOrderState::LEN
+    )]
+    pub limit_order: Box<Account<'info, LimitOrderState>>,
+
+    /// CHECK: The tick array contains both ticks of the order range, created if not exist
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_array_start_index.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub tick_array: UncheckedAccount<'info>,
+
+    /// The user token account pays the sold token
+    #[account(
+        mut,
+        token::mint = input_vault.mint
+    )]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for the sold token
+    #[account(mut)]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of the sold token
+    #[account(
+        address = input_vault.mint
+    )]
+    pub input_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program for token transfers
+    pub token_program: Program<'info, Token>,
+
+    /// SPL program 2022 for token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// Program to create the order account and the tick array account
+    pub system_program: Program<'info, System>,
+    // remaining account
+    // #[account(
+    //     seeds = [
+    //         POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+}
+
+pub fn open_limit_order<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, OpenLimitOrder<'info>>,
+    tick_lower_index: i32,
+    tick_array_start_index: i32,
+    nonce: u64,
+    amount: u64,
+) -> Result<()> {
+    let pool_state_loader = &ctx.accounts.pool_state;
+    let pool_state = &mut pool_state_loader.load_mut()?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity) {
+        return err!(ErrorCode::NotApproved);
+    }
+    check_pool_permission(
+        pool_state.is_permissioned(),
+        pool_state_loader.key(),
+        ctx.accounts.owner.key,
+        ctx.remaining_accounts,
+    )?;
+    let tick_spacing = pool_state.tick_spacing;
+    let tick_upper_index = tick_lower_index + i32::from(tick_spacing);
+    check_tick_array_start_index(tick_array_start_index, tick_lower_index, tick_spacing)?;
+    require!(
+        tick_upper_index <= tick_math::MAX_TICK,
+        ErrorCode::TickUpperOverflow
+    );
+    // the fill tick must be able to update the other tick of the order when crossed by a swap
+    require_eq!(
+        TickArrayState::get_array_start_index(tick_upper_index, tick_spacing),
+        tick_array_start_index,
+        ErrorCode::InvalidLimitOrderTick
+    );
+
+    let zero_for_one = ctx.accounts.input_vault.key() == pool_state.token_vault_0;
+    if zero_for_one {
+        // sell token_0, the range must be above the current price
+        require_gt!(
+            tick_lower_index,
+            pool_state.tick_current,
+            ErrorCode::InvalidLimitOrderTick
+        );
+    } else {
+        require_keys_eq!(
+            ctx.accounts.input_vault.key(),
+            pool_state.token_vault_1,
+            ErrorCode::InvalidInputPoolVault
+        );
+        // sell token_1, the range must be below the current price
+        require_gte!(
+            pool_state.tick_current,
+            tick_upper_index,
+            ErrorCode::InvalidLimitOrderTick
+        );
+    }
+
+    let sqrt_price_lower_x64 = tick_math::get_sqrt_price_at_tick(tick_lower_index)?;
+    let sqrt_price_upper_x64 = tick_math::get_sqrt_price_at_tick(tick_upper_index)?;
+    let liquidity = if zero_for_one {
+        liquidity_math::get_liquidity_from_amount_0(
+            sqrt_price_lower_x64,
+            sqrt_price_upper_x64,
+            amount,
+        )
+    } else {
+        liquidity_math::get_liquidity_from_amount_1(
+            sqrt_price_lower_x64,
+            sqrt_price_upper_x64,
+            amount,
+        )
+    };
+    require!(liquidity > 0, ErrorCode::InvalidLiquidity);
+    let amount_in = if zero_for_one {
+        liquidity_math::get_delta_amount_0_unsigned(
+            sqrt_price_lower_x64,
+            sqrt_price_upper_x64,
+            liquidity,
+            true,
+        )?
+    } else {
+        liquidity_math::get_delta_amount_1_unsigned(
+            sqrt_price_lower_x64,
+            sqrt_price_upper_x64,
+            liquidity,
+            true,
+        )?
+    };
+    let transfer_fee = get_transfer_inverse_fee(ctx.accounts.input_vault_mint.clone(), amount_in)?;
+
+    TickArrayState::get_or_create_tick_array(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.tick_array.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        pool_state_loader,
+        tick_array_start_index,
+        tick_spacing,
+    )?;
+
+    // the order liquidity is recorded in the fill entry of its fill tick and fill epoch,
+    // the tick array grows by an entry if there is no free one
+    let fill_tick_index = if zero_for_one {
+        tick_upper_index
+    } else {
+        tick_lower_index
+    };
+    let tick_array_info = ctx.accounts.tick_array.to_account_info();
+    let need_grow = {
+        let (mut tick_array, fills) = load_tick_array_mut(&tick_array_info)?;
+        let fill_epoch = tick_array
+            .get_tick_state_mut(fill_tick_index, tick_spacing)?
+            .order_fill_epoch;
+        LimitOrderFill::find_or_free(&fills, fill_tick_index, fill_epoch).is_none()
+    };
+    if need_grow {
+        grow_limit_order_fills(
+            &tick_array_info,
+            ctx.accounts.owner.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        )?;
+    }
+
+    let timestamp = Clock::get()?.unix_timestamp as u64;
+    let updated_reward_infos = pool_state.update_reward_infos(timestamp)?;
+    pool_state.update_seconds_per_liquidity(timestamp);
+
+    let fill_epoch;
+    let fee_growth_inside_0_x64;
+    let fee_growth_inside_1_x64;
+    let reward_growths_inside_x64;
+    {
+        let (mut tick_array, mut fills) = load_tick_array_mut(&tick_array_info)?;
+        let initialized_tick_count_before = tick_array.initialized_tick_count;
+        let liquidity_delta = i128::try_from(liquidity).unwrap();
+        let mut flip_count = 0;
+        for (tick_index, upper) in [(tick_lower_index, false), (tick_upper_index, true)] {
+            let tick_state = tick_array.get_tick_state_mut(tick_index, tick_spacing)?;
+            tick_state.tick = tick_index;
+            tick_state.sync_reward_generations(pool_state.reward_generations);
+            if tick_state.update(
+                pool_state.tick_current,
+                liquidity_delta,
+                pool_state.fee_growth_global_0_x64,
+                pool_state.fee_growth_global_1_x64,
+                upper,
+                &updated_reward_infos,
+                pool_state.seconds_per_liquidity_global_x64,
+                timestamp,
+            )? {
+                flip_count += 1;
+            }
+        }
+        // the order liquidity is recorded on the tick which fills the order
+        let fill_tick_state = tick_array.get_tick_state_mut(fill_tick_index, tick_spacing)?;
+        fill_tick_state.order_liquidity = fill_tick_state
+            .order_liquidity
+            .checked_add(liquidity)
+            .ok_or(ErrorCode::CalculateOverflow)?;
+        fill_epoch = fill_tick_state.order_fill_epoch;
+        LimitOrderFill::add_order(&mut fills, fill_tick_index, fill_epoch, liquidity)?;
+
+        // the fees and rewards of the order start from the growth inside the range
+        let tick_lower_state = *tick_array.get_tick_state_mut(tick_lower_index, tick_spacing)?;
+        let tick_upper_state = *tick_array.get_tick_state_mut(tick_upper_index, tick_spacing)?;
+        (fee_growth_inside_0_x64, fee_growth_inside_1_x64) = get_fee_growth_inside(
+            &tick_lower_state,
+            &tick_upper_state,
+            pool_state.tick_current,
+            pool_state.fee_growth_global_0_x64,
+            pool_state.fee_growth_global_1_x64,
+        );
+        reward_growths_inside_x64 = get_reward_growths_inside(
+            &tick_lower_state,
+            &tick_upper_state,
+            pool_state.tick_current,
+            &updated_reward_infos,
+        );
+
+        for _ in 0..flip_count {
+            tick_array.update_initialized_tick_count(true)?;
+        }
+        if flip_count > 0 && initialized_tick_count_before == 0 {
+            let use_tickarray_bitmap_extension =
+                pool_state.is_overflow_default_tickarray_bitmap(vec![tick_array_start_index]);
+            let tickarray_bitmap_extension = if use_tickarray_bitmap_extension {
+                let tick_array_bitmap_extension_key =
+                    TickArrayBitmapExtension::key(pool_state_loader.key());
+                Some(
+                    ctx.remaining_accounts
+                        .iter()
+                        .find(|account_info| {
+                            account_info.key().eq(&tick_array_bitmap_extension_key)
+                        })
+                        .ok_or(ErrorCode::MissingTickArrayBitmapExtensionAccount)?,
+                )
+            } else {
+                None
+            };
+            pool_state.flip_tick_array_bit(tickarray_bitmap_extension, tick_array_start_index)?;
+        }
+    }
+
+    transfer_from_user_to_pool_vault(
+        &ctx.accounts.owner,
+        &ctx.accounts.input_token_account.to_account_info(),
+        &ctx.accounts.input_vault.to_account_info(),
+        Some(ctx.accounts.input_vault_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_in + transfer_fee,
+        ctx.remaining_accounts,
+    )?;
+
+    let limit_order = &mut ctx.accounts.limit_order;
+    limit_order.bump = ctx.bumps.limit_order;
+    limit_order.pool_id = pool_state_loader.key();
+    limit_order.owner = ctx.accounts.owner.key();
+    limit_order.nonce = nonce;
+    limit_order.tick_lower_index = tick_lower_index;
+    limit_order.tick_upper_index = tick_upper_index;
+    limit_order.zero_for_one = zero_for_one;
+    limit_order.liquidity = liquidity;
+    limit_order.fill_epoch = fill_epoch;
+    limit_order.amount_in = amount_in;
+    limit_order.fee_growth_inside_0_last_x64 = fee_growth_inside_0_x64;
+    limit_order.fee_growth_inside_1_last_x64 = fee_growth_inside_1_x64;
+    limit_order.reward_growths_inside_last_x64 = reward_growths_inside_x64;
+    limit_order.reward_generations = pool_state.reward_generations;
+    limit_order.recent_epoch = get_recent_epoch()?;
+
+    emit!(OpenLimitOrderEvent {
+        pool_state: pool_state_loader.key(),
+        owner: ctx.accounts.owner.key(),
+        limit_order: limit_order.key(),
+        tick_lower_index,
+        tick_upper_index,
+        zero_for_one,
+        liquidity,
+        amount_in,
+        transfer_fee,
+    });
+
+    Ok(())
+}
+
+/// Grows the tick array by a limit order fill entry, the payer pays the rent
+fn grow_limit_order_fills<'info>(
+    tick_array_info: &AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+) -> Result<()> {
+    let new_size = tick_array_info.data_len() + LimitOrderFill::LEN;
+    tick_array_info.realloc(new_size, true)?;
+
+    // If more lamports are needed, transfer them to the account.
+    let rent_exempt_lamports = Rent::get()?.minimum_balance(new_size);
+    let top_up_lamports = rent_exempt_lamports.saturating_sub(tick_array_info.lamports());
+    if top_up_lamports > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program,
+                system_program::Transfer {
+                    from: payer,
+                    to: tick_array_info.clone(),
+                },
+            ),
+            top_up_lamports,
+        )?;
+    }
+    Ok(())
+}
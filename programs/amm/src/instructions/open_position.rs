@@ -9,8 +9,11 @@ use anchor_lang::system_program::{transfer, Transfer};
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::metadata::{
     create_metadata_accounts_v3,
-    mpl_token_metadata::types::{Creator, DataV2},
-    CreateMetadataAccountsV3, Metadata,
+    mpl_token_metadata::{
+        self,
+        types::{Creator, DataV2},
+    },
+    CreateMetadataAccountsV3, Metadata, MetadataAccount,
 };
 use anchor_spl::token::{Mint, Token, TokenAccount};
 use anchor_spl::token_2022::{
@@ -22,7 +25,10 @@ use anchor_spl::token_2022::{
     },
     Token2022,
 };
-use anchor_spl::token_2022_extensions::spl_token_metadata_interface;
+use anchor_spl::token_2022_extensions::spl_token_metadata_interface::{
+    self,
+    state::{Field, TokenMetadata},
+};
 use anchor_spl::token_interface;
 use std::cell::RefMut;
 #[cfg(feature = "enable-log")]
@@ -265,6 +271,13 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
         if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity) {
             return err!(ErrorCode::NotApproved);
         }
+        // the position belongs to the NFT owner, the payer only pays for it
+        check_pool_permission(
+            pool_state.is_permissioned(),
+            pool_state_loader.key(),
+            position_nft_owner.key,
+            remaining_accounts,
+        )?;
         check_ticks_order(tick_lower_index, tick_upper_index)?;
         check_tick_array_start_index(
             tick_array_lower_start_index,
@@ -326,7 +339,7 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
         ]);
 
         let (amount_0, amount_1, amount_0_transfer_fee, amount_1_transfer_fee) = add_liquidity(
-            payer,
+            &NativeAccounts::load(payer, Some(system_program.to_account_info()), &[]),
             token_account_0,
             token_account_1,
             token_vault_0,
@@ -354,6 +367,7 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
             tick_lower_index,
             tick_upper_index,
             base_flag,
+            remaining_accounts,
         )?;
 
         // let personal_position = &mut personal_position;
@@ -369,7 +383,11 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
             protocol_position.fee_growth_inside_1_last_x64;
 
         // update rewards, must update before update liquidity
-        personal_position.update_rewards(protocol_position.reward_growth_inside, false)?;
+        personal_position.update_rewards(
+            protocol_position.reward_growth_inside,
+            pool_state.reward_generations,
+            false,
+        )?;
         personal_position.liquidity = liquidity;
 
         emit!(CreatePersonalPositionEvent {
@@ -405,7 +423,7 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
 
 /// Add liquidity to an initialized pool
 pub fn add_liquidity<'b, 'c: 'info, 'info>(
-    payer: &'b Signer<'info>,
+    native_accounts: &NativeAccounts<'info>,
     token_account_0: &'b AccountInfo<'info>,
     token_account_1: &'b AccountInfo<'info>,
     token_vault_0: &'b AccountInfo<'info>,
@@ -425,6 +443,7 @@ pub fn add_liquidity<'b, 'c: 'info, 'info>(
     tick_lower_index: i32,
     tick_upper_index: i32,
     base_flag: Option<bool>,
+    transfer_hook_accounts: &[AccountInfo<'info>],
 ) -> Result<(u64, u64, u64, u64)> {
     if *liquidity == 0 {
         if base_flag.is_none() {
@@ -471,68 +490,14 @@ pub fn add_liquidity<'b, 'c: 'info, 'info>(
     }
     assert!(*liquidity > 0);
     let liquidity_before = pool_state.liquidity;
-    require_keys_eq!(tick_array_lower_loader.load()?.pool_id, pool_state.key());
-    require_keys_eq!(tick_array_upper_loader.load()?.pool_id, pool_state.key());
-
-    // get tick_state
-    let mut tick_lower_state = *tick_array_lower_loader
-        .load_mut()?
-        .get_tick_state_mut(tick_lower_index, pool_state.tick_spacing)?;
-    let mut tick_upper_state = *tick_array_upper_loader
-        .load_mut()?
-        .get_tick_state_mut(tick_upper_index, pool_state.tick_spacing)?;
-    if tick_lower_state.tick == 0 {
-        tick_lower_state.tick = tick_lower_index;
-    }
-    if tick_upper_state.tick == 0 {
-        tick_upper_state.tick = tick_upper_index;
-    }
-    let clock = Clock::get()?;
-    let (amount_0, amount_1, flip_tick_lower, flip_tick_upper) = modify_position(
-        i128::try_from(*liquidity).unwrap(),
+    let (amount_0, amount_1) = mint_liquidity(
         pool_state,
+        tick_array_lower_loader,
+        tick_array_upper_loader,
         protocol_position,
-        &mut tick_lower_state,
-        &mut tick_upper_state,
-        clock.unix_timestamp as u64,
-    )?;
-
-    // update tick_state
-    tick_array_lower_loader.load_mut()?.update_tick_state(
-        tick_lower_index,
-        pool_state.tick_spacing,
-        tick_lower_state,
+        tick_array_bitmap_extension,
+        *liquidity,
     )?;
-    tick_array_upper_loader.load_mut()?.update_tick_state(
-        tick_upper_index,
-        pool_state.tick_spacing,
-        tick_upper_state,
-    )?;
-
-    if flip_tick_lower {
-        let mut tick_array_lower = tick_array_lower_loader.load_mut()?;
-        let before_init_tick_count = tick_array_lower.initialized_tick_count;
-        tick_array_lower.update_initialized_tick_count(true)?;
-
-        if before_init_tick_count == 0 {
-            pool_state.flip_tick_array_bit(
-                tick_array_bitmap_extension,
-                tick_array_lower.start_tick_index,
-            )?;
-        }
-    }
-    if flip_tick_upper {
-        let mut tick_array_upper = tick_array_upper_loader.load_mut()?;
-        let before_init_tick_count = tick_array_upper.initialized_tick_count;
-        tick_array_upper.update_initialized_tick_count(true)?;
-
-        if before_init_tick_count == 0 {
-            pool_state.flip_tick_array_bit(
-                tick_array_bitmap_extension,
-                tick_array_upper.start_tick_index,
-            )?;
-        }
-    }
     require!(
         amount_0 > 0 || amount_1 > 0,
         ErrorCode::ForbidBothZeroForSupplyLiquidity
@@ -581,23 +546,23 @@ pub fn add_liquidity<'b, 'c: 'info, 'info>(
     if token_program_2022.is_some() {
         token_2022_program_opt = Some(token_program_2022.clone().unwrap().to_account_info());
     }
-    transfer_from_user_to_pool_vault(
-        payer,
+    native_accounts.transfer_from_user_to_pool_vault(
         token_account_0,
         token_vault_0,
         vault_0_mint,
         &token_program,
         token_2022_program_opt.clone(),
         amount_0 + amount_0_transfer_fee,
+        transfer_hook_accounts,
     )?;
-    transfer_from_user_to_pool_vault(
-        payer,
+    native_accounts.transfer_from_user_to_pool_vault(
         token_account_1,
         token_vault_1,
         vault_1_mint,
         &token_program,
         token_2022_program_opt.clone(),
         amount_1 + amount_1_transfer_fee,
+        transfer_hook_accounts,
     )?;
     emit!(LiquidityChangeEvent {
         pool_state: pool_state.key(),
@@ -615,6 +580,80 @@ pub fn add_liquidity<'b, 'c: 'info, 'info>(
     ))
 }
 
+/// Mint liquidity of the protocol position range, update the tick states and the tick array bitmap
+pub fn mint_liquidity<'c: 'info, 'info>(
+    pool_state: &mut RefMut<PoolState>,
+    tick_array_lower_loader: &AccountLoad<'info, TickArrayState>,
+    tick_array_upper_loader: &AccountLoad<'info, TickArrayState>,
+    protocol_position: &mut ProtocolPositionState,
+    tick_array_bitmap_extension: Option<&'c AccountInfo<'info>>,
+    liquidity: u128,
+) -> Result<(u64, u64)> {
+    require_keys_eq!(tick_array_lower_loader.load()?.pool_id, pool_state.key());
+    require_keys_eq!(tick_array_upper_loader.load()?.pool_id, pool_state.key());
+
+    // get tick_state
+    let mut tick_lower_state = *tick_array_lower_loader
+        .load_mut()?
+        .get_tick_state_mut(protocol_position.tick_lower_index, pool_state.tick_spacing)?;
+    let mut tick_upper_state = *tick_array_upper_loader
+        .load_mut()?
+        .get_tick_state_mut(protocol_position.tick_upper_index, pool_state.tick_spacing)?;
+    if tick_lower_state.tick == 0 {
+        tick_lower_state.tick = protocol_position.tick_lower_index;
+    }
+    if tick_upper_state.tick == 0 {
+        tick_upper_state.tick = protocol_position.tick_upper_index;
+    }
+    let clock = Clock::get()?;
+    let (amount_0, amount_1, flip_tick_lower, flip_tick_upper) = modify_position(
+        i128::try_from(liquidity).unwrap(),
+        pool_state,
+        protocol_position,
+        &mut tick_lower_state,
+        &mut tick_upper_state,
+        clock.unix_timestamp as u64,
+    )?;
+
+    // update tick_state
+    tick_array_lower_loader.load_mut()?.update_tick_state(
+        protocol_position.tick_lower_index,
+        pool_state.tick_spacing,
+        tick_lower_state,
+    )?;
+    tick_array_upper_loader.load_mut()?.update_tick_state(
+        protocol_position.tick_upper_index,
+        pool_state.tick_spacing,
+        tick_upper_state,
+    )?;
+
+    if flip_tick_lower {
+        let mut tick_array_lower = tick_array_lower_loader.load_mut()?;
+        let before_init_tick_count = tick_array_lower.initialized_tick_count;
+        tick_array_lower.update_initialized_tick_count(true)?;
+
+        if before_init_tick_count == 0 {
+            pool_state.flip_tick_array_bit(
+                tick_array_bitmap_extension,
+                tick_array_lower.start_tick_index,
+            )?;
+        }
+    }
+    if flip_tick_upper {
+        let mut tick_array_upper = tick_array_upper_loader.load_mut()?;
+        let before_init_tick_count = tick_array_upper.initialized_tick_count;
+        tick_array_upper.update_initialized_tick_count(true)?;
+
+        if before_init_tick_count == 0 {
+            pool_state.flip_tick_array_bit(
+                tick_array_bitmap_extension,
+                tick_array_upper.start_tick_index,
+            )?;
+        }
+    }
+    Ok((amount_0, amount_1))
+}
+
 pub fn modify_position(
     liquidity_delta: i128,
     pool_state: &mut RefMut<PoolState>,
@@ -663,6 +702,9 @@ pub fn update_position(
     timestamp: u64,
 ) -> Result<(bool, bool)> {
     let updated_reward_infos = pool_state.update_reward_infos(timestamp)?;
+    pool_state.update_seconds_per_liquidity(timestamp);
+    tick_lower_state.sync_reward_generations(pool_state.reward_generations);
+    tick_upper_state.sync_reward_generations(pool_state.reward_generations);
 
     let mut flipped_lower = false;
     let mut flipped_upper = false;
@@ -677,6 +719,8 @@ pub fn update_position(
             pool_state.fee_growth_global_1_x64,
             false,
             &updated_reward_infos,
+            pool_state.seconds_per_liquidity_global_x64,
+            timestamp,
         )?;
         flipped_upper = tick_upper_state.update(
             pool_state.tick_current,
@@ -685,6 +729,8 @@ pub fn update_position(
             pool_state.fee_growth_global_1_x64,
             true,
             &updated_reward_infos,
+            pool_state.seconds_per_liquidity_global_x64,
+            timestamp,
         )?;
         #[cfg(feature = "enable-log")]
         msg!(
@@ -933,6 +979,209 @@ pub fn initialize_token_metadata_extension<'info>(
     Ok(())
 }
 
+/// The fields of the token metadata extension describing the position, the name shows the token pair in wallets
+pub fn get_position_metadata_fields(
+    pool_id: Pubkey,
+    symbol_0: &str,
+    symbol_1: &str,
+    trade_fee_rate: u32,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+) -> Vec<(Field, String)> {
+    let token_pair = format!("{}/{}", symbol_0, symbol_1);
+    let mut fields = vec![
+        (Field::Name, format!("Byreal CLMM {}", token_pair)),
+        (Field::Key("pool_id".to_string()), pool_id.to_string()),
+        (Field::Key("token_pair".to_string()), token_pair),
+        (
+            Field::Key("fee_tier".to_string()),
+            format_fee_rate(trade_fee_rate),
+        ),
+    ];
+    fields.extend(get_position_range_metadata_fields(
+        tick_lower_index,
+        tick_upper_index,
+    ));
+    fields
+}
+
+/// The fields of the token metadata extension to update when the range of the position changes
+pub fn get_position_range_metadata_fields(
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+) -> Vec<(Field, String)> {
+    vec![
+        (
+            Field::Key("tick_lower_index".to_string()),
+            tick_lower_index.to_string(),
+        ),
+        (
+            Field::Key("tick_upper_index".to_string()),
+            tick_upper_index.to_string(),
+        ),
+    ]
+}
+
+/// Formats the fee rate in hundredths of a bip as a percentage, e.g. 2500 as 0.25%
+fn format_fee_rate(fee_rate: u32) -> String {
+    let integer = fee_rate / 10_000;
+    let fraction = fee_rate % 10_000;
+    if fraction == 0 {
+        format!("{}%", integer)
+    } else {
+        format!(
+            "{}.{}%",
+            integer,
+            format!("{:04}", fraction).trim_end_matches('0')
+        )
+    }
+}
+
+/// The symbol of the token from the token metadata extension of the mint, or from the metaplex metadata
+/// account of the mint found in the remaining accounts. The mint address if the symbol is not found.
+pub fn get_token_symbol(
+    mint: &InterfaceAccount<token_interface::Mint>,
+    remaining_accounts: &[AccountInfo],
+) -> Result<String> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner == Token2022::id() {
+        let mint_data = mint_info.try_borrow_data()?;
+        let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+        if let Ok(metadata) = mint_state.get_variable_len_extension::<TokenMetadata>() {
+            return Ok(metadata.symbol);
+        }
+    }
+    for account_info in remaining_accounts {
+        if *account_info.owner != mpl_token_metadata::ID {
+            continue;
+        }
+        let Ok(metadata) = MetadataAccount::try_deserialize(&mut &account_info.data.borrow()[..])
+        else {
+            continue;
+        };
+        if metadata.mint == mint.key() {
+            return Ok(metadata.symbol.trim_matches('\0').to_string());
+        }
+    }
+    Ok(mint.key().to_string())
+}
+
+/// Updates the fields of the token metadata extension of the position NFT signed by the personal position,
+/// which is the update authority. The position NFTs without the token metadata extension are skipped.
+pub fn update_position_nft_metadata<'info>(
+    payer: &AccountInfo<'info>,
+    position_nft_mint: &AccountInfo<'info>,
+    personal_position: &Account<'info, PersonalPositionState>,
+    system_program: &AccountInfo<'info>,
+    token_2022_program: &AccountInfo<'info>,
+    fields: Vec<(Field, String)>,
+) -> Result<()> {
+    if *position_nft_mint.owner != Token2022::id() {
+        return Ok(());
+    }
+    let mint_data = position_nft_mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let Ok(mut metadata) = mint_state.get_variable_len_extension::<TokenMetadata>() else {
+        return Ok(());
+    };
+    if Option::<Pubkey>::from(metadata.update_authority) != Some(personal_position.key()) {
+        return Ok(());
+    }
+    for (field, value) in fields.iter() {
+        metadata.update(field.clone(), value.clone());
+    }
+    let new_account_len =
+        mint_state.try_get_new_account_len_for_variable_len_extension(&metadata)?;
+    let additional_lamports = Rent::get()?
+        .minimum_balance(new_account_len)
+        .saturating_sub(position_nft_mint.lamports());
+    // CPI call will borrow the account data
+    drop(mint_data);
+
+    if additional_lamports > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: position_nft_mint.clone(),
+                },
+            ),
+            additional_lamports,
+        )?;
+    }
+
+    let seeds = personal_position.seeds();
+    for (field, value) in fields {
+        solana_program::program::invoke_signed(
+            &spl_token_metadata_interface::instruction::update_field(
+                token_2022_program.key,
+                position_nft_mint.key,
+                &personal_position.key(),
+                field,
+                value,
+            ),
+            &[
+                position_nft_mint.clone(),
+                personal_position.to_account_info(),
+                token_2022_program.clone(),
+            ],
+            &[&seeds],
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod position_metadata_test {
+    use super::*;
+
+    #[test]
+    fn format_fee_rate_test() {
+        assert_eq!(format_fee_rate(2500), "0.25%");
+        assert_eq!(format_fee_rate(100), "0.01%");
+        assert_eq!(format_fee_rate(1), "0.0001%");
+        assert_eq!(format_fee_rate(10000), "1%");
+        assert_eq!(format_fee_rate(12500), "1.25%");
+        assert_eq!(format_fee_rate(0), "0%");
+    }
+
+    #[test]
+    fn position_metadata_fields_test() {
+        let pool_id = Pubkey::new_unique();
+        let fields = get_position_metadata_fields(pool_id, "SOL", "USDC", 2500, -100, 200);
+        let mut metadata = TokenMetadata::default();
+        for (field, value) in fields {
+            metadata.update(field, value);
+        }
+        assert_eq!(metadata.name, "Byreal CLMM SOL/USDC");
+        assert_eq!(
+            metadata.additional_metadata,
+            vec![
+                ("pool_id".to_string(), pool_id.to_string()),
+                ("token_pair".to_string(), "SOL/USDC".to_string()),
+                ("fee_tier".to_string(), "0.25%".to_string()),
+                ("tick_lower_index".to_string(), "-100".to_string()),
+                ("tick_upper_index".to_string(), "200".to_string()),
+            ]
+        );
+
+        // the range fields replace the values of the same keys
+        for (field, value) in get_position_range_metadata_fields(-300, 400) {
+            metadata.update(field, value);
+        }
+        assert_eq!(metadata.additional_metadata.len(), 5);
+        assert_eq!(
+            metadata.additional_metadata[3],
+            ("tick_lower_index".to_string(), "-300".to_string())
+        );
+        assert_eq!(
+            metadata.additional_metadata[4],
+            ("tick_upper_index".to_string(), "400".to_string())
+        );
+    }
+}
+
 #[cfg(test)]
 mod modify_position_test {
     use super::modify_position;
@@ -1,5 +1,6 @@
 use super::open_position::open_position;
 use crate::states::*;
+use crate::util::check_deadline;
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::metadata::Metadata;
@@ -91,19 +92,15 @@ pub struct OpenPositionV2<'info> {
     )]
     pub personal_position: Box<Account<'info, PersonalPositionState>>,
 
-    /// The token_0 account deposit token to the pool
-    #[account(
-        mut,
-        token::mint = token_vault_0.mint
-    )]
-    pub token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: The token_0 account deposit token to the pool, validated by the token program on transfer.
+    /// If token_0 is the native mint, it can be the payer itself to wrap SOL.
+    #[account(mut)]
+    pub token_account_0: UncheckedAccount<'info>,
 
-    /// The token_1 account deposit token to the pool
-    #[account(
-        mut,
-        token::mint = token_vault_1.mint
-    )]
-    pub token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: The token_1 account deposit token to the pool, validated by the token program on transfer.
+    /// If token_1 is the native mint, it can be the payer itself to wrap SOL.
+    #[account(mut)]
+    pub token_account_1: UncheckedAccount<'info>,
 
     /// The address that holds pool tokens for token_0
     #[account(
@@ -167,7 +164,9 @@ pub fn open_position_v2<'a, 'b, 'c: 'info, 'info>(
     tick_array_upper_start_index: i32,
     with_metadata: bool,
     base_flag: Option<bool>,
+    deadline: Option<i64>,
 ) -> Result<()> {
+    check_deadline(deadline)?;
     open_position(
         &ctx.accounts.payer,
         &ctx.accounts.position_nft_owner,
@@ -1,4 +1,7 @@
-use super::open_position::open_position;
+use super::open_position::{
+    get_position_metadata_fields, get_token_symbol, open_position, update_position_nft_metadata,
+};
+use crate::error::ErrorCode;
 use crate::states::*;
 use crate::util::create_position_nft_mint_with_extensions;
 use anchor_lang::prelude::*;
@@ -140,6 +143,8 @@ pub struct OpenPositionWithToken22Nft<'info> {
     //     bump
     // )]
     // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    // the amm config of the pool is required with metadata to show the fee tier in the position NFT metadata,
+    // the metaplex metadata accounts of the pool mints are optional to show the token symbols
 }
 
 pub fn open_position_with_token22_nft<'a, 'b, 'c: 'info, 'info>(
@@ -213,5 +218,34 @@ pub fn open_position_with_token22_nft<'a, 'b, 'c: 'info, 'info>(
         with_metadata,
         base_flag,
         true,
-    )
+    )?;
+
+    if with_metadata {
+        let fields = {
+            let pool_state = ctx.accounts.pool_state.load()?;
+            let amm_config_info = ctx
+                .remaining_accounts
+                .iter()
+                .find(|account_info| account_info.key() == pool_state.amm_config)
+                .ok_or(ErrorCode::MissingAmmConfigAccount)?;
+            let amm_config = Account::<AmmConfig>::try_from(amm_config_info)?;
+            get_position_metadata_fields(
+                ctx.accounts.pool_state.key(),
+                &get_token_symbol(&ctx.accounts.vault_0_mint, ctx.remaining_accounts)?,
+                &get_token_symbol(&ctx.accounts.vault_1_mint, ctx.remaining_accounts)?,
+                pool_state.get_base_fee_rate(amm_config.trade_fee_rate),
+                tick_lower_index,
+                tick_upper_index,
+            )
+        };
+        update_position_nft_metadata(
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.position_nft_mint.to_account_info(),
+            &ctx.accounts.personal_position,
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.token_program_2022.to_account_info(),
+            fields,
+        )?;
+    }
+    Ok(())
 }
@@ -0,0 +1,44 @@
+use super::collect_remaining_rewards::get_remaining_reward_amount;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::transfer_from_pool_vault_to_user;
+use anchor_lang::prelude::*;
+
+pub fn recycle_reward(ctx: Context<CollectRemainingRewards>, reward_index: u8) -> Result<()> {
+    // only pool owner or reward-manager can recycle the reward slot
+    if ctx.accounts.reward_funder.key() != ctx.accounts.pool_state.load()?.owner
+        && ctx.accounts.admin_group.reward_config_manager != ctx.accounts.reward_funder.key()
+    {
+        return err!(ErrorCode::NotApproved);
+    }
+
+    let amount_remaining = get_remaining_reward_amount(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.reward_token_vault,
+        reward_index,
+        true,
+    )?;
+
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.reward_token_vault.to_account_info(),
+        &ctx.accounts.funder_token_account.to_account_info(),
+        Some(ctx.accounts.reward_vault_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.to_account_info()),
+        amount_remaining,
+        &[],
+    )?;
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.recycle_reward(reward_index as usize);
+    emit!(RecycleRewardEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        reward_index,
+        reward_mint: ctx.accounts.reward_token_vault.mint,
+        amount_remaining,
+        reward_generation: pool_state.reward_generations[reward_index as usize],
+    });
+
+    Ok(())
+}
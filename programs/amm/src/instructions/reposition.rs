@@ -0,0 +1,495 @@
+use super::decrease_liquidity::{
+    check_unclaimed_fees_and_vault, collect_rewards, decrease_liquidity_and_update_position,
+};
+use super::open_position::{
+    get_position_range_metadata_fields, mint_liquidity, update_position_nft_metadata,
+};
+use crate::error::ErrorCode;
+use crate::libraries::{liquidity_math, tick_math};
+use crate::states::*;
+use crate::util::{self, transfer_from_pool_vault_to_user, AccountLoad};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+use std::ops::DerefMut;
+
+#[derive(Accounts)]
+#[instruction(tick_lower_index: i32, tick_upper_index: i32,tick_array_lower_start_index:i32,tick_array_upper_start_index:i32)]
+pub struct Reposition<'info> {
+    /// The position owner or delegated authority, pays for the new protocol position and tick arrays
+    #[account(mut)]
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+        token::authority = nft_owner,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: The position NFT mint, the range in its token metadata extension is updated
+    #[account(mut, address = personal_position.nft_mint)]
+    pub position_nft_mint: UncheckedAccount<'info>,
+
+    /// The position to move to the new range
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The protocol position of the current range
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &personal_position.tick_lower_index.to_be_bytes(),
+            &personal_position.tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        constraint = protocol_position.pool_id == pool_state.key(),
+    )]
+    pub protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// Stores init state for the current lower tick
+    #[account(mut, constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// Stores init state for the current upper tick
+    #[account(mut, constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+
+    /// The protocol position of the new range
+    #[account(
+        init_if_needed,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_lower_index.to_be_bytes(),
+            &tick_upper_index.to_be_bytes(),
+        ],
+        bump,
+        payer = nft_owner,
+        space = ProtocolPositionState::LEN
+    )]
+    pub new_protocol_position: Box<Account<'info, ProtocolPositionState>>,
+
+    /// CHECK: Account to store data for the new lower tick
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_array_lower_start_index.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub new_tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Account to store data for the new upper tick
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_array_upper_start_index.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub new_tick_array_upper: UncheckedAccount<'info>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for the token_0 left over
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for the token_1 left over
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Program to create the protocol position and tick arrays
+    pub system_program: Program<'info, System>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+    // remaining account
+    // #[account(
+    //     seeds = [
+    //         POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    // reward accounts, same as decrease_liquidity_v2
+}
+
+pub fn reposition<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, Reposition<'info>>,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_array_lower_start_index: i32,
+    tick_array_upper_start_index: i32,
+    liquidity_min: u128,
+) -> Result<()> {
+    let tick_lower_index_before = ctx.accounts.personal_position.tick_lower_index;
+    let tick_upper_index_before = ctx.accounts.personal_position.tick_upper_index;
+    require!(
+        tick_lower_index != tick_lower_index_before || tick_upper_index != tick_upper_index_before,
+        ErrorCode::SameRepositionRange
+    );
+    check_ticks_order(tick_lower_index, tick_upper_index)?;
+
+    let mut tickarray_bitmap_extension = None;
+    let remaining_collect_accounts = &mut Vec::new();
+    {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)
+            || !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)
+        {
+            return err!(ErrorCode::NotApproved);
+        }
+        // the liquidity is deposited into the new range like increase_liquidity
+        let pool_permission_accounts = check_pool_permission(
+            pool_state.is_permissioned(),
+            ctx.accounts.pool_state.key(),
+            ctx.accounts.nft_owner.key,
+            ctx.remaining_accounts,
+        )?;
+        check_tick_array_start_index(
+            tick_array_lower_start_index,
+            tick_lower_index,
+            pool_state.tick_spacing,
+        )?;
+        check_tick_array_start_index(
+            tick_array_upper_start_index,
+            tick_upper_index,
+            pool_state.tick_spacing,
+        )?;
+
+        let use_tickarray_bitmap_extension = pool_state.is_overflow_default_tickarray_bitmap(vec![
+            ctx.accounts.tick_array_lower.load()?.start_tick_index,
+            ctx.accounts.tick_array_upper.load()?.start_tick_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+        ]);
+
+        for account_info in ctx.remaining_accounts.iter() {
+            if account_info
+                .key()
+                .eq(&TickArrayBitmapExtension::key(pool_state.key()))
+            {
+                tickarray_bitmap_extension = Some(account_info);
+                continue;
+            }
+            if is_pool_permission_account(account_info, &pool_permission_accounts) {
+                continue;
+            }
+            remaining_collect_accounts.push(account_info);
+        }
+        if use_tickarray_bitmap_extension {
+            require!(
+                tickarray_bitmap_extension.is_some(),
+                ErrorCode::MissingTickArrayBitmapExtensionAccount
+            );
+        }
+    }
+
+    // withdraw all liquidity and fees of the current range, the tokens stay in the vaults
+    let liquidity_before = ctx.accounts.personal_position.liquidity;
+    let (decrease_amount_0, fee_amount_0, decrease_amount_1, fee_amount_1) =
+        decrease_liquidity_and_update_position(
+            &ctx.accounts.pool_state,
+            &mut ctx.accounts.protocol_position,
+            &mut ctx.accounts.personal_position,
+            &ctx.accounts.tick_array_lower,
+            &ctx.accounts.tick_array_upper,
+            tickarray_bitmap_extension,
+            liquidity_before,
+        )?;
+
+    let token_program_2022 = ctx.accounts.token_program_2022.to_account_info();
+    let reward_amounts = collect_rewards(
+        &ctx.accounts.pool_state,
+        remaining_collect_accounts.as_slice(),
+        &ctx.accounts.token_program,
+        Some(token_program_2022.clone()),
+        &mut ctx.accounts.personal_position,
+        true,
+        [u64::MAX; REWARD_NUM],
+    )?;
+
+    let amount_0_available = decrease_amount_0.checked_add(fee_amount_0).unwrap();
+    let amount_1_available = decrease_amount_1.checked_add(fee_amount_1).unwrap();
+
+    // deposit as much as possible into the new range
+    let liquidity;
+    let deposit_amount_0;
+    let deposit_amount_1;
+    {
+        let pool_state_loader = &ctx.accounts.pool_state;
+        let pool_state = &mut pool_state_loader.load_mut()?;
+        let tick_array_lower_loader = TickArrayState::get_or_create_tick_array(
+            ctx.accounts.nft_owner.to_account_info(),
+            ctx.accounts.new_tick_array_lower.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            pool_state_loader,
+            tick_array_lower_start_index,
+            pool_state.tick_spacing,
+        )?;
+        let tick_array_upper_loader =
+            if tick_array_lower_start_index == tick_array_upper_start_index {
+                AccountLoad::<TickArrayState>::try_from(
+                    &ctx.accounts.new_tick_array_upper.to_account_info(),
+                )?
+            } else {
+                TickArrayState::get_or_create_tick_array(
+                    ctx.accounts.nft_owner.to_account_info(),
+                    ctx.accounts.new_tick_array_upper.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                    pool_state_loader,
+                    tick_array_upper_start_index,
+                    pool_state.tick_spacing,
+                )?
+            };
+
+        let protocol_position = ctx.accounts.new_protocol_position.deref_mut();
+        if protocol_position.pool_id == Pubkey::default() {
+            protocol_position.bump = ctx.bumps.new_protocol_position;
+            protocol_position.pool_id = pool_state_loader.key();
+            protocol_position.tick_lower_index = tick_lower_index;
+            protocol_position.tick_upper_index = tick_upper_index;
+        }
+
+        liquidity = get_liquidity_within_amounts(
+            pool_state.tick_current,
+            pool_state.sqrt_price_x64,
+            tick_lower_index,
+            tick_upper_index,
+            amount_0_available,
+            amount_1_available,
+        )?;
+        require_gte!(liquidity, liquidity_min, ErrorCode::PriceSlippageCheck);
+        require!(liquidity > 0, ErrorCode::ForbidBothZeroForSupplyLiquidity);
+
+        let pool_liquidity_before = pool_state.liquidity;
+        (deposit_amount_0, deposit_amount_1) = mint_liquidity(
+            pool_state,
+            &tick_array_lower_loader,
+            &tick_array_upper_loader,
+            protocol_position,
+            tickarray_bitmap_extension,
+            liquidity,
+        )?;
+        emit!(LiquidityChangeEvent {
+            pool_state: pool_state_loader.key(),
+            tick: pool_state.tick_current,
+            tick_lower: tick_lower_index,
+            tick_upper: tick_upper_index,
+            liquidity_before: pool_liquidity_before,
+            liquidity_after: pool_state.liquidity,
+        });
+    }
+    require_gte!(
+        amount_0_available,
+        deposit_amount_0,
+        ErrorCode::PriceSlippageCheck
+    );
+    require_gte!(
+        amount_1_available,
+        deposit_amount_1,
+        ErrorCode::PriceSlippageCheck
+    );
+
+    let new_protocol_position = &ctx.accounts.new_protocol_position;
+    let personal_position = &mut ctx.accounts.personal_position;
+    personal_position.tick_lower_index = tick_lower_index;
+    personal_position.tick_upper_index = tick_upper_index;
+    personal_position.fee_growth_inside_0_last_x64 =
+        new_protocol_position.fee_growth_inside_0_last_x64;
+    personal_position.fee_growth_inside_1_last_x64 =
+        new_protocol_position.fee_growth_inside_1_last_x64;
+    // update rewards, must update before update liquidity
+    personal_position.update_rewards(
+        new_protocol_position.reward_growth_inside,
+        ctx.accounts.pool_state.load()?.reward_generations,
+        false,
+    )?;
+    personal_position.liquidity = liquidity;
+    update_position_nft_metadata(
+        &ctx.accounts.nft_owner.to_account_info(),
+        &ctx.accounts.position_nft_mint.to_account_info(),
+        personal_position,
+        &ctx.accounts.system_program.to_account_info(),
+        &token_program_2022,
+        get_position_range_metadata_fields(tick_lower_index, tick_upper_index),
+    )?;
+
+    // return the tokens not deposited into the new range
+    let refund_amount_0 = amount_0_available - deposit_amount_0;
+    let refund_amount_1 = amount_1_available - deposit_amount_1;
+    let transfer_fee_0 =
+        util::get_transfer_fee(ctx.accounts.vault_0_mint.clone(), refund_amount_0)?;
+    let transfer_fee_1 =
+        util::get_transfer_fee(ctx.accounts.vault_1_mint.clone(), refund_amount_1)?;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(token_program_2022.clone()),
+        refund_amount_0,
+        ctx.remaining_accounts,
+    )?;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(token_program_2022),
+        refund_amount_1,
+        ctx.remaining_accounts,
+    )?;
+
+    check_unclaimed_fees_and_vault(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+    )?;
+
+    emit!(RepositionEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        position_nft_mint: personal_position.nft_mint,
+        tick_lower_index_before,
+        tick_upper_index_before,
+        liquidity_before,
+        tick_lower_index,
+        tick_upper_index,
+        liquidity,
+        decrease_amount_0,
+        decrease_amount_1,
+        fee_amount_0,
+        fee_amount_1,
+        reward_amounts,
+        deposit_amount_0,
+        deposit_amount_1,
+        refund_amount_0,
+        refund_amount_1,
+        transfer_fee_0,
+        transfer_fee_1,
+    });
+
+    Ok(())
+}
+
+/// Returns the max liquidity which can be deposited into the range with the amounts. The deposit amounts
+/// of the liquidity are rounded up, so the liquidity is computed again with one less unit of each token
+/// if they exceed the amounts.
+pub fn get_liquidity_within_amounts(
+    tick_current: i32,
+    sqrt_price_x64: u128,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    amount_0: u64,
+    amount_1: u64,
+) -> Result<u128> {
+    let sqrt_price_lower_x64 = tick_math::get_sqrt_price_at_tick(tick_lower_index)?;
+    let sqrt_price_upper_x64 = tick_math::get_sqrt_price_at_tick(tick_upper_index)?;
+    let liquidity = liquidity_math::get_liquidity_from_amounts(
+        sqrt_price_x64,
+        sqrt_price_lower_x64,
+        sqrt_price_upper_x64,
+        amount_0,
+        amount_1,
+    );
+    let (deposit_amount_0, deposit_amount_1) = liquidity_math::get_delta_amounts_signed(
+        tick_current,
+        sqrt_price_x64,
+        tick_lower_index,
+        tick_upper_index,
+        i128::try_from(liquidity).map_err(|_| ErrorCode::CalculateOverflow)?,
+    )?;
+    if deposit_amount_0 <= amount_0 && deposit_amount_1 <= amount_1 {
+        return Ok(liquidity);
+    }
+    Ok(liquidity_math::get_liquidity_from_amounts(
+        sqrt_price_x64,
+        sqrt_price_lower_x64,
+        sqrt_price_upper_x64,
+        amount_0.saturating_sub(1),
+        amount_1.saturating_sub(1),
+    ))
+}
+
+#[cfg(test)]
+mod reposition_test {
+    use super::*;
+
+    #[test]
+    fn liquidity_within_amounts_test() {
+        let tick_current = 15;
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(tick_current).unwrap();
+        for (tick_lower_index, tick_upper_index) in [(-120, 120), (0, 60), (-600, -60), (60, 600)] {
+            for (amount_0, amount_1) in [(1_000_003, 999_997), (7, 1_000_000_007), (123_457, 13)] {
+                let liquidity = get_liquidity_within_amounts(
+                    tick_current,
+                    sqrt_price_x64,
+                    tick_lower_index,
+                    tick_upper_index,
+                    amount_0,
+                    amount_1,
+                )
+                .unwrap();
+                let (deposit_amount_0, deposit_amount_1) =
+                    liquidity_math::get_delta_amounts_signed(
+                        tick_current,
+                        sqrt_price_x64,
+                        tick_lower_index,
+                        tick_upper_index,
+                        liquidity as i128,
+                    )
+                    .unwrap();
+                assert!(deposit_amount_0 <= amount_0);
+                assert!(deposit_amount_1 <= amount_1);
+            }
+        }
+    }
+}
@@ -81,6 +81,16 @@ pub fn set_reward_params<'a, 'b, 'c: 'info, 'info>(
         return err!(ErrorCode::UnInitializedRewardInfo);
     }
 
+    // the phases of a scheduled reward are committed up front, it can only be restarted as a constant reward
+    if pool_state.reward_schedules[reward_index as usize].is_enabled() {
+        require!(
+            reward_info.last_update_time == reward_info.end_time
+                || (admin_operator && reward_info.open_time > current_timestamp),
+            ErrorCode::RewardScheduleNotChangeable
+        );
+        pool_state.reward_schedules[reward_index as usize] = RewardSchedule::default();
+    }
+
     let reward_amount = if admin_operator {
         admin_update(
             &mut reward_info,
@@ -104,7 +114,29 @@ pub fn set_reward_params<'a, 'b, 'c: 'info, 'info>(
         .unwrap()
     };
 
+    // the remaining reward of an ended reward must be refunded to the third-party funders before it restarts,
+    // the funded totals start over with the remaining reward not collected by the reward authority
+    if pool_state.reward_infos[reward_index as usize].last_update_time
+        == pool_state.reward_infos[reward_index as usize].end_time
+    {
+        require_eq!(
+            pool_state.reward_pending_contributed[reward_index as usize],
+            0,
+            ErrorCode::RewardFundsNotRefunded
+        );
+        let reward_token_vault = InterfaceAccount::<TokenAccount>::try_from(
+            ctx.remaining_accounts
+                .first()
+                .ok_or(ErrorCode::AccountLack)?,
+        )?;
+        require_keys_eq!(reward_token_vault.key(), reward_info.token_vault);
+        pool_state.restart_reward_funds(reward_index as usize, reward_token_vault.amount)?;
+    }
     pool_state.reward_infos[reward_index as usize] = reward_info;
+    pool_state.reward_total_funded[reward_index as usize] = pool_state.reward_total_funded
+        [reward_index as usize]
+        .checked_add(reward_amount)
+        .ok_or(ErrorCode::CalculateOverflow)?;
 
     if reward_amount > 0 {
         let mut remaining_accounts = ctx.remaining_accounts.iter();
@@ -132,6 +164,7 @@ pub fn set_reward_params<'a, 'b, 'c: 'info, 'info>(
             &ctx.accounts.token_program,
             Some(ctx.accounts.token_program_2022.to_account_info()),
             reward_amount_with_transfer_fee,
+            &[],
         )?;
     }
 
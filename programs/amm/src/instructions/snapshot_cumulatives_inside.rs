@@ -0,0 +1,61 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(tick_lower_index: i32, tick_upper_index: i32)]
+pub struct SnapshotCumulativesInside<'info> {
+    /// The pool of the tick range
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The tick array contains the lower tick
+    #[account(constraint = tick_array_lower.load()?.pool_id == pool_state.key())]
+    pub tick_array_lower: AccountLoader<'info, TickArrayState>,
+
+    /// The tick array contains the upper tick
+    #[account(constraint = tick_array_upper.load()?.pool_id == pool_state.key())]
+    pub tick_array_upper: AccountLoader<'info, TickArrayState>,
+}
+
+#[derive(Copy, Clone, AnchorSerialize, AnchorDeserialize, Debug, PartialEq)]
+pub struct CumulativesInside {
+    /// The seconds per unit of liquidity inside the tick range, as a Q64.64
+    pub seconds_per_liquidity_inside_x64: u128,
+    /// The seconds spent while the pool price was inside the tick range
+    pub seconds_inside: u64,
+}
+
+/// Returns a snapshot of the seconds per liquidity and the seconds spent inside a tick range
+pub fn snapshot_cumulatives_inside(
+    ctx: Context<SnapshotCumulativesInside>,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+) -> Result<CumulativesInside> {
+    check_ticks_order(tick_lower_index, tick_upper_index)?;
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let tick_array_lower = ctx.accounts.tick_array_lower.load()?;
+    let tick_array_upper = ctx.accounts.tick_array_upper.load()?;
+
+    let tick_lower_state =
+        tick_array_lower.get_tick_state(tick_lower_index, pool_state.tick_spacing)?;
+    let tick_upper_state =
+        tick_array_upper.get_tick_state(tick_upper_index, pool_state.tick_spacing)?;
+    require!(
+        tick_lower_state.is_initialized() && tick_upper_state.is_initialized(),
+        ErrorCode::TickNotInitialized
+    );
+
+    let block_timestamp = u64::from(oracle::block_timestamp());
+    let (seconds_per_liquidity_inside_x64, seconds_inside) = get_seconds_inside(
+        tick_lower_state,
+        tick_upper_state,
+        pool_state.tick_current,
+        pool_state.get_seconds_per_liquidity_global_x64(block_timestamp),
+        block_timestamp,
+    );
+
+    Ok(CumulativesInside {
+        seconds_per_liquidity_inside_x64,
+        seconds_inside,
+    })
+}
@@ -1,10 +1,13 @@
 use crate::error::ErrorCode;
 use crate::libraries::{
-    big_num::U128, fixed_point_64, full_math::MulDiv, liquidity_math, swap_math, tick_math,
+    big_num::{U128, U256},
+    fixed_point_64,
+    full_math::MulDiv,
+    liquidity_math, swap_math, tick_math,
 };
 use crate::states::*;
 use crate::util::*;
-use anchor_lang::{prelude::*, solana_program};
+use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 use std::cell::RefMut;
 use std::collections::VecDeque;
@@ -107,6 +110,55 @@ pub struct SwapState {
     pub liquidity: u128,
 }
 
+/// The denominator of the price impact in basis points
+pub const PRICE_IMPACT_DENOMINATOR_VALUE: u32 = 10_000;
+
+/// The limits of a swap independent of the amounts, no limit is applied if a field is `None`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapGuard {
+    /// The max change of the price from the pool price before the swap, in basis points
+    pub max_price_impact_bps: Option<u32>,
+    /// The max number of initialized ticks crossed by the swap
+    pub max_ticks_crossed: Option<u32>,
+}
+
+impl SwapGuard {
+    pub fn check_ticks_crossed(&self, ticks_crossed: u32) -> Result<()> {
+        if let Some(max_ticks_crossed) = self.max_ticks_crossed {
+            require_gte!(
+                max_ticks_crossed,
+                ticks_crossed,
+                ErrorCode::TooManyTicksCrossed
+            );
+        }
+        Ok(())
+    }
+
+    /// |price_after - price_before| / price_before must not exceed max_price_impact_bps / 10000
+    pub fn check_price_impact(
+        &self,
+        sqrt_price_x64_before: u128,
+        sqrt_price_x64_after: u128,
+    ) -> Result<()> {
+        if let Some(max_price_impact_bps) = self.max_price_impact_bps {
+            let price_before =
+                U256::from(sqrt_price_x64_before) * U256::from(sqrt_price_x64_before);
+            let price_after = U256::from(sqrt_price_x64_after) * U256::from(sqrt_price_x64_after);
+            let price_delta = if price_after > price_before {
+                price_after - price_before
+            } else {
+                price_before - price_after
+            };
+            require!(
+                price_delta * U256::from(PRICE_IMPACT_DENOMINATOR_VALUE)
+                    <= price_before * U256::from(max_price_impact_bps),
+                ErrorCode::PriceImpactExceeded
+            );
+        }
+        Ok(())
+    }
+}
+
 #[derive(Default)]
 struct StepComputations {
     // the price at the beginning of the step
@@ -136,11 +188,52 @@ pub fn swap_internal<'b, 'info>(
     zero_for_one: bool,
     is_base_input: bool,
     block_timestamp: u32,
+) -> Result<(u64, u64)> {
+    swap_internal_with_guard(
+        amm_config,
+        pool_state,
+        tick_array_states,
+        observation_state,
+        &mut [],
+        &mut [],
+        tickarray_bitmap_extension,
+        amount_specified,
+        sqrt_price_limit_x64,
+        zero_for_one,
+        is_base_input,
+        block_timestamp,
+        &SwapGuard::default(),
+    )
+}
+
+/// Same as `swap_internal`, aborts the swap if it exceeds the limits of the guard.
+/// `observation_extension` is the observations stored after the observation account struct,
+/// `limit_order_fills` is the limit order fills stored after each tick array struct, keyed by the start tick index.
+pub fn swap_internal_with_guard(
+    amm_config: &AmmConfig,
+    pool_state: &mut RefMut<PoolState>,
+    tick_array_states: &mut VecDeque<RefMut<TickArrayState>>,
+    observation_state: &mut RefMut<ObservationState>,
+    observation_extension: &mut [Observation],
+    limit_order_fills: &mut [(i32, RefMut<[LimitOrderFill]>)],
+    tickarray_bitmap_extension: &Option<TickArrayBitmapExtension>,
+    amount_specified: u64,
+    sqrt_price_limit_x64: u128,
+    zero_for_one: bool,
+    is_base_input: bool,
+    block_timestamp: u32,
+    guard: &SwapGuard,
 ) -> Result<(u64, u64)> {
     require!(amount_specified != 0, ErrorCode::ZeroAmountSpecified);
     if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap) {
         return err!(ErrorCode::NotApproved);
     }
+    // wait for the pool to be open
+    require_gt!(
+        block_timestamp as u64,
+        pool_state.open_time,
+        ErrorCode::PoolNotOpen
+    );
     require!(
         if zero_for_one {
             sqrt_price_limit_x64 < pool_state.sqrt_price_x64
@@ -153,8 +246,15 @@ pub fn swap_internal<'b, 'info>(
     );
 
     let liquidity_start = pool_state.liquidity;
+    let sqrt_price_x64_start = pool_state.sqrt_price_x64;
+    let mut ticks_crossed = 0u32;
 
     let updated_reward_infos = pool_state.update_reward_infos(block_timestamp as u64)?;
+    pool_state.update_seconds_per_liquidity(block_timestamp as u64);
+
+    let launch_fee_destination = pool_state.launch_fee.excess_fee_destination;
+    let protocol_fee_rate = pool_state.get_protocol_fee_rate(amm_config.protocol_fee_rate);
+    let fund_fee_rate = pool_state.get_fund_fee_rate(amm_config.fund_fee_rate);
 
     let mut state = SwapState {
         amount_specified_remaining: amount_specified,
@@ -210,7 +310,7 @@ pub fn swap_internal<'b, 'info>(
             state.tick,
             state.liquidity,
             state.protocol_fee,
-            amm_config.protocol_fee_rate
+            protocol_fee_rate
         );
         // Save these three pieces of information for PriceChangeEvent
         // let tick_before = state.tick;
@@ -297,12 +397,23 @@ pub fn swap_internal<'b, 'info>(
             state.liquidity,
             state.amount_specified_remaining
         );
+        // the dynamic fee of every step follows the volatility of the price moved by the swap so far
+        let trade_fee_rate = pool_state.get_trade_fee_rate_at_tick(
+            amm_config.trade_fee_rate,
+            state.tick,
+            block_timestamp as u64,
+        );
+        let launch_fee_rate = pool_state.get_launch_fee_rate_at_tick(
+            amm_config.trade_fee_rate,
+            state.tick,
+            block_timestamp as u64,
+        );
         let swap_step = swap_math::compute_swap_step(
             step.sqrt_price_start_x64,
             target_price,
             state.liquidity,
             state.amount_specified_remaining,
-            amm_config.trade_fee_rate,
+            trade_fee_rate,
             is_base_input,
             zero_for_one,
             block_timestamp,
@@ -344,11 +455,25 @@ pub fn swap_internal<'b, 'info>(
                 .ok_or(ErrorCode::CalculateOverflow)?;
         }
 
+        // the launch fee above the normal fee rate is not shared by the protocol fee and fund fee
+        let launch_fee_amount = if launch_fee_rate > 0 {
+            U128::from(step.fee_amount)
+                .mul_div_floor(U128::from(launch_fee_rate), U128::from(trade_fee_rate))
+                .ok_or(ErrorCode::CalculateOverflow)?
+                .as_u64()
+        } else {
+            0
+        };
+        step.fee_amount = step
+            .fee_amount
+            .checked_sub(launch_fee_amount)
+            .ok_or(ErrorCode::CalculateOverflow)?;
+
         let step_fee_amount = step.fee_amount;
         // if the protocol fee is on, calculate how much is owed, decrement fee_amount, and increment protocol_fee
-        if amm_config.protocol_fee_rate > 0 {
+        if protocol_fee_rate > 0 {
             let delta = U128::from(step_fee_amount)
-                .checked_mul(amm_config.protocol_fee_rate.into())
+                .checked_mul(protocol_fee_rate.into())
                 .unwrap()
                 .checked_div(FEE_RATE_DENOMINATOR_VALUE.into())
                 .unwrap()
@@ -357,9 +482,9 @@ pub fn swap_internal<'b, 'info>(
             state.protocol_fee = state.protocol_fee.checked_add(delta).unwrap();
         }
         // if the fund fee is on, calculate how much is owed, decrement fee_amount, and increment fund_fee
-        if amm_config.fund_fee_rate > 0 {
+        if fund_fee_rate > 0 {
             let delta = U128::from(step_fee_amount)
-                .checked_mul(amm_config.fund_fee_rate.into())
+                .checked_mul(fund_fee_rate.into())
                 .unwrap()
                 .checked_div(FEE_RATE_DENOMINATOR_VALUE.into())
                 .unwrap()
@@ -367,6 +492,26 @@ pub fn swap_internal<'b, 'info>(
             step.fee_amount = step.fee_amount.checked_sub(delta).unwrap();
             state.fund_fee = state.fund_fee.checked_add(delta).unwrap();
         }
+        match launch_fee_destination {
+            LaunchFeeSchedule::DESTINATION_FUND_FEE => {
+                state.fund_fee = state
+                    .fund_fee
+                    .checked_add(launch_fee_amount)
+                    .ok_or(ErrorCode::CalculateOverflow)?;
+            }
+            LaunchFeeSchedule::DESTINATION_PROTOCOL_FEE => {
+                state.protocol_fee = state
+                    .protocol_fee
+                    .checked_add(launch_fee_amount)
+                    .ok_or(ErrorCode::CalculateOverflow)?;
+            }
+            _ => {
+                step.fee_amount = step
+                    .fee_amount
+                    .checked_add(launch_fee_amount)
+                    .ok_or(ErrorCode::CalculateOverflow)?;
+            }
+        }
 
         // update global fee tracker
         if state.liquidity > 0 {
@@ -393,7 +538,10 @@ pub fn swap_internal<'b, 'info>(
             if step.initialized {
                 #[cfg(feature = "enable-log")]
                 msg!("loading next tick {}", step.tick_next);
+                ticks_crossed += 1;
+                guard.check_ticks_crossed(ticks_crossed)?;
 
+                next_initialized_tick.sync_reward_generations(pool_state.reward_generations);
                 let mut liquidity_net = next_initialized_tick.cross(
                     if zero_for_one {
                         state.fee_growth_global_x64
@@ -406,13 +554,79 @@ pub fn swap_internal<'b, 'info>(
                         state.fee_growth_global_x64
                     },
                     &updated_reward_infos,
+                    pool_state.seconds_per_liquidity_global_x64,
+                    block_timestamp as u64,
                 );
+                // the limit orders of the tick are filled, their liquidity won't be added back if the price reverts
+                let order_fill_epoch = next_initialized_tick.order_fill_epoch;
+                let order_liquidity = next_initialized_tick.fill_orders(zero_for_one)?;
                 // update tick_state to tick_array account
                 tick_array_current.update_tick_state(
                     next_initialized_tick.tick,
                     pool_state.tick_spacing.into(),
                     *next_initialized_tick,
                 )?;
+                if order_liquidity != 0 {
+                    // the other tick of the orders is always in the same tick array
+                    let order_tick_index = if zero_for_one {
+                        step.tick_next + i32::from(pool_state.tick_spacing)
+                    } else {
+                        step.tick_next - i32::from(pool_state.tick_spacing)
+                    };
+                    let order_tick_state = tick_array_current
+                        .get_tick_state_mut(order_tick_index, pool_state.tick_spacing)?;
+                    order_tick_state.sync_reward_generations(pool_state.reward_generations);
+                    order_tick_state.remove_order_liquidity_net(zero_for_one, order_liquidity)?;
+                    let order_tick_state = *order_tick_state;
+
+                    // the growth inside of the orders stops at the fill, it's recorded so that the
+                    // orders can be paid even if the price goes back into their range later
+                    let (tick_lower_state, tick_upper_state) = if zero_for_one {
+                        (&*next_initialized_tick, &order_tick_state)
+                    } else {
+                        (&order_tick_state, &*next_initialized_tick)
+                    };
+                    let tick_after_cross = if zero_for_one {
+                        step.tick_next - 1
+                    } else {
+                        step.tick_next
+                    };
+                    let (fee_growth_inside_0_x64, fee_growth_inside_1_x64) = get_fee_growth_inside(
+                        tick_lower_state,
+                        tick_upper_state,
+                        tick_after_cross,
+                        if zero_for_one {
+                            state.fee_growth_global_x64
+                        } else {
+                            pool_state.fee_growth_global_0_x64
+                        },
+                        if zero_for_one {
+                            pool_state.fee_growth_global_1_x64
+                        } else {
+                            state.fee_growth_global_x64
+                        },
+                    );
+                    let reward_growths_inside_x64 = get_reward_growths_inside(
+                        tick_lower_state,
+                        tick_upper_state,
+                        tick_after_cross,
+                        &updated_reward_infos,
+                    );
+                    let tick_array_start_index = tick_array_current.start_tick_index;
+                    if let Some((_, fills)) = limit_order_fills
+                        .iter_mut()
+                        .find(|(start_tick_index, _)| *start_tick_index == tick_array_start_index)
+                    {
+                        LimitOrderFill::record_fill(
+                            fills,
+                            next_initialized_tick.tick,
+                            order_fill_epoch,
+                            fee_growth_inside_0_x64,
+                            fee_growth_inside_1_x64,
+                            reward_growths_inside_x64,
+                        );
+                    }
+                }
 
                 if zero_for_one {
                     liquidity_net = liquidity_net.neg();
@@ -445,9 +659,9 @@ pub fn swap_internal<'b, 'info>(
             state.tick,
             state.liquidity,
             state.protocol_fee,
-            amm_config.protocol_fee_rate,
+            protocol_fee_rate,
             state.fund_fee,
-            amm_config.fund_fee_rate,
+            fund_fee_rate,
         );
         // emit!(PriceChangeEvent {
         //     pool_state: pool_state.key(),
@@ -460,10 +674,17 @@ pub fn swap_internal<'b, 'info>(
         //     zero_for_one,
         // });
     }
+    guard.check_price_impact(sqrt_price_x64_start, state.sqrt_price_x64)?;
+    pool_state.update_volatility_accumulator(state.tick, block_timestamp as u64);
     // update tick
     if state.tick != pool_state.tick_current {
         // update the previous tick to the observation
-        observation_state.update(block_timestamp, pool_state.tick_current);
+        observation_state.update(
+            observation_extension,
+            block_timestamp,
+            pool_state.tick_current,
+            pool_state.seconds_per_liquidity_global_x64,
+        );
         pool_state.tick_current = state.tick;
     }
     pool_state.sqrt_price_x64 = state.sqrt_price_x64;
@@ -556,12 +777,11 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
     sqrt_price_limit_x64: u128,
     is_base_input: bool,
 ) -> Result<u64> {
-    let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
-
     let amount_0;
     let amount_1;
     let zero_for_one;
     let swap_price_before;
+    let trade_fee_rate;
 
     let input_balance_before = ctx.input_vault.amount;
     let output_balance_before = ctx.output_vault.amount;
@@ -571,9 +791,6 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
         let pool_state = &mut ctx.pool_state.load_mut()?;
         zero_for_one = ctx.input_vault.mint == pool_state.token_mint_0;
 
-        // wait for the pool to be open
-        require_gt!(block_timestamp, pool_state.open_time);
-
         require!(
             if zero_for_one {
                 ctx.input_vault.key() == pool_state.token_vault_0
@@ -584,10 +801,19 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
             },
             ErrorCode::InvalidInputPoolVault
         );
+        let pool_permission_accounts = check_pool_permission(
+            pool_state.is_permissioned(),
+            pool_state.key(),
+            ctx.signer.key,
+            remaining_accounts,
+        )?;
 
         let mut tickarray_bitmap_extension = None;
         let tick_array_states = &mut VecDeque::new();
-        tick_array_states.push_back(ctx.tick_array_state.load_mut()?);
+        let limit_order_fills = &mut Vec::new();
+        let (tick_array_state, fills) = load_tick_array_mut(ctx.tick_array_state.as_ref())?;
+        limit_order_fills.push((tick_array_state.start_tick_index, fills));
+        tick_array_states.push_back(tick_array_state);
 
         let tick_array_bitmap_extension_key = TickArrayBitmapExtension::key(pool_state.key());
         for account_info in remaining_accounts.into_iter() {
@@ -599,14 +825,27 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
                 );
                 continue;
             }
-            tick_array_states.push_back(AccountLoad::load_data_mut(account_info)?);
+            if is_pool_permission_account(account_info, &pool_permission_accounts) {
+                continue;
+            }
+            let (tick_array_state, fills) = load_tick_array_mut(account_info)?;
+            limit_order_fills.push((tick_array_state.start_tick_index, fills));
+            tick_array_states.push_back(tick_array_state);
         }
 
-        (amount_0, amount_1) = swap_internal(
+        trade_fee_rate = pool_state.get_trade_fee_rate(
+            ctx.amm_config.trade_fee_rate,
+            u64::from(oracle::block_timestamp()),
+        );
+        let (mut observation_state, mut observation_extension) =
+            load_observation_state_mut(ctx.observation_state)?;
+        (amount_0, amount_1) = swap_internal_with_guard(
             &ctx.amm_config,
             pool_state,
             tick_array_states,
-            &mut ctx.observation_state.load_mut()?,
+            &mut observation_state,
+            &mut observation_extension,
+            limit_order_fills,
             &tickarray_bitmap_extension,
             amount_specified,
             if sqrt_price_limit_x64 == 0 {
@@ -621,6 +860,7 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
             zero_for_one,
             is_base_input,
             oracle::block_timestamp(),
+            &SwapGuard::default(),
         )?;
 
         #[cfg(feature = "enable-log")]
@@ -661,6 +901,7 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
             &ctx.token_program,
             None,
             amount_0,
+            &[],
         )?;
         if vault_1.amount <= amount_1 {
             // freeze pool, disable all instructions
@@ -675,6 +916,7 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
             &ctx.token_program,
             None,
             amount_1,
+            &[],
         )?;
     } else {
         transfer_from_user_to_pool_vault(
@@ -685,6 +927,7 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
             &ctx.token_program,
             None,
             amount_1,
+            &[],
         )?;
         if vault_0.amount <= amount_0 {
             // freeze pool, disable all instructions
@@ -698,6 +941,7 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
             &ctx.token_program,
             None,
             amount_0,
+            &[],
         )?;
     }
     ctx.output_vault.reload()?;
@@ -716,7 +960,8 @@ pub fn exact_internal<'b, 'c: 'info, 'info>(
         zero_for_one,
         sqrt_price_x64: pool_state.sqrt_price_x64,
         liquidity: pool_state.liquidity,
-        tick: pool_state.tick_current
+        tick: pool_state.tick_current,
+        fee_rate: trade_fee_rate,
     });
     if zero_for_one {
         require_gt!(swap_price_before, pool_state.sqrt_price_x64);
@@ -958,6 +1203,8 @@ mod swap_test {
                             0,
                             false,
                             &[RewardInfo::default(); 3],
+                            0,
+                            0,
                         )
                         .unwrap();
 
@@ -979,6 +1226,8 @@ mod swap_test {
                             0,
                             false,
                             &[RewardInfo::default(); 3],
+                            0,
+                            0,
                         )
                         .unwrap();
                 }
@@ -1006,6 +1255,8 @@ mod swap_test {
                             0,
                             true,
                             &[RewardInfo::default(); 3],
+                            0,
+                            0,
                         )
                         .unwrap();
 
@@ -1028,6 +1279,8 @@ mod swap_test {
                             0,
                             true,
                             &[RewardInfo::default(); 3],
+                            0,
+                            0,
                         )
                         .unwrap();
                 }
@@ -1683,6 +1936,209 @@ mod swap_test {
         }
     }
 
+    mod swap_guard_test {
+        use super::*;
+        use crate::error::ErrorCode;
+
+        #[test]
+        fn check_price_impact_test() {
+            // price from 10000 to 10201 and 9801
+            let guard = SwapGuard {
+                max_price_impact_bps: Some(201),
+                max_ticks_crossed: None,
+            };
+            assert!(guard.check_price_impact(100, 101).is_ok());
+            assert!(guard.check_price_impact(100, 99).is_ok());
+            let guard = SwapGuard {
+                max_price_impact_bps: Some(200),
+                max_ticks_crossed: None,
+            };
+            assert_eq!(
+                guard.check_price_impact(100, 101).unwrap_err(),
+                ErrorCode::PriceImpactExceeded.into()
+            );
+            assert!(guard.check_price_impact(100, 99).is_ok());
+            assert!(SwapGuard::default()
+                .check_price_impact(100, u128::MAX >> 32)
+                .is_ok());
+        }
+
+        fn swap_with_guard(guard: SwapGuard) -> Result<(u64, u64)> {
+            let (amm_config, pool_state, tick_array_states, observation_state) = build_swap_param(
+                -32395,
+                60,
+                3651942632306380802,
+                5124165121219,
+                vec![
+                    TickArrayInfo {
+                        start_tick_index: -32400,
+                        ticks: vec![
+                            build_tick(-32400, 277065331032, -277065331032).take(),
+                            build_tick(-29220, 1330680689, -1330680689).take(),
+                            build_tick(-28860, 6408486554, -6408486554).take(),
+                        ],
+                    },
+                    TickArrayInfo {
+                        start_tick_index: -36000,
+                        ticks: vec![
+                            build_tick(-32460, 1194569667438, 536061033698).take(),
+                            build_tick(-32520, 790917615645, 790917615645).take(),
+                        ],
+                    },
+                ],
+            );
+            // cross the tick(-32400) and tick(-32460), but not reach tick(-32520)
+            let result = swap_internal_with_guard(
+                &amm_config,
+                &mut pool_state.borrow_mut(),
+                &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                &mut observation_state.borrow_mut(),
+                &mut [],
+                &mut [],
+                &None,
+                121882400020,
+                tick_math::get_sqrt_price_at_tick(-32500).unwrap(),
+                true,
+                true,
+                oracle::block_timestamp_mock() as u32,
+                &guard,
+            );
+            result
+        }
+
+        #[test]
+        fn max_ticks_crossed_test() {
+            assert!(swap_with_guard(SwapGuard::default()).is_ok());
+            assert!(swap_with_guard(SwapGuard {
+                max_price_impact_bps: None,
+                max_ticks_crossed: Some(2),
+            })
+            .is_ok());
+            assert_eq!(
+                swap_with_guard(SwapGuard {
+                    max_price_impact_bps: None,
+                    max_ticks_crossed: Some(1),
+                })
+                .unwrap_err(),
+                ErrorCode::TooManyTicksCrossed.into()
+            );
+        }
+
+        #[test]
+        fn max_price_impact_test() {
+            // the price falls about 1.2% from tick -32395 to tick -32500 at most
+            assert!(swap_with_guard(SwapGuard {
+                max_price_impact_bps: Some(200),
+                max_ticks_crossed: None,
+            })
+            .is_ok());
+            assert_eq!(
+                swap_with_guard(SwapGuard {
+                    max_price_impact_bps: Some(10),
+                    max_ticks_crossed: None,
+                })
+                .unwrap_err(),
+                ErrorCode::PriceImpactExceeded.into()
+            );
+        }
+    }
+
+    mod open_time_test {
+        use super::*;
+        use crate::error::ErrorCode;
+
+        #[test]
+        fn swap_before_open_time_test() {
+            let tick_current = -32395;
+            let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(tick_current).unwrap();
+            let (amm_config, pool_state, tick_array_states, observation_state) = build_swap_param(
+                tick_current,
+                60,
+                sqrt_price_x64,
+                5124165121219,
+                vec![TickArrayInfo {
+                    start_tick_index: -32400,
+                    ticks: vec![build_tick(-32400, 277065331032, -277065331032).take()],
+                }],
+            );
+            let block_timestamp = oracle::block_timestamp_mock();
+            for (open_time, is_open) in [
+                (block_timestamp + 3600, false),
+                (block_timestamp, false),
+                (block_timestamp - 1, true),
+            ] {
+                pool_state.borrow_mut().open_time = open_time;
+                let result = swap_internal(
+                    &amm_config,
+                    &mut pool_state.borrow_mut(),
+                    &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                    &mut observation_state.borrow_mut(),
+                    &None,
+                    1000,
+                    tick_math::get_sqrt_price_at_tick(-32400).unwrap(),
+                    true,
+                    true,
+                    block_timestamp as u32,
+                );
+                if is_open {
+                    assert!(result.is_ok());
+                } else {
+                    assert_eq!(result.unwrap_err(), ErrorCode::PoolNotOpen.into());
+                }
+            }
+        }
+    }
+
+    mod observation_test {
+        use super::*;
+
+        #[test]
+        fn observe_twap_after_swap_in_same_timestamp_test() {
+            let tick_current = -28776;
+            let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(tick_current).unwrap();
+            let (amm_config, pool_state, tick_array_states, observation_state) = build_swap_param(
+                tick_current,
+                60,
+                sqrt_price_x64,
+                624165121219,
+                vec![TickArrayInfo {
+                    start_tick_index: -32400,
+                    ticks: vec![
+                        build_tick(-32400, 277065331032, -277065331032).take(),
+                        build_tick(-29220, 1330680689, -1330680689).take(),
+                        build_tick(-28860, 6408486554, -6408486554).take(),
+                    ],
+                }],
+            );
+            let block_timestamp = oracle::block_timestamp_mock() as u32;
+            observation_state
+                .borrow_mut()
+                .update(&mut [], block_timestamp - 100, tick_current, 0);
+
+            // the swap moves the price far away and the twap is read in the same timestamp
+            swap_internal(
+                &amm_config,
+                &mut pool_state.borrow_mut(),
+                &mut get_tick_array_states_mut(&tick_array_states).borrow_mut(),
+                &mut observation_state.borrow_mut(),
+                &None,
+                12188240002,
+                tick_math::get_sqrt_price_at_tick(-32400).unwrap(),
+                true,
+                true,
+                block_timestamp,
+            )
+            .unwrap();
+            let tick_after_swap = pool_state.borrow().tick_current;
+            assert!(tick_after_swap < -28860);
+            let twap_tick = observation_state
+                .borrow()
+                .get_twap_tick(&[], block_timestamp, 100, tick_after_swap)
+                .unwrap();
+            assert_eq!(twap_tick, tick_current);
+        }
+    }
+
     #[test]
     fn explain_why_zero_for_one_less_or_equal_current_tick() {
         let tick_current = -28859;
@@ -1,6 +1,8 @@
 use crate::error::ErrorCode;
 use crate::states::*;
+use crate::swap::SwapGuard;
 use crate::swap_v2::{exact_internal_v2, SwapSingleV2};
+use crate::util::{check_deadline, NativeAccounts};
 use anchor_lang::prelude::*;
 use anchor_spl::memo::Memo;
 use anchor_spl::{
@@ -13,9 +15,9 @@ pub struct SwapRouterBaseIn<'info> {
     /// The user performing the swap
     pub payer: Signer<'info>,
 
-    /// The token account that pays input tokens for the swap
+    /// CHECK: The token account that pays input tokens for the swap, validated by the token program on transfer
     #[account(mut)]
-    pub input_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub input_token_account: UncheckedAccount<'info>,
 
     /// The mint of input token
     #[account(mut)]
@@ -28,43 +30,54 @@ pub struct SwapRouterBaseIn<'info> {
 
     /// Memo program
     pub memo_program: Program<'info, Memo>,
+    // remaining accounts, for every hop of the route, the count of the accounts of every hop is
+    // passed in `hop_account_counts`:
+    // amm_config
+    // pool_state
+    // output_token_account
+    // input_vault
+    // output_vault
+    // output_token_mint
+    // observation_state
+    // tickarray_bitmap_extension: must add account if need regardless the sequence
+    // tick_array_account_1
+    // tick_array_account_...
 }
 
-pub fn swap_router_base_in<'a, 'b, 'c: 'info, 'info>(
-    ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseIn<'info>>,
-    amount_in: u64,
-    amount_out_minimum: u64,
-) -> Result<()> {
-    let mut amount_in_internal = amount_in;
-    let mut input_token_account = Box::new(ctx.accounts.input_token_account.clone());
-    let mut input_token_mint = Box::new(ctx.accounts.input_token_mint.clone());
-    let mut accounts: &[AccountInfo] = ctx.remaining_accounts;
-    while !accounts.is_empty() {
-        let mut remaining_accounts = accounts.iter();
-        let account_info = remaining_accounts.next().unwrap();
-        if accounts.len() != ctx.remaining_accounts.len()
-            && account_info.data_len() != AmmConfig::LEN
-        {
-            accounts = remaining_accounts.as_slice();
-            continue;
-        }
-        let amm_config = Box::new(Account::<AmmConfig>::try_from(account_info)?);
-        let pool_state_loader =
-            AccountLoader::<PoolState>::try_from(remaining_accounts.next().unwrap())?;
-        let output_token_account = Box::new(InterfaceAccount::<TokenAccount>::try_from(
-            &remaining_accounts.next().unwrap(),
-        )?);
-        let input_vault = Box::new(InterfaceAccount::<TokenAccount>::try_from(
-            remaining_accounts.next().unwrap(),
-        )?);
-        let output_vault = Box::new(InterfaceAccount::<TokenAccount>::try_from(
-            remaining_accounts.next().unwrap(),
-        )?);
-        let output_token_mint = Box::new(InterfaceAccount::<Mint>::try_from(
-            remaining_accounts.next().unwrap(),
-        )?);
-        let observation_state =
-            AccountLoader::<ObservationState>::try_from(remaining_accounts.next().unwrap())?;
+/// A hop of the route loaded from the remaining accounts
+pub struct RouteHop<'c, 'info> {
+    pub swap_accounts: SwapSingleV2<'info>,
+    /// The tickarray_bitmap_extension and tick array accounts of the hop
+    pub tick_array_accounts: &'c [AccountInfo<'info>],
+}
+
+/// Loads the hops of a route from the remaining accounts, `hop_account_counts` is the count of the
+/// remaining accounts of every hop. The output token account of a hop is the input token account of the next hop.
+pub fn load_route_hops<'c: 'info, 'info>(
+    payer: &Signer<'info>,
+    input_token_account: &UncheckedAccount<'info>,
+    input_token_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Program<'info, Token>,
+    token_program_2022: &Program<'info, Token2022>,
+    memo_program: &Program<'info, Memo>,
+    remaining_accounts: &'c [AccountInfo<'info>],
+    hop_account_counts: &[u8],
+) -> Result<Vec<RouteHop<'c, 'info>>> {
+    let mut hops = Vec::new();
+    let mut input_token_account = input_token_account.clone();
+    let mut input_token_mint = Box::new(input_token_mint.clone());
+    let mut accounts: &'c [AccountInfo<'info>] = remaining_accounts;
+    for hop_account_count in hop_account_counts {
+        let hop_len = usize::from(*hop_account_count);
+        require_gte!(hop_len, 7, ErrorCode::InvalidAccount);
+        require_gte!(accounts.len(), hop_len, ErrorCode::InvalidAccount);
+        let amm_config = Box::new(Account::<AmmConfig>::try_from(&accounts[0])?);
+        let pool_state_loader = AccountLoader::<PoolState>::try_from(&accounts[1])?;
+        let output_token_account = UncheckedAccount::try_from(&accounts[2]);
+        let input_vault = Box::new(InterfaceAccount::<TokenAccount>::try_from(&accounts[3])?);
+        let output_vault = Box::new(InterfaceAccount::<TokenAccount>::try_from(&accounts[4])?);
+        let output_token_mint = Box::new(InterfaceAccount::<Mint>::try_from(&accounts[5])?);
+        let observation_state = AccountLoader::<ObservationState>::try_from(&accounts[6])?;
 
         {
             let pool_state = pool_state_loader.load()?;
@@ -73,33 +86,76 @@ pub fn swap_router_base_in<'a, 'b, 'c: 'info, 'info>(
             // check ammConfig account is associate with the pool
             require_keys_eq!(pool_state.amm_config, amm_config.key());
         }
-
-        // solana_program::log::sol_log_compute_units();
-        accounts = remaining_accounts.as_slice();
-        amount_in_internal = exact_internal_v2(
-            &mut SwapSingleV2 {
-                payer: ctx.accounts.payer.clone(),
+        require_keys_eq!(output_token_mint.key(), output_vault.mint);
+        hops.push(RouteHop {
+            swap_accounts: SwapSingleV2 {
+                payer: payer.clone(),
                 amm_config,
                 input_token_account: input_token_account.clone(),
                 pool_state: pool_state_loader,
                 output_token_account: output_token_account.clone(),
-                input_vault: input_vault.clone(),
-                output_vault: output_vault.clone(),
+                input_vault,
+                output_vault,
                 input_vault_mint: input_token_mint.clone(),
                 output_vault_mint: output_token_mint.clone(),
                 observation_state,
-                token_program: ctx.accounts.token_program.clone(),
-                token_program_2022: ctx.accounts.token_program_2022.clone(),
-                memo_program: ctx.accounts.memo_program.clone(),
+                token_program: token_program.clone(),
+                token_program_2022: token_program_2022.clone(),
+                memo_program: memo_program.clone(),
             },
-            accounts,
+            tick_array_accounts: &accounts[7..hop_len],
+        });
+        // output token is the new swap input token
+        input_token_account = output_token_account;
+        input_token_mint = output_token_mint;
+        accounts = &accounts[hop_len..];
+    }
+    require!(
+        !hops.is_empty() && accounts.is_empty(),
+        ErrorCode::InvalidAccount
+    );
+    Ok(hops)
+}
+
+pub fn swap_router_base_in<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseIn<'info>>,
+    amount_in: u64,
+    amount_out_minimum: u64,
+    deadline: Option<i64>,
+    hop_account_counts: Vec<u8>,
+) -> Result<()> {
+    check_deadline(deadline)?;
+    let mut hops = load_route_hops(
+        &ctx.accounts.payer,
+        &ctx.accounts.input_token_account,
+        &ctx.accounts.input_token_mint,
+        &ctx.accounts.token_program,
+        &ctx.accounts.token_program_2022,
+        &ctx.accounts.memo_program,
+        ctx.remaining_accounts,
+        &hop_account_counts,
+    )?;
+
+    // the route does not wrap or unwrap SOL
+    let native_accounts = NativeAccounts::load(&ctx.accounts.payer, None, &[]);
+    let mut route = Vec::with_capacity(hops.len());
+    let mut amount_in_internal = amount_in;
+    for hop in hops.iter_mut() {
+        let amount_out_internal = exact_internal_v2(
+            &mut hop.swap_accounts,
+            hop.tick_array_accounts,
+            &native_accounts,
             amount_in_internal,
             0,
             true,
+            &SwapGuard::default(),
         )?;
-        // output token is the new swap input token
-        input_token_account = output_token_account;
-        input_token_mint = output_token_mint;
+        route.push(SwapRouterHop {
+            pool_state: hop.swap_accounts.pool_state.key(),
+            amount_in: amount_in_internal,
+            amount_out: amount_out_internal,
+        });
+        amount_in_internal = amount_out_internal;
     }
     require_gte!(
         amount_in_internal,
@@ -107,5 +163,15 @@ pub fn swap_router_base_in<'a, 'b, 'c: 'info, 'info>(
         ErrorCode::TooLittleOutputReceived
     );
 
+    emit!(SwapRouterEvent {
+        sender: ctx.accounts.payer.key(),
+        input_token_mint: ctx.accounts.input_token_mint.key(),
+        output_token_mint: hops.last().unwrap().swap_accounts.output_vault_mint.key(),
+        amount_in,
+        amount_out: amount_in_internal,
+        is_base_input: true,
+        hops: route,
+    });
+
     Ok(())
 }
@@ -0,0 +1,120 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::swap::SwapGuard;
+use crate::swap_router_base_in::load_route_hops;
+use crate::swap_v2::{swap_compute_v2, swap_settle_v2};
+use crate::util::{check_deadline, NativeAccounts};
+use anchor_lang::prelude::*;
+use anchor_spl::memo::Memo;
+use anchor_spl::{
+    token::Token,
+    token_interface::{Mint, Token2022},
+};
+
+#[derive(Accounts)]
+pub struct SwapRouterBaseOut<'info> {
+    /// The user performing the swap
+    pub payer: Signer<'info>,
+
+    /// CHECK: The token account that pays input tokens for the swap, validated by the token program on transfer
+    #[account(mut)]
+    pub input_token_account: UncheckedAccount<'info>,
+
+    /// The mint of input token
+    #[account(mut)]
+    pub input_token_mint: InterfaceAccount<'info, Mint>,
+
+    /// SPL program for token transfers
+    pub token_program: Program<'info, Token>,
+    /// SPL program 2022 for token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// Memo program
+    pub memo_program: Program<'info, Memo>,
+    // remaining accounts, same as swap_router_base_in
+}
+
+pub fn swap_router_base_out<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseOut<'info>>,
+    amount_out: u64,
+    amount_in_maximum: u64,
+    deadline: Option<i64>,
+    hop_account_counts: Vec<u8>,
+) -> Result<()> {
+    check_deadline(deadline)?;
+    let mut hops = load_route_hops(
+        &ctx.accounts.payer,
+        &ctx.accounts.input_token_account,
+        &ctx.accounts.input_token_mint,
+        &ctx.accounts.token_program,
+        &ctx.accounts.token_program_2022,
+        &ctx.accounts.memo_program,
+        ctx.remaining_accounts,
+        &hop_account_counts,
+    )?;
+    // the hops are computed in reverse against the pool states before the route, a pool can't be
+    // swapped twice in a route as its second swap would be settled at a stale price
+    for (i, hop) in hops.iter().enumerate() {
+        let pool_id = hop.swap_accounts.pool_state.key();
+        require!(
+            hops[..i]
+                .iter()
+                .all(|prev_hop| prev_hop.swap_accounts.pool_state.key() != pool_id),
+            ErrorCode::RepeatedPoolInRoute
+        );
+    }
+
+    // the route does not wrap or unwrap SOL
+    let native_accounts = NativeAccounts::load(&ctx.accounts.payer, None, &[]);
+
+    // Walk the route in reverse, the exact output amount of a hop is the input amount of the next hop
+    let mut swap_results = Vec::with_capacity(hops.len());
+    let mut amount_out_internal = amount_out;
+    for hop in hops.iter_mut().rev() {
+        let swap_result = swap_compute_v2(
+            &mut hop.swap_accounts,
+            hop.tick_array_accounts,
+            &native_accounts,
+            amount_out_internal,
+            0,
+            false,
+            &SwapGuard::default(),
+        )?;
+        swap_results.push((amount_out_internal, swap_result));
+        amount_out_internal = swap_result.transfer_amount_in();
+    }
+    swap_results.reverse();
+
+    // Settle the hops in order, so every intermediate token is received before it is paid
+    let mut route = Vec::with_capacity(hops.len());
+    for (hop, (amount_specified, swap_result)) in hops.iter_mut().zip(swap_results.iter()) {
+        let (hop_amount_in, hop_amount_out) = swap_settle_v2(
+            &mut hop.swap_accounts,
+            hop.tick_array_accounts,
+            &native_accounts,
+            swap_result,
+            *amount_specified,
+            0,
+            false,
+        )?;
+        route.push(SwapRouterHop {
+            pool_state: hop.swap_accounts.pool_state.key(),
+            amount_in: hop_amount_in,
+            amount_out: hop_amount_out,
+        });
+    }
+    let amount_in = route.first().unwrap().amount_in;
+    require_gte!(amount_in_maximum, amount_in, ErrorCode::TooMuchInputPaid);
+
+    emit!(SwapRouterEvent {
+        sender: ctx.accounts.payer.key(),
+        input_token_mint: ctx.accounts.input_token_mint.key(),
+        output_token_mint: hops.last().unwrap().swap_accounts.output_vault_mint.key(),
+        amount_in,
+        amount_out: route.last().unwrap().amount_out,
+        is_base_input: false,
+        hops: route,
+    });
+
+    Ok(())
+}
@@ -3,10 +3,10 @@ use std::ops::Deref;
 
 use crate::error::ErrorCode;
 use crate::libraries::tick_math;
-use crate::swap::swap_internal;
+use crate::swap::{swap_internal_with_guard, SwapGuard};
 use crate::util::*;
 use crate::{states::*, util};
-use anchor_lang::{prelude::*, solana_program};
+use anchor_lang::prelude::*;
 use anchor_spl::memo::Memo;
 use anchor_spl::token::Token;
 use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
@@ -26,13 +26,15 @@ pub struct SwapSingleV2<'info> {
     #[account(mut)]
     pub pool_state: AccountLoader<'info, PoolState>,
 
-    /// The user token account for input token
+    /// CHECK: The user token account for input token, validated by the token program on transfer.
+    /// If the input mint is the native mint, it can be the payer itself to wrap SOL.
     #[account(mut)]
-    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub input_token_account: UncheckedAccount<'info>,
 
-    /// The user token account for output token
+    /// CHECK: The user token account for output token, validated by the token program on transfer.
+    /// If the output mint is the native mint, it can be the payer itself to unwrap SOL.
     #[account(mut)]
-    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub output_token_account: UncheckedAccount<'info>,
 
     /// The vault token account for input token
     #[account(mut)]
@@ -68,31 +70,97 @@ pub struct SwapSingleV2<'info> {
     pub output_vault_mint: Box<InterfaceAccount<'info, Mint>>,
     // remaining accounts
     // tickarray_bitmap_extension: must add account if need regardless the sequence
+    // system_program and native temp account: must add accounts if wrap or unwrap SOL regardless the sequence
+    // transfer hook program, extra account metas and extra accounts: must add accounts if the mint has a transfer hook regardless the sequence,
+    // an account not required by the transfer hooks fails the swap
     // tick_array_account_1
     // tick_array_account_2
     // tick_array_account_...
 }
 
+/// The result of a swap calculated by `swap_compute_v2` and not settled yet
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SwapComputeResult {
+    pub zero_for_one: bool,
+    /// The sqrt price of the pool before the swap
+    pub swap_price_before: u128,
+    /// The trade fee rate applied to the swap
+    pub trade_fee_rate: u32,
+    /// The amount of token_0 and token_1 swapped by the pool
+    pub amount_0: u64,
+    pub amount_1: u64,
+    /// The transfer fee of token_0 and token_1 charged by the token 2022 extension
+    pub transfer_fee_0: u64,
+    pub transfer_fee_1: u64,
+}
+
+impl SwapComputeResult {
+    /// The amount transferred from the user to the pool vault, include the transfer fee
+    pub fn transfer_amount_in(&self) -> u64 {
+        if self.zero_for_one {
+            self.amount_0 + self.transfer_fee_0
+        } else {
+            self.amount_1 + self.transfer_fee_1
+        }
+    }
+}
+
 /// Performs a single exact input/output swap
 /// if is_base_input = true, return value is the max_amount_out, otherwise is min_amount_in
 pub fn exact_internal_v2<'c: 'info, 'info>(
     ctx: &mut SwapSingleV2<'info>,
     remaining_accounts: &'c [AccountInfo<'info>],
+    native_accounts: &NativeAccounts<'info>,
     amount_specified: u64,
     sqrt_price_limit_x64: u128,
     is_base_input: bool,
+    guard: &SwapGuard,
 ) -> Result<u64> {
-    // invoke_memo_instruction(SWAP_MEMO_MSG, ctx.memo_program.to_account_info())?;
+    let swap_result = swap_compute_v2(
+        ctx,
+        remaining_accounts,
+        native_accounts,
+        amount_specified,
+        sqrt_price_limit_x64,
+        is_base_input,
+        guard,
+    )?;
+    let (amount_in, amount_out) = swap_settle_v2(
+        ctx,
+        remaining_accounts,
+        native_accounts,
+        &swap_result,
+        amount_specified,
+        sqrt_price_limit_x64,
+        is_base_input,
+    )?;
+    if is_base_input {
+        Ok(amount_out)
+    } else {
+        Ok(amount_in)
+    }
+}
 
-    let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+/// Calculates a single exact input/output swap and updates the pool state, without transferring any token.
+/// The result must be settled by `swap_settle_v2` in the same instruction.
+pub fn swap_compute_v2<'c: 'info, 'info>(
+    ctx: &mut SwapSingleV2<'info>,
+    remaining_accounts: &'c [AccountInfo<'info>],
+    native_accounts: &NativeAccounts<'info>,
+    amount_specified: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+    guard: &SwapGuard,
+) -> Result<SwapComputeResult> {
+    // invoke_memo_instruction(SWAP_MEMO_MSG, ctx.memo_program.to_account_info())?;
 
     let amount_0;
     let amount_1;
     let zero_for_one;
     let swap_price_before;
-
-    let input_balance_before = ctx.input_token_account.amount;
-    let output_balance_before = ctx.output_token_account.amount;
+    let trade_fee_rate;
+    // the accounts which are not tick arrays, checked against the accounts of the transfer hooks
+    let mut transfer_hook_account_keys = Vec::new();
 
     // calculate specified amount because the amount includes transfer_fee as input and without transfer_fee as output
     let (amount_calculate_specified, transfer_fee) = if is_base_input {
@@ -111,8 +179,6 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
         let pool_state = &mut ctx.pool_state.load_mut()?;
         zero_for_one = ctx.input_vault.mint == pool_state.token_mint_0;
 
-        require_gt!(block_timestamp, pool_state.open_time);
-
         require!(
             if zero_for_one {
                 ctx.input_vault.key() == pool_state.token_vault_0
@@ -123,9 +189,18 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
             },
             ErrorCode::InvalidInputPoolVault
         );
+        let pool_permission_accounts = check_pool_permission(
+            pool_state.is_permissioned(),
+            pool_state.key(),
+            ctx.payer.key,
+            remaining_accounts,
+        )?;
+        let mut transfer_hook_keys = get_transfer_hook_keys(&ctx.input_vault_mint)?;
+        transfer_hook_keys.extend(get_transfer_hook_keys(&ctx.output_vault_mint)?);
 
         let mut tickarray_bitmap_extension = None;
         let tick_array_states = &mut VecDeque::new();
+        let limit_order_fills = &mut Vec::new();
 
         let tick_array_bitmap_extension_key = TickArrayBitmapExtension::key(pool_state.key());
         for account_info in remaining_accounts.into_iter() {
@@ -137,14 +212,36 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
                 );
                 continue;
             }
-            tick_array_states.push_back(AccountLoad::load_data_mut(account_info)?);
+            if native_accounts.contains(account_info.key)
+                || is_pool_permission_account(account_info, &pool_permission_accounts)
+                || transfer_hook_keys.contains(account_info.key)
+            {
+                continue;
+            }
+            // the extra accounts of a transfer hook are checked after the swap amounts are known,
+            // any other account must be a tick array
+            if !transfer_hook_keys.is_empty() && !TickArrayState::is_tick_array(account_info) {
+                transfer_hook_account_keys.push(account_info.key());
+                continue;
+            }
+            let (tick_array_state, fills) = load_tick_array_mut(account_info)?;
+            limit_order_fills.push((tick_array_state.start_tick_index, fills));
+            tick_array_states.push_back(tick_array_state);
         }
 
-        (amount_0, amount_1) = swap_internal(
+        trade_fee_rate = pool_state.get_trade_fee_rate(
+            ctx.amm_config.trade_fee_rate,
+            u64::from(oracle::block_timestamp()),
+        );
+        let (mut observation_state, mut observation_extension) =
+            load_observation_state_mut(&ctx.observation_state)?;
+        (amount_0, amount_1) = swap_internal_with_guard(
             &ctx.amm_config,
             pool_state,
             tick_array_states,
-            &mut ctx.observation_state.load_mut()?,
+            &mut observation_state,
+            &mut observation_extension,
+            limit_order_fills,
             &tickarray_bitmap_extension,
             amount_calculate_specified,
             if sqrt_price_limit_x64 == 0 {
@@ -159,6 +256,7 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
             zero_for_one,
             is_base_input,
             oracle::block_timestamp(),
+            guard,
         )?;
 
         #[cfg(feature = "enable-log")]
@@ -173,6 +271,91 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
             ErrorCode::TooSmallInputOrOutputAmount
         );
     }
+
+    // the transfer fee amount charged by withheld_amount
+    let transfer_fee_0;
+    let transfer_fee_1;
+    if zero_for_one {
+        transfer_fee_0 = if is_base_input && amount_0 == amount_calculate_specified {
+            transfer_fee
+        } else {
+            util::get_transfer_inverse_fee(ctx.input_vault_mint.clone(), amount_0).unwrap()
+        };
+        transfer_fee_1 = util::get_transfer_fee(ctx.output_vault_mint.clone(), amount_1).unwrap();
+    } else {
+        transfer_fee_0 = util::get_transfer_fee(ctx.output_vault_mint.clone(), amount_0).unwrap();
+        transfer_fee_1 = if is_base_input && amount_1 == amount_calculate_specified {
+            transfer_fee
+        } else {
+            util::get_transfer_inverse_fee(ctx.input_vault_mint.clone(), amount_1).unwrap()
+        };
+    }
+
+    if !transfer_hook_account_keys.is_empty() {
+        let (amount_in, amount_out) = if zero_for_one {
+            (amount_0 + transfer_fee_0, amount_1)
+        } else {
+            (amount_1 + transfer_fee_1, amount_0)
+        };
+        let mut allowed_keys = get_transfer_hook_account_keys(
+            ctx.input_token_account.to_account_info(),
+            &ctx.input_vault_mint,
+            ctx.input_vault.to_account_info(),
+            ctx.payer.to_account_info(),
+            remaining_accounts,
+            amount_in,
+        )?;
+        allowed_keys.extend(get_transfer_hook_account_keys(
+            ctx.output_vault.to_account_info(),
+            &ctx.output_vault_mint,
+            ctx.output_token_account.to_account_info(),
+            ctx.pool_state.to_account_info(),
+            remaining_accounts,
+            amount_out,
+        )?);
+        require!(
+            transfer_hook_account_keys
+                .iter()
+                .all(|key| allowed_keys.contains(key)),
+            ErrorCode::InvalidAccount
+        );
+    }
+
+    Ok(SwapComputeResult {
+        zero_for_one,
+        swap_price_before,
+        trade_fee_rate,
+        amount_0,
+        amount_1,
+        transfer_fee_0,
+        transfer_fee_1,
+    })
+}
+
+/// Transfers the tokens of a swap calculated by `swap_compute_v2` and emits the swap event.
+/// Returns the amount paid from the input token account and received by the output token account.
+pub fn swap_settle_v2<'info>(
+    ctx: &mut SwapSingleV2<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    native_accounts: &NativeAccounts<'info>,
+    swap_result: &SwapComputeResult,
+    amount_specified: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+) -> Result<(u64, u64)> {
+    let SwapComputeResult {
+        zero_for_one,
+        swap_price_before,
+        trade_fee_rate,
+        amount_0,
+        amount_1,
+        transfer_fee_0,
+        transfer_fee_1,
+    } = *swap_result;
+
+    let input_balance_before = get_user_token_amount(&ctx.input_token_account)?;
+    let output_balance_before = get_user_token_amount(&ctx.output_token_account)?;
+
     let (token_account_0, token_account_1, vault_0, vault_1, vault_0_mint, vault_1_mint) =
         if zero_for_one {
             (
@@ -197,20 +380,10 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
     // user or pool real amount delta without tranfer fee
     let amount_0_without_fee;
     let amount_1_without_fee;
-    // the transfer fee amount charged by withheld_amount
-    let transfer_fee_0;
-    let transfer_fee_1;
     // transfer amount
     let transfer_amount_0;
     let transfer_amount_1;
     if zero_for_one {
-        transfer_fee_0 = if is_base_input && amount_0 == amount_calculate_specified {
-            transfer_fee
-        } else {
-            util::get_transfer_inverse_fee(vault_0_mint.clone(), amount_0).unwrap()
-        };
-        transfer_fee_1 = util::get_transfer_fee(vault_1_mint.clone(), amount_1).unwrap();
-
         amount_0_without_fee = amount_0;
         amount_1_without_fee = amount_1.checked_sub(transfer_fee_1).unwrap();
         (transfer_amount_0, transfer_amount_1) = (amount_0 + transfer_fee_0, amount_1);
@@ -223,21 +396,21 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
             transfer_fee_1
         );
         //  x -> y, deposit x token from user to pool vault.
-        transfer_from_user_to_pool_vault(
-            &ctx.payer,
+        native_accounts.transfer_from_user_to_pool_vault(
             &token_account_0.to_account_info(),
             &vault_0.to_account_info(),
             Some(vault_0_mint),
             &ctx.token_program,
             Some(ctx.token_program_2022.to_account_info()),
             transfer_amount_0,
+            remaining_accounts,
         )?;
         if vault_1.amount <= transfer_amount_1 {
             // freeze pool, disable all instructions
             ctx.pool_state.load_mut()?.set_status(255);
         }
         // x -> y，transfer y token from pool vault to user.
-        transfer_from_pool_vault_to_user(
+        native_accounts.transfer_from_pool_vault_to_user(
             &ctx.pool_state,
             &vault_1.to_account_info(),
             &token_account_1.to_account_info(),
@@ -245,15 +418,9 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
             &ctx.token_program,
             Some(ctx.token_program_2022.to_account_info()),
             transfer_amount_1,
+            remaining_accounts,
         )?;
     } else {
-        transfer_fee_0 = util::get_transfer_fee(vault_0_mint.clone(), amount_0).unwrap();
-        transfer_fee_1 = if is_base_input && amount_1 == amount_calculate_specified {
-            transfer_fee
-        } else {
-            util::get_transfer_inverse_fee(vault_1_mint.clone(), amount_1).unwrap()
-        };
-
         amount_0_without_fee = amount_0.checked_sub(transfer_fee_0).unwrap();
         amount_1_without_fee = amount_1;
         (transfer_amount_0, transfer_amount_1) = (amount_0, amount_1 + transfer_fee_1);
@@ -265,20 +432,20 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
             amount_1,
             transfer_fee_1
         );
-        transfer_from_user_to_pool_vault(
-            &ctx.payer,
+        native_accounts.transfer_from_user_to_pool_vault(
             &token_account_1.to_account_info(),
             &vault_1.to_account_info(),
             Some(vault_1_mint),
             &ctx.token_program,
             Some(ctx.token_program_2022.to_account_info()),
             transfer_amount_1,
+            remaining_accounts,
         )?;
         if vault_0.amount <= transfer_amount_0 {
             // freeze pool, disable all instructions
             ctx.pool_state.load_mut()?.set_status(255);
         }
-        transfer_from_pool_vault_to_user(
+        native_accounts.transfer_from_pool_vault_to_user(
             &ctx.pool_state,
             &vault_0.to_account_info(),
             &token_account_0.to_account_info(),
@@ -286,10 +453,9 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
             &ctx.token_program,
             Some(ctx.token_program_2022.to_account_info()),
             transfer_amount_0,
+            remaining_accounts,
         )?;
     }
-    ctx.output_token_account.reload()?;
-    ctx.input_token_account.reload()?;
 
     let pool_state = ctx.pool_state.load()?;
     emit!(SwapEvent {
@@ -304,7 +470,8 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
         zero_for_one,
         sqrt_price_x64: pool_state.sqrt_price_x64,
         liquidity: pool_state.liquidity,
-        tick: pool_state.tick_current
+        tick: pool_state.tick_current,
+        fee_rate: trade_fee_rate,
     });
     if zero_for_one {
         require_gt!(swap_price_before, pool_state.sqrt_price_x64);
@@ -328,17 +495,14 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
         }
     }
 
-    if is_base_input {
-        Ok(ctx
-            .output_token_account
-            .amount
+    Ok((
+        input_balance_before
+            .checked_sub(get_user_token_amount(&ctx.input_token_account)?)
+            .unwrap(),
+        get_user_token_amount(&ctx.output_token_account)?
             .checked_sub(output_balance_before)
-            .unwrap())
-    } else {
-        Ok(input_balance_before
-            .checked_sub(ctx.input_token_account.amount)
-            .unwrap())
-    }
+            .unwrap(),
+    ))
 }
 
 pub fn swap_v2<'a, 'b, 'c: 'info, 'info>(
@@ -347,13 +511,38 @@ pub fn swap_v2<'a, 'b, 'c: 'info, 'info>(
     other_amount_threshold: u64,
     sqrt_price_limit_x64: u128,
     is_base_input: bool,
+    deadline: Option<i64>,
+) -> Result<()> {
+    swap_v2_with_guard(
+        ctx,
+        amount,
+        other_amount_threshold,
+        sqrt_price_limit_x64,
+        is_base_input,
+        SwapGuard::default(),
+        deadline,
+    )
+}
+
+pub fn swap_v2_with_guard<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+    guard: SwapGuard,
+    deadline: Option<i64>,
 ) -> Result<()> {
+    check_deadline(deadline)?;
+    let native_accounts = NativeAccounts::load(&ctx.accounts.payer, None, ctx.remaining_accounts);
     let amount_result = exact_internal_v2(
         ctx.accounts,
         ctx.remaining_accounts,
+        &native_accounts,
         amount,
         sqrt_price_limit_x64,
         is_base_input,
+        &guard,
     )?;
     if is_base_input {
         require_gte!(
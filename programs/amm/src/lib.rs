@@ -57,6 +57,14 @@ pub mod byreal_clmm {
         instructions::update_amm_admin_group(ctx, params)
     }
 
+    /// Update the permission manager of the AMM admin group, who manages the permissioned pools.
+    pub fn update_permission_manager(
+        ctx: Context<UpdatePermissionManagerAccounts>,
+        permission_manager: Pubkey,
+    ) -> Result<()> {
+        instructions::update_permission_manager(ctx, permission_manager)
+    }
+
     // The configuration of AMM protocol, include trade fee and protocol fee
     /// # Arguments
     ///
@@ -105,7 +113,8 @@ pub mod byreal_clmm {
     /// * `fund_fee_rate`- The new fund fee rate of amm config, be set when `param` is 2
     /// * `new_owner`- The config's new owner, be set when `param` is 3
     /// * `new_fund_owner`- The config's new fund owner, be set when `param` is 4
-    /// * `param`- The value can be 0 | 1 | 2 | 3 | 4, otherwise will report a error
+    /// * `flash_enabled`- 1 to allow flash loans for the pools of the config, 0 to forbid, be set when `param` is 5
+    /// * `param`- The value can be 0 | 1 | 2 | 3 | 4 | 5, otherwise will report a error
     ///
     pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u32) -> Result<()> {
         instructions::update_amm_config(ctx, param, value)
@@ -117,13 +126,33 @@ pub mod byreal_clmm {
     ///
     /// * `ctx`- The context of accounts
     /// * `sqrt_price_x64` - the initial sqrt price (amount_token_1 / amount_token_0) of the pool as a Q64.64
-    /// Note: The open_time must be smaller than the current block_timestamp on chain.
+    /// * `open_time` - The unix timestamp after which swaps are allowed, the liquidity can be added before it
+    ///
     pub fn create_pool(
         ctx: Context<CreatePool>,
         sqrt_price_x64: u128,
         open_time: u64,
     ) -> Result<()> {
-        instructions::create_pool(ctx, sqrt_price_x64, open_time)
+        instructions::create_pool(ctx, sqrt_price_x64, open_time, None)
+    }
+
+    /// Creates a pool for the given token pair and the initial price
+    /// Same as `create_pool`, the trade fee decays from a high rate after the open time
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `sqrt_price_x64` - the initial sqrt price (amount_token_1 / amount_token_0) of the pool as a Q64.64
+    /// * `open_time` - The unix timestamp after which swaps are allowed, the liquidity can be added before it
+    /// * `launch_fee_param` - The trade fee decaying from a high rate after the open time
+    ///
+    pub fn create_pool_with_launch_fee(
+        ctx: Context<CreatePool>,
+        sqrt_price_x64: u128,
+        open_time: u64,
+        launch_fee_param: LaunchFeeParam,
+    ) -> Result<()> {
+        instructions::create_pool(ctx, sqrt_price_x64, open_time, Some(launch_fee_param))
     }
 
     /// Update pool status for given value
@@ -137,6 +166,108 @@ pub mod byreal_clmm {
         instructions::update_pool_status(ctx, status)
     }
 
+    /// Update the dynamic fee config of a pool, the dynamic fee mode is disabled if `dynamic_fee_control` is 0
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `param` - The dynamic fee params, the volatility state of the pool is reset
+    ///
+    pub fn update_dynamic_fee_config(
+        ctx: Context<UpdateDynamicFeeConfig>,
+        param: DynamicFeeParam,
+    ) -> Result<()> {
+        instructions::update_dynamic_fee_config(ctx, param)
+    }
+
+    /// Update the fee override of a pool, the fee rates of the pool replace the rates of its amm config if enabled
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `enabled` - Whether the fee override is enabled, the rates are cleared if disabled
+    /// * `trade_fee_rate` - The trade fee rate of the pool, denominated in hundredths of a bip (10^-6)
+    /// * `protocol_fee_rate` - The protocol fee rate of the pool, as a share of the trade fee
+    /// * `fund_fee_rate` - The fund fee rate of the pool, as a share of the trade fee
+    ///
+    pub fn update_pool_fee_override(
+        ctx: Context<UpdatePoolFeeOverride>,
+        enabled: bool,
+        trade_fee_rate: u32,
+        protocol_fee_rate: u32,
+        fund_fee_rate: u32,
+    ) -> Result<()> {
+        instructions::update_pool_fee_override(
+            ctx,
+            enabled,
+            trade_fee_rate,
+            protocol_fee_rate,
+            fund_fee_rate,
+        )
+    }
+
+    /// Reschedule the time a pool opens for trading, only allowed before the pool is open
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `open_time` - The new unix timestamp after which swaps are allowed, must be in the future
+    ///
+    pub fn update_pool_open_time(ctx: Context<UpdatePoolOpenTime>, open_time: u64) -> Result<()> {
+        instructions::update_pool_open_time(ctx, open_time)
+    }
+
+    /// Creates the permission account of a pool and turns the pool into a permissioned pool,
+    /// only the users in the allow list or holding the gating token can swap, provide liquidity and borrow flash loans.
+    /// The swap payer, the position NFT owner and the owner of the flash loan recipient accounts are checked.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `gating_mint` - The holders of the mint are allowed, default pubkey if there is no gating token
+    ///
+    pub fn create_pool_permission(
+        ctx: Context<CreatePoolPermission>,
+        gating_mint: Pubkey,
+    ) -> Result<()> {
+        instructions::create_pool_permission(ctx, gating_mint)
+    }
+
+    /// Update the permission of a pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `param`- The value can be 0 | 1 | 2 | 3 | 4, otherwise will report a error
+    /// * `keys`- add the keys to the allow list when the `param` is 0
+    ///           remove the keys from the allow list when the `param` is 1
+    ///           set the gating mint to the first key when the `param` is 2, no key to remove the gating mint
+    ///           enable the permissioned mode when the `param` is 3
+    ///           disable the permissioned mode when the `param` is 4
+    ///
+    pub fn update_pool_permission(
+        ctx: Context<UpdatePoolPermission>,
+        param: u8,
+        keys: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::update_pool_permission(ctx, param, keys)
+    }
+
+    /// Update the min duration between two observations of a pool, which widens the time window
+    /// covered by the observations. The duration can only be increased.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `update_duration` - The new min duration in seconds between two observations
+    ///
+    pub fn update_observation_duration(
+        ctx: Context<UpdateObservationDuration>,
+        update_duration: u32,
+    ) -> Result<()> {
+        instructions::update_observation_duration(ctx, update_duration)
+    }
+
     /// Creates an operation account for the program
     ///
     /// # Arguments
@@ -197,6 +328,23 @@ pub mod byreal_clmm {
         instructions::initialize_reward(ctx, param)
     }
 
+    /// Initialize a reward info emitting in phases, the whole schedule is funded at once
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `param` - reward open timestamp, end timestamp and the base emissions per second
+    /// * `phases` - the duration and the emission rate of each phase relative to the base emissions,
+    /// the phases run one after another from the open time and must end at the end time
+    ///
+    pub fn initialize_reward_with_schedule(
+        ctx: Context<InitializeReward>,
+        param: InitializeRewardParam,
+        phases: Vec<RewardSchedulePhase>,
+    ) -> Result<()> {
+        instructions::initialize_reward_with_schedule(ctx, param, phases)
+    }
+
     /// Collect remaining reward token for reward founder
     ///
     /// # Arguments
@@ -204,13 +352,47 @@ pub mod byreal_clmm {
     /// * `ctx`- The context of accounts
     /// * `reward_index` - the index to reward info
     ///
-    pub fn collect_remaining_rewards(
-        ctx: Context<CollectRemainingRewards>,
+    /// The share of the remaining reward of a third-party funder is refunded to the funder
+    /// when its `RewardFunderState` account is passed as the first remaining account.
+    ///
+    pub fn collect_remaining_rewards<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectRemainingRewards<'info>>,
         reward_index: u8,
     ) -> Result<()> {
         instructions::collect_remaining_rewards(ctx, reward_index)
     }
 
+    /// Collect the whole reward vault for the pool owner or reward manager and reset the reward slot
+    /// for a new reward, only after the claim period of the ended reward, the reward not claimed
+    /// by the positions is forfeited
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `reward_index` - the index to reward info
+    ///
+    pub fn recycle_reward(ctx: Context<CollectRemainingRewards>, reward_index: u8) -> Result<()> {
+        instructions::recycle_reward(ctx, reward_index)
+    }
+
+    /// Add funds to an existing reward by any funder, the end time of the reward is extended
+    /// at the current emission rate
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `reward_index` - the index to reward info
+    /// * `amount` - the amount of reward token transferred to the reward vault, the share of the remaining
+    /// reward is refunded to the funder through `collect_remaining_rewards` after the reward ends
+    ///
+    pub fn add_reward_funds(
+        ctx: Context<AddRewardFunds>,
+        reward_index: u8,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::add_reward_funds(ctx, reward_index, amount)
+    }
+
     /// Update rewards info of the given pool, can be called for everyone
     ///
     /// # Arguments
@@ -258,9 +440,10 @@ pub mod byreal_clmm {
         instructions::deposit_offchain_reward(ctx, amount)
     }
 
-    /// claim offchain reward from the pool
-    pub fn claim_offchain_reward(
-        ctx: Context<ClaimOffchainRewardAccounts>,
+    /// claim offchain reward from the pool, the claim status of the claimer can be passed
+    /// as the trailing remaining account to record the amount for the claims with merkle proofs
+    pub fn claim_offchain_reward<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ClaimOffchainRewardAccounts<'info>>,
         amount: u64,
     ) -> Result<()> {
         instructions::claim_offchain_reward(ctx, amount)
@@ -274,6 +457,41 @@ pub mod byreal_clmm {
         instructions::withdraw_offchain_reward(ctx, amount)
     }
 
+    /// Publish the merkle root of the offchain rewards for a new epoch, only the reward claim manager can call
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `epoch` - The epoch of the root, must be greater than the epoch of the current root
+    /// * `merkle_root` - The root of the cumulative amount claimable by each claimer of each reward mint
+    ///
+    pub fn update_offchain_reward_merkle_root(
+        ctx: Context<UpdateOffchainRewardMerkleRoot>,
+        epoch: u64,
+        merkle_root: [u8; 32],
+    ) -> Result<()> {
+        instructions::update_offchain_reward_merkle_root(ctx, epoch, merkle_root)
+    }
+
+    /// Claim offchain reward from the pool with a merkle proof, no co-sign of the reward claim manager is needed
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `epoch` - The epoch of the latest merkle root the proof is built from
+    /// * `cumulative_amount` - The cumulative amount of the reward mint the claimer is entitled to,
+    ///    the amount not claimed yet is transferred
+    /// * `proof` - The merkle proof of the claimer's leaf
+    ///
+    pub fn claim_offchain_reward_with_proof(
+        ctx: Context<ClaimOffchainRewardWithProof>,
+        epoch: u64,
+        cumulative_amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::claim_offchain_reward_with_proof(ctx, epoch, cumulative_amount, proof)
+    }
+
     /// Collect the protocol fee accrued to the pool
     ///
     /// # Arguments
@@ -282,8 +500,8 @@ pub mod byreal_clmm {
     /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
     /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
     ///
-    pub fn collect_protocol_fee(
-        ctx: Context<CollectProtocolFee>,
+    pub fn collect_protocol_fee<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectProtocolFee<'info>>,
         amount_0_requested: u64,
         amount_1_requested: u64,
     ) -> Result<()> {
@@ -298,8 +516,8 @@ pub mod byreal_clmm {
     /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
     /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
     ///
-    pub fn collect_fund_fee(
-        ctx: Context<CollectFundFee>,
+    pub fn collect_fund_fee<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectFundFee<'info>>,
         amount_0_requested: u64,
         amount_1_requested: u64,
     ) -> Result<()> {
@@ -346,6 +564,7 @@ pub mod byreal_clmm {
 
     /// #[deprecated(note = "Use `open_position_with_token22_nft` instead.")]
     /// Creates a new position wrapped in a NFT, support Token2022
+    /// The payer can be passed as the token account of the native mint to pay SOL directly
     ///
     /// # Arguments
     ///
@@ -383,10 +602,13 @@ pub mod byreal_clmm {
             tick_array_upper_start_index,
             with_metadata,
             base_flag,
+            None,
         )
     }
 
-    /// Creates a new position wrapped in a Token2022 NFT without relying on metadata_program and metadata_account, reduce the cost for user to create a personal position.
+    /// Creates a new position wrapped in a NFT, support Token2022
+    /// Same as `open_position_v2`, fails if the transaction is executed after the deadline
+    /// The payer can be passed as the token account of the native mint to pay SOL directly
     ///
     /// # Arguments
     ///
@@ -400,6 +622,51 @@ pub mod byreal_clmm {
     /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
     /// * `with_metadata` - The flag indicating whether to create NFT mint metadata
     /// * `base_flag` - if the liquidity specified as zero, true: calculate liquidity base amount_0_max otherwise base amount_1_max
+    /// * `deadline` - The unix timestamp after which the transaction fails with `TransactionTooOld`
+    ///
+    pub fn open_position_v3<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenPositionV2<'info>>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+        with_metadata: bool,
+        base_flag: Option<bool>,
+        deadline: i64,
+    ) -> Result<()> {
+        instructions::open_position_v2(
+            ctx,
+            liquidity,
+            amount_0_max,
+            amount_1_max,
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            with_metadata,
+            base_flag,
+            Some(deadline),
+        )
+    }
+
+    /// Creates a new position wrapped in a Token2022 NFT without relying on metadata_program and metadata_account, reduce the cost for user to create a personal position.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `tick_lower_index` - The low boundary of market
+    /// * `tick_upper_index` - The upper boundary of market
+    /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
+    /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper
+    /// * `liquidity` - The liquidity to be added, if zero, and the base_flag is specified, calculate liquidity base amount_0_max or amount_1_max according base_flag, otherwise open position with zero liquidity
+    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
+    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
+    /// * `with_metadata` - The flag indicating whether to create NFT mint metadata, which shows the pool, token pair, range and fee tier
+    /// of the position. The amm config of the pool is required in the remaining accounts.
+    /// * `base_flag` - if the liquidity specified as zero, true: calculate liquidity base amount_0_max otherwise base amount_1_max
     ///
     pub fn open_position_with_token22_nft<'a, 'b, 'c: 'info, 'info>(
         ctx: Context<'a, 'b, 'c, 'info, OpenPositionWithToken22Nft<'info>>,
@@ -460,6 +727,8 @@ pub mod byreal_clmm {
     }
 
     /// Increases liquidity for an existing position, with amount paid by `payer`, support Token2022
+    /// The nft_owner can be passed as the token account of the native mint to pay SOL directly,
+    /// the system program is then found by key at any position of the remaining accounts
     ///
     /// # Arguments
     ///
@@ -479,7 +748,49 @@ pub mod byreal_clmm {
         if liquidity == 0 {
             assert!(base_flag.is_some());
         }
-        instructions::increase_liquidity_v2(ctx, liquidity, amount_0_max, amount_1_max, base_flag)
+        instructions::increase_liquidity_v2(
+            ctx,
+            liquidity,
+            amount_0_max,
+            amount_1_max,
+            base_flag,
+            None,
+        )
+    }
+
+    /// Increases liquidity for an existing position, with amount paid by `payer`, support Token2022
+    /// Same as `increase_liquidity_v2`, fails if the transaction is executed after the deadline
+    /// The nft_owner can be passed as the token account of the native mint to pay SOL directly,
+    /// the system program is then found by key at any position of the remaining accounts
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `liquidity` - The desired liquidity to be added, if zero, calculate liquidity base amount_0 or amount_1 according base_flag
+    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
+    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
+    /// * `base_flag` - must be specified if liquidity is zero, true: calculate liquidity base amount_0_max otherwise base amount_1_max
+    /// * `deadline` - The unix timestamp after which the transaction fails with `TransactionTooOld`
+    ///
+    pub fn increase_liquidity_v3<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, IncreaseLiquidityV2<'info>>,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+        base_flag: Option<bool>,
+        deadline: i64,
+    ) -> Result<()> {
+        if liquidity == 0 {
+            assert!(base_flag.is_some());
+        }
+        instructions::increase_liquidity_v2(
+            ctx,
+            liquidity,
+            amount_0_max,
+            amount_1_max,
+            base_flag,
+            Some(deadline),
+        )
     }
 
     /// #[deprecated(note = "Use `decrease_liquidity_v2` instead.")]
@@ -502,6 +813,8 @@ pub mod byreal_clmm {
     }
 
     /// Decreases liquidity for an existing position, support Token2022
+    /// The nft_owner can be passed as the recipient of the native mint to receive SOL directly,
+    /// with the system program and the native temp account in the remaining accounts
     ///
     /// # Arguments
     ///
@@ -516,7 +829,97 @@ pub mod byreal_clmm {
         amount_0_min: u64,
         amount_1_min: u64,
     ) -> Result<()> {
-        instructions::decrease_liquidity_v2(ctx, liquidity, amount_0_min, amount_1_min)
+        instructions::decrease_liquidity_v2(ctx, liquidity, amount_0_min, amount_1_min, None)
+    }
+
+    /// Decreases liquidity for an existing position, support Token2022
+    /// Same as `decrease_liquidity_v2`, fails if the transaction is executed after the deadline
+    /// The nft_owner can be passed as the recipient of the native mint to receive SOL directly,
+    /// with the system program and the native temp account in the remaining accounts
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` -  The context of accounts
+    /// * `liquidity` - The amount by which liquidity will be decreased
+    /// * `amount_0_min` - The minimum amount of token_0 that should be accounted for the burned liquidity
+    /// * `amount_1_min` - The minimum amount of token_1 that should be accounted for the burned liquidity
+    /// * `deadline` - The unix timestamp after which the transaction fails with `TransactionTooOld`
+    ///
+    pub fn decrease_liquidity_v3<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, DecreaseLiquidityV2<'info>>,
+        liquidity: u128,
+        amount_0_min: u64,
+        amount_1_min: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        instructions::decrease_liquidity_v2(
+            ctx,
+            liquidity,
+            amount_0_min,
+            amount_1_min,
+            Some(deadline),
+        )
+    }
+
+    /// Moves all liquidity of a position to a new range on the same NFT, the fees are re-deposited
+    /// with the withdrawn tokens, the rewards are collected and the tokens left over are returned.
+    /// The range in the token metadata of the position NFT is updated.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `tick_lower_index` - The new low boundary of the position
+    /// * `tick_upper_index` - The new upper boundary of the position
+    /// * `tick_array_lower_start_index` - The start index of tick array which include the new tick low
+    /// * `tick_array_upper_start_index` - The start index of tick array which include the new tick upper
+    /// * `liquidity_min` - The minimum liquidity deposited into the new range, for slippage check
+    ///
+    pub fn reposition<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, Reposition<'info>>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+        liquidity_min: u128,
+    ) -> Result<()> {
+        instructions::reposition(
+            ctx,
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            liquidity_min,
+        )
+    }
+
+    /// Collects the fees owed to a position without modifying its liquidity
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_0_requested` - The maximum amount of token_0 fee to collect
+    /// * `amount_1_requested` - The maximum amount of token_1 fee to collect
+    ///
+    pub fn collect_fees<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectFees<'info>>,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> Result<()> {
+        instructions::collect_fees(ctx, amount_0_requested, amount_1_requested)
+    }
+
+    /// Collects the rewards owed to a position without modifying its liquidity
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amounts_requested` - The maximum amount to collect for each reward
+    ///
+    pub fn collect_rewards<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectRewards<'info>>,
+        amounts_requested: [u64; REWARD_NUM],
+    ) -> Result<()> {
+        instructions::collect_personal_rewards(ctx, amounts_requested)
     }
 
     /// #[deprecated(note = "Use `swap_v2` instead.")]
@@ -547,6 +950,8 @@ pub mod byreal_clmm {
     }
 
     /// Swaps one token for as much as possible of another token across a single pool, support token program 2022
+    /// The payer can be passed as the token account of the native mint to pay or receive SOL directly,
+    /// with the system program and the native temp account in the remaining accounts
     ///
     /// # Arguments
     ///
@@ -569,23 +974,224 @@ pub mod byreal_clmm {
             other_amount_threshold,
             sqrt_price_limit_x64,
             is_base_input,
+            None,
+        )
+    }
+
+    /// Swaps one token for as much as possible of another token across a single pool, support token program 2022
+    /// Same as `swap_v2`, fails if the transaction is executed after the deadline
+    /// The payer can be passed as the token account of the native mint to pay or receive SOL directly,
+    /// with the system program and the native temp account in the remaining accounts
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
+    /// * `other_amount_threshold` - For slippage check
+    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit. If zero for one, the price cannot
+    /// * `is_base_input` - swap base input or swap base output
+    /// * `deadline` - The unix timestamp after which the transaction fails with `TransactionTooOld`
+    ///
+    pub fn swap_v3<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+        deadline: i64,
+    ) -> Result<()> {
+        instructions::swap_v2(
+            ctx,
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            is_base_input,
+            Some(deadline),
+        )
+    }
+
+    /// Same as `swap_v2`, the swap is aborted if it moves the price or crosses initialized ticks more than the guard allows
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
+    /// * `other_amount_threshold` - For slippage check
+    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit. If zero for one, the price cannot
+    /// * `is_base_input` - swap base input or swap base output
+    /// * `guard` - The max price impact in basis points and the max number of initialized ticks crossed, no limit if `None`
+    /// * `deadline` - The unix timestamp after which the transaction fails with `TransactionTooOld`, no check if `None`
+    ///
+    pub fn swap_v2_with_guard<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+        guard: SwapGuard,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        instructions::swap_v2_with_guard(
+            ctx,
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            is_base_input,
+            guard,
+            deadline,
         )
     }
 
-    //== drop this method
-    // /// Swap token for as much as possible of another token across the path provided, base input
-    // ///
-    // /// # Arguments
-    // ///
-    // /// * `ctx` - The context of accounts
-    // /// * `amount_in` - Token amount to be swapped in
-    // /// * `amount_out_minimum` - Panic if output amount is below minimum amount. For slippage.
-    // ///
-    // pub fn swap_router_base_in<'a, 'b, 'c: 'info, 'info>(
-    //     ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseIn<'info>>,
-    //     amount_in: u64,
-    //     amount_out_minimum: u64,
-    // ) -> Result<()> {
-    //     instructions::swap_router_base_in(ctx, amount_in, amount_out_minimum)
-    // }
+    /// Query the time weighted average tick and price of a pool, the result is returned by return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `seconds_ago` - The length in seconds of the window ending at the current block timestamp
+    ///
+    pub fn observe_twap(ctx: Context<ObserveTwap>, seconds_ago: u32) -> Result<TwapResult> {
+        instructions::observe_twap(ctx, seconds_ago)
+    }
+
+    /// Grow the observation ring buffer of a pool, anyone can pay the rent of the new observations.
+    /// The ring buffer uses the new observations the next time it wraps around.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `observation_cardinality_next` - The number of observations the ring buffer grows to
+    ///
+    pub fn increase_observation_cardinality(
+        ctx: Context<IncreaseObservationCardinality>,
+        observation_cardinality_next: u16,
+    ) -> Result<()> {
+        instructions::increase_observation_cardinality(ctx, observation_cardinality_next)
+    }
+
+    /// Query the seconds per liquidity and the seconds spent inside a tick range, the result is returned by return data.
+    /// The values only have relative meaning, compare two snapshots of the same range to get the value over a period.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `tick_lower_index` - The lower tick of the range, must be initialized
+    /// * `tick_upper_index` - The upper tick of the range, must be initialized
+    ///
+    pub fn snapshot_cumulatives_inside(
+        ctx: Context<SnapshotCumulativesInside>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+    ) -> Result<CumulativesInside> {
+        instructions::snapshot_cumulatives_inside(ctx, tick_lower_index, tick_upper_index)
+    }
+
+    /// Swap token for as much as possible of another token across the path provided, base input
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_in` - Token amount to be swapped in
+    /// * `amount_out_minimum` - Panic if output amount is below minimum amount. For slippage.
+    /// * `deadline` - The unix timestamp after which the transaction fails with `TransactionTooOld`, no check if `None`
+    /// * `hop_account_counts` - The count of the remaining accounts of every hop, in the order of the route
+    ///
+    pub fn swap_router_base_in<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseIn<'info>>,
+        amount_in: u64,
+        amount_out_minimum: u64,
+        deadline: Option<i64>,
+        hop_account_counts: Vec<u8>,
+    ) -> Result<()> {
+        instructions::swap_router_base_in(
+            ctx,
+            amount_in,
+            amount_out_minimum,
+            deadline,
+            hop_account_counts,
+        )
+    }
+
+    /// Swap as little as possible of one token for an exact amount of another token across the path provided, base output
+    ///
+    /// A pool can only appear once in the path.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_out` - Token amount to be received by the last hop
+    /// * `amount_in_maximum` - Panic if input amount is above maximum amount. For slippage.
+    /// * `deadline` - The unix timestamp after which the transaction fails with `TransactionTooOld`, no check if `None`
+    /// * `hop_account_counts` - The count of the remaining accounts of every hop, in the order of the route
+    ///
+    pub fn swap_router_base_out<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseOut<'info>>,
+        amount_out: u64,
+        amount_in_maximum: u64,
+        deadline: Option<i64>,
+        hop_account_counts: Vec<u8>,
+    ) -> Result<()> {
+        instructions::swap_router_base_out(
+            ctx,
+            amount_out,
+            amount_in_maximum,
+            deadline,
+            hop_account_counts,
+        )
+    }
+
+    /// Lends tokens from the pool vaults and invokes the callback program, the vaults must be
+    /// repaid with the fee based on the trade fee rate before the callback returns.
+    /// Flash loans must be enabled by the amm config of the pool, and can be disabled per pool by the pool status.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts, the remaining accounts are passed to the callback program
+    /// * `amount_0` - The amount of token_0 to borrow
+    /// * `amount_1` - The amount of token_1 to borrow
+    /// * `data` - The instruction data of the callback program
+    ///
+    pub fn flash<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, Flash<'info>>,
+        amount_0: u64,
+        amount_1: u64,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::flash(ctx, amount_0, amount_1, data)
+    }
+
+    /// Place a limit order in the single tick spacing range starting at `tick_lower_index`.
+    /// The sold token is decided by the input vault, the order is filled when a swap crosses the range.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `tick_lower_index` - The lower tick of the order range, the range must be out of the current price
+    /// * `tick_array_start_index` - The start index of the tick array contains both ticks of the range
+    /// * `nonce` - Chosen by the owner to derive the order account, so several orders can be opened in the same range
+    /// * `amount` - The amount of the sold token to deposit, the transfer fee is charged on top of it
+    ///
+    pub fn open_limit_order<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenLimitOrder<'info>>,
+        tick_lower_index: i32,
+        tick_array_start_index: i32,
+        nonce: u64,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::open_limit_order(ctx, tick_lower_index, tick_array_start_index, nonce, amount)
+    }
+
+    /// Close a limit order, claim the converted token if the order is filled, otherwise cancel it.
+    /// The fees and rewards earned by the order are paid too, the reward accounts are passed as remaining accounts
+    /// like `decrease_liquidity_v2`. A filled order loses its fees and rewards if the price went back
+    /// into its range before it is closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn close_limit_order<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CloseLimitOrder<'info>>,
+    ) -> Result<()> {
+        instructions::close_limit_order(ctx)
+    }
 }
@@ -30,8 +30,13 @@ pub struct AmmAdminGroup {
     /// such as create amm config, update amm config
     pub normal_manager: Pubkey,
 
+    /// the address who can manage the permissioned pools,
+    /// such as the allow list and the gating token of a pool,
+    /// default pubkey if it's not set by `update_permission_manager` yet
+    pub permission_manager: Pubkey,
+
     /// The space required for the account. may be used for future extensions.
-    pub pad: [Pubkey; 6],
+    pub pad: [Pubkey; 5],
 }
 
 impl AmmAdminGroup {
@@ -72,4 +77,5 @@ pub struct ModifyAmmAdminGroupEvent {
     pub pool_manager: Pubkey,
     pub emergency_manager: Pubkey,
     pub normal_manager: Pubkey,
+    pub permission_manager: Pubkey,
 }
@@ -22,8 +22,10 @@ pub struct AmmConfig {
     pub tick_spacing: u16,
     /// The fund fee, denominated in hundredths of a bip (10^-6)
     pub fund_fee_rate: u32,
+    /// Flash loans are allowed for the pools of the config if it is not zero
+    pub flash_enabled: u8,
     // padding space for upgrade
-    pub padding_u32: u32,
+    pub padding_u8: [u8; 3],
     pub fund_owner: Pubkey,
     pub padding: [u64; 3],
 }
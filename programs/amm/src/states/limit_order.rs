@@ -0,0 +1,349 @@
+use crate::error::ErrorCode;
+use crate::libraries::{liquidity_math, tick_math};
+use crate::states::REWARD_NUM;
+use anchor_lang::prelude::*;
+use std::convert::identity;
+
+/// Seed to derive account address and signature
+pub const LIMIT_ORDER_SEED: &str = "limit_order";
+
+/// A limit order backed by liquidity in a single tick spacing range.
+/// The order is filled when the price crosses the range, and the converted token is kept for the owner
+/// even if the price reverts.
+#[account]
+#[derive(Default, Debug)]
+pub struct LimitOrderState {
+    /// Bump to identify PDA
+    pub bump: u8,
+
+    /// The ID of the pool of the order
+    pub pool_id: Pubkey,
+
+    /// The owner of the order
+    pub owner: Pubkey,
+
+    /// Chosen by the owner to open several orders in the same range
+    pub nonce: u64,
+
+    /// The lower bound tick of the order range
+    pub tick_lower_index: i32,
+
+    /// The upper bound tick of the order range, always tick_lower_index + tick_spacing
+    pub tick_upper_index: i32,
+
+    /// true if the order sells token_0 for token_1, it's filled when the price crosses tick_upper_index upward.
+    /// Otherwise it sells token_1 for token_0, and it's filled when the price crosses tick_lower_index downward.
+    pub zero_for_one: bool,
+
+    /// The amount of liquidity of the order
+    pub liquidity: u128,
+
+    /// The order_fill_epoch of the fill tick when the order is opened
+    pub fill_epoch: u32,
+
+    /// The amount of the sold token deposited by the order
+    pub amount_in: u64,
+
+    /// The fee growth inside the order range when the order is opened, Q64.64
+    pub fee_growth_inside_0_last_x64: u128,
+    pub fee_growth_inside_1_last_x64: u128,
+
+    /// The reward growths inside the order range when the order is opened, Q64.64
+    pub reward_growths_inside_last_x64: [u128; REWARD_NUM],
+
+    /// The pool reward generations the reward growths inside are recorded for
+    pub reward_generations: [u16; REWARD_NUM],
+
+    // account update recent epoch
+    pub recent_epoch: u64,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 8],
+}
+
+impl LimitOrderState {
+    pub const LEN: usize = 8
+        + 1
+        + 32
+        + 32
+        + 8
+        + 4
+        + 4
+        + 1
+        + 16
+        + 4
+        + 8
+        + 16
+        + 16
+        + 16 * REWARD_NUM
+        + 2 * REWARD_NUM
+        + 8
+        + 8 * 8;
+
+    /// The tick which fills the order when it is crossed
+    pub fn fill_tick_index(&self) -> i32 {
+        if self.zero_for_one {
+            self.tick_upper_index
+        } else {
+            self.tick_lower_index
+        }
+    }
+
+    /// The order is filled if the fill tick has been crossed since the order was opened
+    pub fn is_filled(&self, fill_tick_order_fill_epoch: u32) -> bool {
+        self.fill_epoch != fill_tick_order_fill_epoch
+    }
+
+    /// The amount of token_0 and token_1 owed to a filled order
+    pub fn get_filled_amounts(&self) -> Result<(u64, u64)> {
+        let sqrt_price_lower_x64 = tick_math::get_sqrt_price_at_tick(self.tick_lower_index)?;
+        let sqrt_price_upper_x64 = tick_math::get_sqrt_price_at_tick(self.tick_upper_index)?;
+        if self.zero_for_one {
+            Ok((
+                0,
+                liquidity_math::get_delta_amount_1_unsigned(
+                    sqrt_price_lower_x64,
+                    sqrt_price_upper_x64,
+                    self.liquidity,
+                    false,
+                )?,
+            ))
+        } else {
+            Ok((
+                liquidity_math::get_delta_amount_0_unsigned(
+                    sqrt_price_lower_x64,
+                    sqrt_price_upper_x64,
+                    self.liquidity,
+                    false,
+                )?,
+                0,
+            ))
+        }
+    }
+}
+
+/// The growth inside the range of the limit orders of a fill tick opened in the same fill epoch,
+/// recorded when the orders are filled. The entries are stored after the tick array struct.
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+#[derive(Default, Debug)]
+pub struct LimitOrderFill {
+    /// The fill tick of the orders
+    pub tick: i32,
+    /// The order_fill_epoch of the fill tick when the orders are opened
+    pub fill_epoch: u32,
+    /// The liquidity of the orders not closed yet, the entry is free if it is zero
+    pub liquidity: u128,
+    /// 1 if the orders are filled
+    pub filled: u8,
+    /// The fee growth inside the order range when the orders are filled, Q64.64
+    pub fee_growth_inside_0_x64: u128,
+    pub fee_growth_inside_1_x64: u128,
+    /// The reward growths inside the order range when the orders are filled, Q64.64
+    pub reward_growths_inside_x64: [u128; REWARD_NUM],
+}
+
+// The entries are cast from the tail of the tick array account data
+unsafe impl bytemuck::Pod for LimitOrderFill {}
+unsafe impl bytemuck::Zeroable for LimitOrderFill {}
+
+impl LimitOrderFill {
+    pub const LEN: usize = 4 + 4 + 16 + 1 + 16 + 16 + 16 * REWARD_NUM;
+
+    /// The index of the entry of the orders of the fill tick opened in `fill_epoch`
+    pub fn find(fills: &[LimitOrderFill], tick: i32, fill_epoch: u32) -> Option<usize> {
+        fills.iter().position(|fill| {
+            identity(fill.liquidity) != 0
+                && identity(fill.tick) == tick
+                && identity(fill.fill_epoch) == fill_epoch
+        })
+    }
+
+    /// The index of the entry a new order of the fill tick opened in `fill_epoch` is added to,
+    /// none if the tick array must grow by an entry first
+    pub fn find_or_free(fills: &[LimitOrderFill], tick: i32, fill_epoch: u32) -> Option<usize> {
+        Self::find(fills, tick, fill_epoch)
+            .or_else(|| fills.iter().position(|fill| identity(fill.liquidity) == 0))
+    }
+
+    /// Adds the liquidity of a new order to the entry of its fill tick and fill epoch
+    pub fn add_order(
+        fills: &mut [LimitOrderFill],
+        tick: i32,
+        fill_epoch: u32,
+        liquidity: u128,
+    ) -> Result<()> {
+        let index =
+            Self::find_or_free(fills, tick, fill_epoch).ok_or(ErrorCode::InvalidLimitOrderTick)?;
+        let fill = &mut fills[index];
+        if identity(fill.liquidity) == 0 {
+            *fill = LimitOrderFill {
+                tick,
+                fill_epoch,
+                ..Default::default()
+            };
+        }
+        fill.liquidity = fill
+            .liquidity
+            .checked_add(liquidity)
+            .ok_or(ErrorCode::CalculateOverflow)?;
+        Ok(())
+    }
+
+    /// Records the growth inside of the orders of the fill tick filled in `fill_epoch`, the orders
+    /// opened before the entries were stored have no entry
+    pub fn record_fill(
+        fills: &mut [LimitOrderFill],
+        tick: i32,
+        fill_epoch: u32,
+        fee_growth_inside_0_x64: u128,
+        fee_growth_inside_1_x64: u128,
+        reward_growths_inside_x64: [u128; REWARD_NUM],
+    ) {
+        if let Some(index) = Self::find(fills, tick, fill_epoch) {
+            let fill = &mut fills[index];
+            fill.filled = 1;
+            fill.fee_growth_inside_0_x64 = fee_growth_inside_0_x64;
+            fill.fee_growth_inside_1_x64 = fee_growth_inside_1_x64;
+            fill.reward_growths_inside_x64 = reward_growths_inside_x64;
+        }
+    }
+
+    /// Removes the liquidity of a closed order from its entry, returns the entry if the order is recorded in one
+    pub fn remove_order(
+        fills: &mut [LimitOrderFill],
+        tick: i32,
+        fill_epoch: u32,
+        liquidity: u128,
+    ) -> Result<Option<LimitOrderFill>> {
+        let index = match Self::find(fills, tick, fill_epoch) {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let fill = fills[index];
+        fills[index].liquidity = fill
+            .liquidity
+            .checked_sub(liquidity)
+            .ok_or(ErrorCode::CalculateOverflow)?;
+        Ok(Some(fill))
+    }
+}
+
+/// Emitted when a limit order is opened
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct OpenLimitOrderEvent {
+    /// The pool of the order
+    pub pool_state: Pubkey,
+    /// The owner of the order
+    pub owner: Pubkey,
+    /// The order account
+    pub limit_order: Pubkey,
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    /// true if the order sells token_0 for token_1
+    pub zero_for_one: bool,
+    pub liquidity: u128,
+    /// The amount of the sold token deposited, without the transfer fee
+    pub amount_in: u64,
+    /// The transfer fee charged by the withheld_amount of the sold token
+    pub transfer_fee: u64,
+}
+
+/// Emitted when a limit order is closed, either claimed after filled or cancelled
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct CloseLimitOrderEvent {
+    /// The pool of the order
+    pub pool_state: Pubkey,
+    /// The owner of the order
+    pub owner: Pubkey,
+    /// The order account
+    pub limit_order: Pubkey,
+    /// true if the order was filled, otherwise it's cancelled
+    pub filled: bool,
+    /// The amount of token_0 and token_1 transferred to the owner, without the fees
+    pub amount_0: u64,
+    pub amount_1: u64,
+    /// The fees of token_0 and token_1 earned by the order
+    pub fee_amount_0: u64,
+    pub fee_amount_1: u64,
+    /// The rewards earned by the order
+    pub reward_amounts: [u64; REWARD_NUM],
+}
+
+#[cfg(test)]
+mod limit_order_test {
+    use super::*;
+
+    #[test]
+    fn filled_amounts_test() {
+        let mut order = LimitOrderState {
+            tick_lower_index: 100,
+            tick_upper_index: 110,
+            liquidity: 1_000_000_000,
+            zero_for_one: true,
+            fill_epoch: 3,
+            ..Default::default()
+        };
+        assert_eq!(order.fill_tick_index(), 110);
+        assert!(!order.is_filled(3));
+        assert!(order.is_filled(4));
+
+        let sqrt_price_lower_x64 = tick_math::get_sqrt_price_at_tick(100).unwrap();
+        let sqrt_price_upper_x64 = tick_math::get_sqrt_price_at_tick(110).unwrap();
+        // the token_0 deposited is converted to token_1 at the prices of the range
+        let (amount_0, amount_1) = order.get_filled_amounts().unwrap();
+        assert_eq!(amount_0, 0);
+        assert_eq!(
+            amount_1,
+            liquidity_math::get_delta_amount_1_unsigned(
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                order.liquidity,
+                false
+            )
+            .unwrap()
+        );
+        assert!(amount_1 > 0);
+
+        order.zero_for_one = false;
+        assert_eq!(order.fill_tick_index(), 100);
+        let (amount_0, amount_1) = order.get_filled_amounts().unwrap();
+        assert!(amount_0 > 0);
+        assert_eq!(amount_1, 0);
+    }
+
+    #[test]
+    fn limit_order_fill_test() {
+        let mut fills = vec![LimitOrderFill::default(); 2];
+        LimitOrderFill::add_order(&mut fills, 110, 3, 1000).unwrap();
+        LimitOrderFill::add_order(&mut fills, 110, 3, 500).unwrap();
+        LimitOrderFill::add_order(&mut fills, 100, 0, 700).unwrap();
+        assert_eq!(LimitOrderFill::find(&fills, 110, 3), Some(0));
+        assert_eq!(identity(fills[0].liquidity), 1500);
+        // no free entry for the orders of the next fill epoch
+        assert_eq!(LimitOrderFill::find_or_free(&fills, 110, 4), None);
+
+        LimitOrderFill::record_fill(&mut fills, 110, 3, 10, 20, [30; REWARD_NUM]);
+        let fill = LimitOrderFill::remove_order(&mut fills, 110, 3, 1000)
+            .unwrap()
+            .unwrap();
+        assert_eq!(fill.filled, 1);
+        assert_eq!(identity(fill.fee_growth_inside_0_x64), 10);
+        assert_eq!(identity(fill.fee_growth_inside_1_x64), 20);
+        assert_eq!(identity(fill.reward_growths_inside_x64), [30; REWARD_NUM]);
+
+        // the fill of the orders of the next fill epoch doesn't change the recorded growth
+        LimitOrderFill::record_fill(&mut fills, 110, 4, 40, 50, [60; REWARD_NUM]);
+        let fill = LimitOrderFill::remove_order(&mut fills, 110, 3, 500)
+            .unwrap()
+            .unwrap();
+        assert_eq!(identity(fill.fee_growth_inside_0_x64), 10);
+        // the entry is free after all the orders are closed
+        assert_eq!(LimitOrderFill::find(&fills, 110, 3), None);
+        assert_eq!(LimitOrderFill::find_or_free(&fills, 110, 4), Some(0));
+        assert!(LimitOrderFill::remove_order(&mut fills, 110, 3, 1)
+            .unwrap()
+            .is_none());
+    }
+}
@@ -1,23 +1,31 @@
 pub mod admin_group;
 pub mod config;
+pub mod limit_order;
 pub mod offchain_reward_config;
+pub mod offchain_reward_merkle;
 pub mod operation_account;
 pub mod oracle;
 pub mod personal_position;
 pub mod pool;
+pub mod pool_permission;
 pub mod protocol_position;
+pub mod reward_funder;
 pub mod support_mint_associated;
 pub mod tick_array;
 pub mod tickarray_bitmap_extension;
 
 pub use admin_group::*;
 pub use config::*;
+pub use limit_order::*;
 pub use offchain_reward_config::*;
+pub use offchain_reward_merkle::*;
 pub use operation_account::*;
 pub use oracle::*;
 pub use personal_position::*;
 pub use pool::*;
+pub use pool_permission::*;
 pub use protocol_position::*;
+pub use reward_funder::*;
 pub use support_mint_associated::*;
 pub use tick_array::*;
 pub use tickarray_bitmap_extension::*;
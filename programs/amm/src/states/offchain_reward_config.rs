@@ -2,8 +2,12 @@ use crate::error::ErrorCode;
 use anchor_lang::{prelude::*, system_program};
 
 pub const OFFCHAIN_REWARD_SEED: &str = "offchain_reward";
+/// Tags the totals of the reward mints stored at the end of the config account
+pub const OFFCHAIN_REWARD_MINT_TOTALS_TAG: u32 = u32::from_le_bytes(*b"ormt");
 
-/// Holds the current owner of the factory
+/// Holds the current owner of the factory.
+/// The cumulative amounts of the reward mints are stored at the end of the account, after the space
+/// `reward_mint_vec` grows into, see `load_mint_totals`.
 #[account]
 #[derive(Default, Debug)]
 pub struct OffchainRewardConfig {
@@ -35,6 +39,62 @@ impl OffchainRewardConfig {
         Self::BASE_LENGTH + self.reward_mint_vec.len() * 32 // 32 for each Pubkey in reward_mint_vec
     }
 
+    /// The length of the totals of `count` reward mints stored at the end of this account
+    pub fn mint_totals_len(count: usize) -> usize {
+        if count == 0 {
+            return 0;
+        }
+        count * OffchainRewardMintTotals::LEN + 8
+    }
+
+    /// Read the totals of the reward mints stored at the end of the account, empty if the account
+    /// was not tagged by a deposit since the totals are tracked.
+    pub fn load_mint_totals(reward_config: &AccountInfo) -> Result<Vec<OffchainRewardMintTotals>> {
+        let data = reward_config.try_borrow_data()?;
+        let data_len = data.len();
+        if data_len < 8
+            || u32::from_le_bytes(data[data_len - 4..].try_into().unwrap())
+                != OFFCHAIN_REWARD_MINT_TOTALS_TAG
+        {
+            return Ok(Vec::new());
+        }
+        let count = u32::from_le_bytes(data[data_len - 8..data_len - 4].try_into().unwrap());
+        let start = data_len
+            .checked_sub(Self::mint_totals_len(count as usize))
+            .ok_or(ErrorCode::InvalidAccount)?;
+        let mut totals_data = &data[start..data_len - 8];
+        let mut totals = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            totals.push(OffchainRewardMintTotals::deserialize(&mut totals_data)?);
+        }
+        Ok(totals)
+    }
+
+    /// Write the totals of the reward mints at the end of the account, the account must have
+    /// the space after the config data.
+    pub fn save_mint_totals(
+        &self,
+        reward_config: &AccountInfo,
+        totals: &[OffchainRewardMintTotals],
+    ) -> Result<()> {
+        let mut data = reward_config.try_borrow_mut_data()?;
+        let data_len = data.len();
+        let start = data_len
+            .checked_sub(Self::mint_totals_len(totals.len()))
+            .ok_or(ErrorCode::AccountLack)?;
+        require_gte!(start, self.real_len(), ErrorCode::AccountLack);
+        if totals.is_empty() {
+            return Ok(());
+        }
+        let mut totals_data = &mut data[start..data_len - 8];
+        for reward_mint_totals in totals {
+            reward_mint_totals.serialize(&mut totals_data)?;
+        }
+        data[data_len - 8..data_len - 4].copy_from_slice(&(totals.len() as u32).to_le_bytes());
+        data[data_len - 4..].copy_from_slice(&OFFCHAIN_REWARD_MINT_TOTALS_TAG.to_le_bytes());
+        Ok(())
+    }
+
     pub fn initialize(
         &mut self,
         pool_id: Pubkey,
@@ -57,11 +117,12 @@ impl OffchainRewardConfig {
         ]
     }
 
-    /// Check if the reward-config account space needs to be reallocated to add a mint account.
-    /// Returns `true` if the account was reallocated.
+    /// Check if the reward-config account space needs to be reallocated to add a mint account
+    /// or the totals of a mint. Returns `true` if the account was reallocated, the totals of the
+    /// reward mints must be saved again at the new end of the account.
     pub fn realloc_if_needed<'a>(
         reward_config: AccountInfo<'a>,
-        reward_mint_count: usize,
+        account_size_to_fit_members: usize,
         rent_payer: AccountInfo<'a>,
         system_program: AccountInfo<'a>,
     ) -> Result<bool> {
@@ -73,7 +134,6 @@ impl OffchainRewardConfig {
         );
 
         let current_account_size = reward_config.data.borrow().len();
-        let account_size_to_fit_members = Self::need_len(reward_mint_count);
 
         // Check if we need to reallocate space.
         if current_account_size >= account_size_to_fit_members {
@@ -133,3 +193,168 @@ impl OffchainRewardConfig {
         }
     }
 }
+
+/// The cumulative amounts of a reward mint moved in and out of the offchain reward vault,
+/// a mint is tracked by its first deposit
+#[derive(Clone, Copy, Default, Debug, PartialEq, AnchorSerialize, AnchorDeserialize)]
+pub struct OffchainRewardMintTotals {
+    /// The reward token mint
+    pub token_mint: Pubkey,
+    /// The cumulative amount deposited into the vault, starts with the amount in the vault
+    /// when the mint is tracked
+    pub total_deposited: u64,
+    /// The cumulative amount claimed by the claimers
+    pub total_claimed: u64,
+    /// The cumulative amount withdrawn by the reward config manager
+    pub total_withdrawn: u64,
+}
+
+impl OffchainRewardMintTotals {
+    pub const LEN: usize = 32 + 8 + 8 + 8;
+
+    /// The claims co-signed by the reward claim manager can't update the config, the amount claimed
+    /// since the last update is taken from the amount in the vault.
+    pub fn sync_claimed(&mut self, vault_amount: u64) {
+        let implied_claimed = self
+            .total_deposited
+            .saturating_sub(self.total_withdrawn)
+            .saturating_sub(vault_amount);
+        self.total_claimed = self.total_claimed.max(implied_claimed);
+    }
+
+    /// The totals of the reward mint synced with the amount in the vault before the action,
+    /// `None` if the mint is not tracked yet
+    pub fn find_mut<'a>(
+        totals: &'a mut [Self],
+        token_mint: &Pubkey,
+        vault_amount: u64,
+    ) -> Option<&'a mut Self> {
+        let reward_mint_totals = totals
+            .iter_mut()
+            .find(|reward_mint_totals| reward_mint_totals.token_mint == *token_mint)?;
+        reward_mint_totals.sync_claimed(vault_amount);
+        Some(reward_mint_totals)
+    }
+
+    /// Same as `find_mut`, a mint not tracked yet starts with the amount in the vault as deposited
+    pub fn track(totals: &mut Vec<Self>, token_mint: Pubkey, vault_amount: u64) -> &mut Self {
+        let index = match totals
+            .iter()
+            .position(|reward_mint_totals| reward_mint_totals.token_mint == token_mint)
+        {
+            Some(index) => {
+                totals[index].sync_claimed(vault_amount);
+                index
+            }
+            None => {
+                totals.push(Self {
+                    token_mint,
+                    total_deposited: vault_amount,
+                    ..Default::default()
+                });
+                totals.len() - 1
+            }
+        };
+        &mut totals[index]
+    }
+
+    pub fn add_deposited(&mut self, amount: u64) -> Result<()> {
+        self.total_deposited = self
+            .total_deposited
+            .checked_add(amount)
+            .ok_or(ErrorCode::CalculateOverflow)?;
+        Ok(())
+    }
+
+    pub fn add_claimed(&mut self, amount: u64) -> Result<()> {
+        self.total_claimed = self
+            .total_claimed
+            .checked_add(amount)
+            .ok_or(ErrorCode::CalculateOverflow)?;
+        Ok(())
+    }
+
+    pub fn add_withdrawn(&mut self, amount: u64) -> Result<()> {
+        self.total_withdrawn = self
+            .total_withdrawn
+            .checked_add(amount)
+            .ok_or(ErrorCode::CalculateOverflow)?;
+        Ok(())
+    }
+}
+
+/// Emitted when the offchain reward is deposited
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct DepositOffchainRewardEvent {
+    pub pool_id: Pubkey,
+    pub payer: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    pub total_deposited: u64,
+}
+
+/// Emitted when the offchain reward is claimed
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ClaimOffchainRewardEvent {
+    pub pool_id: Pubkey,
+    pub claimer: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    /// The epoch of the merkle root proved, 0 if the claim is co-signed by the reward claim manager
+    pub epoch: u64,
+    pub total_claimed: u64,
+}
+
+/// Emitted when the offchain reward is withdrawn
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct WithdrawOffchainRewardEvent {
+    pub pool_id: Pubkey,
+    pub receiver_token_account: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    pub total_withdrawn: u64,
+}
+
+#[cfg(test)]
+mod offchain_reward_config_test {
+    use super::*;
+
+    #[test]
+    fn track_mint_totals_test() {
+        let token_mint = Pubkey::new_unique();
+        let mut totals = Vec::new();
+        assert!(OffchainRewardMintTotals::find_mut(&mut totals, &token_mint, 0).is_none());
+
+        // the amount in the vault before the first tracked deposit is taken as deposited
+        let reward_mint_totals = OffchainRewardMintTotals::track(&mut totals, token_mint, 300);
+        reward_mint_totals.add_deposited(700).unwrap();
+        assert_eq!(reward_mint_totals.total_deposited, 1000);
+        assert_eq!(totals.len(), 1);
+
+        // 100 withdrawn, then 250 claimed by co-signed claims not recorded in the config
+        let reward_mint_totals =
+            OffchainRewardMintTotals::find_mut(&mut totals, &token_mint, 1000).unwrap();
+        reward_mint_totals.add_withdrawn(100).unwrap();
+        let reward_mint_totals =
+            OffchainRewardMintTotals::find_mut(&mut totals, &token_mint, 650).unwrap();
+        assert_eq!(reward_mint_totals.total_claimed, 250);
+
+        // a recorded claim is not counted again by the next sync
+        reward_mint_totals.add_claimed(50).unwrap();
+        let reward_mint_totals = OffchainRewardMintTotals::track(&mut totals, token_mint, 600);
+        assert_eq!(reward_mint_totals.total_claimed, 300);
+        assert_eq!(totals.len(), 1);
+    }
+
+    #[test]
+    fn mint_totals_len_test() {
+        assert_eq!(OffchainRewardConfig::mint_totals_len(0), 0);
+        assert_eq!(
+            OffchainRewardConfig::mint_totals_len(2),
+            2 * OffchainRewardMintTotals::LEN + 8
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+pub const OFFCHAIN_REWARD_MERKLE_ROOT_SEED: &str = "offchain_reward_merkle_root";
+pub const OFFCHAIN_REWARD_CLAIM_STATUS_SEED: &str = "offchain_reward_claim_status";
+
+/// Prefix of the leaf hash, so an intermediate node can't be proved as a leaf
+const MERKLE_LEAF_PREFIX: &[u8] = &[0];
+/// Prefix of the intermediate node hash
+const MERKLE_NODE_PREFIX: &[u8] = &[1];
+
+/// The merkle root of the offchain rewards published by the reward claim manager every epoch.
+/// Each leaf is the cumulative amount of a reward mint the claimer is entitled to since the first epoch,
+/// so the latest root covers the rewards of all the previous epochs.
+#[account]
+#[derive(Default, Debug)]
+pub struct OffchainRewardMerkleRoot {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The offchain reward config the root belongs to
+    pub reward_config: Pubkey,
+    /// The epoch of the root, increased every time a new root is published
+    pub epoch: u64,
+    /// The root of the merkle tree with sorted pair hashing
+    pub merkle_root: [u8; 32],
+    /// The block timestamp the root was published
+    pub update_time: u64,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 8],
+}
+
+impl OffchainRewardMerkleRoot {
+    pub const LEN: usize = 8 + 1 + 32 + 8 + 32 + 8 + 8 * 8;
+
+    /// The leaf of the claimer's cumulative amount of the reward mint
+    pub fn get_leaf(claimer: &Pubkey, token_mint: &Pubkey, cumulative_amount: u64) -> [u8; 32] {
+        hashv(&[
+            MERKLE_LEAF_PREFIX,
+            claimer.as_ref(),
+            token_mint.as_ref(),
+            &cumulative_amount.to_le_bytes(),
+        ])
+        .to_bytes()
+    }
+
+    /// Whether the leaf is in the tree of the root, the pair of nodes is hashed in sorted order
+    /// so the proof doesn't carry the side of each node.
+    pub fn verify(&self, proof: &[[u8; 32]], leaf: [u8; 32]) -> bool {
+        let mut computed_hash = leaf;
+        for node in proof {
+            computed_hash = if computed_hash <= *node {
+                hashv(&[MERKLE_NODE_PREFIX, &computed_hash, node]).to_bytes()
+            } else {
+                hashv(&[MERKLE_NODE_PREFIX, node, &computed_hash]).to_bytes()
+            };
+        }
+        computed_hash == self.merkle_root
+    }
+}
+
+/// The amount of a reward mint claimed by the claimer, with merkle proofs or approved by the reward
+/// claim manager, prevents the double claims
+#[account]
+#[derive(Default, Debug)]
+pub struct OffchainRewardClaimStatus {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The offchain reward config the reward is claimed from
+    pub reward_config: Pubkey,
+    /// The address who claims the reward
+    pub claimer: Pubkey,
+    /// The reward token mint
+    pub token_mint: Pubkey,
+    /// The cumulative amount claimed
+    pub claimed_amount: u64,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 4],
+}
+
+impl OffchainRewardClaimStatus {
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 32 + 8 + 8 * 4;
+
+    pub fn initialize_if_needed(
+        &mut self,
+        bump: u8,
+        reward_config: Pubkey,
+        claimer: Pubkey,
+        token_mint: Pubkey,
+    ) {
+        if self.claimer == Pubkey::default() {
+            self.bump = bump;
+            self.reward_config = reward_config;
+            self.claimer = claimer;
+            self.token_mint = token_mint;
+        }
+    }
+
+    pub fn add_claimed(&mut self, amount: u64) -> Result<()> {
+        self.claimed_amount = self
+            .claimed_amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::CalculateOverflow)?;
+        Ok(())
+    }
+}
+
+/// Emitted when a new merkle root of the offchain rewards is published
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct UpdateOffchainRewardMerkleRootEvent {
+    pub pool_id: Pubkey,
+    pub epoch: u64,
+    pub merkle_root: [u8; 32],
+}
+
+#[cfg(test)]
+mod offchain_reward_merkle_test {
+    use super::*;
+
+    fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        if a <= b {
+            hashv(&[MERKLE_NODE_PREFIX, &a, &b]).to_bytes()
+        } else {
+            hashv(&[MERKLE_NODE_PREFIX, &b, &a]).to_bytes()
+        }
+    }
+
+    #[test]
+    fn verify_proof_test() {
+        let token_mint = Pubkey::new_unique();
+        let claimers: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = claimers
+            .iter()
+            .enumerate()
+            .map(|(i, claimer)| {
+                OffchainRewardMerkleRoot::get_leaf(claimer, &token_mint, 100 * (i as u64 + 1))
+            })
+            .collect();
+        // the odd leaf is promoted to the next level
+        let node_01 = hash_pair(leaves[0], leaves[1]);
+        let root = OffchainRewardMerkleRoot {
+            merkle_root: hash_pair(node_01, leaves[2]),
+            ..Default::default()
+        };
+
+        assert!(root.verify(&[leaves[1], leaves[2]], leaves[0]));
+        assert!(root.verify(&[leaves[0], leaves[2]], leaves[1]));
+        assert!(root.verify(&[node_01], leaves[2]));
+
+        // the amount or the mint of the leaf is changed
+        let leaf = OffchainRewardMerkleRoot::get_leaf(&claimers[0], &token_mint, 101);
+        assert!(!root.verify(&[leaves[1], leaves[2]], leaf));
+        let leaf = OffchainRewardMerkleRoot::get_leaf(&claimers[0], &Pubkey::new_unique(), 100);
+        assert!(!root.verify(&[leaves[1], leaves[2]], leaf));
+        // the proof of the other leaf
+        assert!(!root.verify(&[leaves[0], leaves[2]], leaves[0]));
+    }
+}
@@ -1,7 +1,9 @@
 /// Oracle provides price data useful for a wide variety of system designs
 ///
 use anchor_lang::prelude::*;
+use std::cell::{Ref, RefMut};
 
+use crate::error::ErrorCode;
 use crate::util::get_recent_epoch;
 
 /// Seed to derive account address and signature
@@ -9,6 +11,10 @@ pub const OBSERVATION_SEED: &str = "observation";
 // Number of ObservationState element
 pub const OBSERVATION_NUM: usize = 100;
 pub const OBSERVATION_UPDATE_DURATION_DEFAULT: u32 = 15;
+// The max update duration, the observations can cover 25 hours at most
+pub const OBSERVATION_UPDATE_DURATION_MAX: u32 = 900;
+// The max number of observations the ring buffer of a pool can grow to
+pub const OBSERVATION_CARDINALITY_MAX: u16 = 1000;
 
 /// The element of observations in ObservationState
 #[zero_copy(unsafe)]
@@ -19,14 +25,20 @@ pub struct Observation {
     pub block_timestamp: u32,
     /// the cumulative of tick during the duration time
     pub tick_cumulative: i64,
+    /// the seconds per in range liquidity of the pool as of the observation timestamp, Q64.64
+    pub seconds_per_liquidity_cumulative_x64: u128,
     /// padding for feature update
-    pub padding: [u64; 4],
+    pub padding: [u64; 2],
 }
 
 impl Observation {
-    pub const LEN: usize = 4 + 8 + 8 * 4;
+    pub const LEN: usize = 4 + 8 + 16 + 8 * 2;
 }
 
+// The observations beyond `OBSERVATION_NUM` are cast from the tail of the account data
+unsafe impl bytemuck::Pod for Observation {}
+unsafe impl bytemuck::Zeroable for Observation {}
+
 #[account(zero_copy(unsafe))]
 #[repr(C, packed)]
 #[cfg_attr(feature = "client", derive(Debug))]
@@ -41,8 +53,16 @@ pub struct ObservationState {
     pub pool_id: Pubkey,
     /// observation array
     pub observations: [Observation; OBSERVATION_NUM],
+    /// the min duration between two stored observations, the newest observation is moved forward until it is
+    /// this duration after the one before it. `OBSERVATION_UPDATE_DURATION_DEFAULT` is used if it is zero
+    pub update_duration: u32,
+    /// the number of observations in the ring buffer, `OBSERVATION_NUM` if it is zero.
+    /// The observations beyond `OBSERVATION_NUM` are stored after the account struct.
+    pub observation_cardinality: u16,
+    /// the number of observations the ring buffer grows to the next time it wraps around
+    pub observation_cardinality_next: u16,
     /// padding for feature update
-    pub padding: [u64; 4],
+    pub padding: [u64; 3],
 }
 
 impl Default for ObservationState {
@@ -54,13 +74,17 @@ impl Default for ObservationState {
             observation_index: 0,
             pool_id: Pubkey::default(),
             observations: [Observation::default(); OBSERVATION_NUM],
-            padding: [0u64; 4],
+            update_duration: 0,
+            observation_cardinality: 0,
+            observation_cardinality_next: 0,
+            padding: [0u64; 3],
         }
     }
 }
 
 impl ObservationState {
-    pub const LEN: usize = 8 + 1 + 8 + 2 + 32 + (Observation::LEN * OBSERVATION_NUM) + 8 * 4;
+    pub const LEN: usize =
+        8 + 1 + 8 + 2 + 32 + (Observation::LEN * OBSERVATION_NUM) + 4 + 4 + 8 * 3;
 
     pub fn initialize(&mut self, pool_id: Pubkey) -> Result<()> {
         self.initialized = false;
@@ -68,45 +92,289 @@ impl ObservationState {
         self.observation_index = 0;
         self.pool_id = pool_id;
         self.observations = [Observation::default(); OBSERVATION_NUM];
-        self.padding = [0u64; 4];
+        self.update_duration = OBSERVATION_UPDATE_DURATION_DEFAULT;
+        self.observation_cardinality = OBSERVATION_NUM as u16;
+        self.observation_cardinality_next = OBSERVATION_NUM as u16;
+        self.padding = [0u64; 3];
         Ok(())
     }
 
-    /// Writes an oracle observation to the account
+    /// The min duration between two stored observations
+    pub fn get_update_duration(&self) -> u32 {
+        if self.update_duration == 0 {
+            OBSERVATION_UPDATE_DURATION_DEFAULT
+        } else {
+            self.update_duration
+        }
+    }
+
+    /// The number of observations in the ring buffer
+    pub fn get_cardinality(&self) -> usize {
+        if self.observation_cardinality == 0 {
+            OBSERVATION_NUM
+        } else {
+            self.observation_cardinality as usize
+        }
+    }
+
+    /// The number of observations the ring buffer grows to the next time it wraps around
+    pub fn get_cardinality_next(&self) -> usize {
+        (self.observation_cardinality_next as usize).max(self.get_cardinality())
+    }
+
+    /// The size of the account holding `cardinality` observations
+    pub fn need_len(cardinality: usize) -> usize {
+        Self::LEN + Observation::LEN * cardinality.saturating_sub(OBSERVATION_NUM)
+    }
+
+    fn get_observation(&self, extension: &[Observation], index: usize) -> Option<Observation> {
+        if index < OBSERVATION_NUM {
+            Some(self.observations[index])
+        } else {
+            extension.get(index - OBSERVATION_NUM).copied()
+        }
+    }
+
+    fn get_observation_mut<'a>(
+        &'a mut self,
+        extension: &'a mut [Observation],
+        index: usize,
+    ) -> Option<&'a mut Observation> {
+        if index < OBSERVATION_NUM {
+            Some(&mut self.observations[index])
+        } else {
+            extension.get_mut(index - OBSERVATION_NUM)
+        }
+    }
+
+    /// Writes an oracle observation to the account, it must be called before every change of the tick
+    /// so that the tick cumulative of the observation is charged with the tick actually in effect.
+    /// A new observation is appended once the newest one is `update_duration` after the one before it,
+    /// otherwise the newest observation is moved forward to `block_timestamp`.
     ///
     /// # Arguments
     ///
     /// * `self` - The ObservationState account to write in
+    /// * `extension` - The observations stored after the account struct
     /// * `block_timestamp` - The current timestamp of to update
+    /// * `tick` - The tick in effect since the newest observation
+    /// * `seconds_per_liquidity_cumulative_x64` - The seconds per liquidity of the pool at `block_timestamp`
     ///
-    pub fn update(&mut self, block_timestamp: u32, tick: i32) {
-        let observation_index = self.observation_index;
+    pub fn update(
+        &mut self,
+        extension: &mut [Observation],
+        block_timestamp: u32,
+        tick: i32,
+        seconds_per_liquidity_cumulative_x64: u128,
+    ) {
+        let observation_index = self.observation_index as usize;
         if !self.initialized {
             self.initialized = true;
-            self.observations[observation_index as usize].block_timestamp = block_timestamp;
-            self.observations[observation_index as usize].tick_cumulative = 0;
+            self.observations[observation_index].block_timestamp = block_timestamp;
+            self.observations[observation_index].tick_cumulative = 0;
+            self.observations[observation_index].seconds_per_liquidity_cumulative_x64 =
+                seconds_per_liquidity_cumulative_x64;
         } else {
-            let last_observation = self.observations[observation_index as usize];
+            let last_observation = match self.get_observation(extension, observation_index) {
+                Some(last_observation) => last_observation,
+                None => return,
+            };
             let delta_time = block_timestamp.saturating_sub(last_observation.block_timestamp);
-            if delta_time < OBSERVATION_UPDATE_DURATION_DEFAULT {
+            if delta_time == 0 {
                 return;
             }
 
             let delta_tick_cumulative = i64::from(tick).checked_mul(delta_time.into()).unwrap();
-            let next_observation_index = if observation_index as usize == OBSERVATION_NUM - 1 {
-                0
-            } else {
-                observation_index + 1
+            let cardinality = self.get_cardinality();
+            let previous_observation = self
+                .get_observation(
+                    extension,
+                    (observation_index + cardinality - 1) % cardinality,
+                )
+                .filter(|previous_observation| {
+                    previous_observation.block_timestamp != 0
+                        && previous_observation.block_timestamp < last_observation.block_timestamp
+                });
+            let next_observation_index = match previous_observation {
+                // the newest observation is still within the update duration of the one before it
+                Some(previous_observation)
+                    if last_observation.block_timestamp - previous_observation.block_timestamp
+                        < self.get_update_duration() =>
+                {
+                    observation_index
+                }
+                _ => {
+                    // the ring buffer only grows when it wraps around, so the observations keep in order
+                    let cardinality = if observation_index == cardinality - 1 {
+                        self.get_cardinality_next()
+                            .min(OBSERVATION_NUM + extension.len())
+                    } else {
+                        cardinality
+                    };
+                    self.observation_cardinality = cardinality as u16;
+                    (observation_index + 1) % cardinality
+                }
             };
-            self.observations[next_observation_index as usize].block_timestamp = block_timestamp;
-            self.observations[next_observation_index as usize].tick_cumulative = last_observation
+            let next_observation = match self.get_observation_mut(extension, next_observation_index)
+            {
+                Some(next_observation) => next_observation,
+                None => return,
+            };
+            next_observation.block_timestamp = block_timestamp;
+            next_observation.tick_cumulative = last_observation
                 .tick_cumulative
                 .wrapping_add(delta_tick_cumulative);
-            self.observation_index = next_observation_index;
+            next_observation.seconds_per_liquidity_cumulative_x64 =
+                seconds_per_liquidity_cumulative_x64;
+            self.observation_index = next_observation_index as u16;
+        }
+    }
+
+    /// Returns the tick cumulative as of each `seconds_ago` from `block_timestamp`
+    ///
+    /// # Arguments
+    ///
+    /// * `extension` - The observations stored after the account struct
+    /// * `block_timestamp` - The current block timestamp
+    /// * `seconds_agos` - How long ago of each cumulative should be returned
+    /// * `tick_current` - The current tick of the pool, used to extrapolate after the most recent observation
+    ///
+    pub fn observe(
+        &self,
+        extension: &[Observation],
+        block_timestamp: u32,
+        seconds_agos: &[u32],
+        tick_current: i32,
+    ) -> Result<Vec<i64>> {
+        seconds_agos
+            .iter()
+            .map(|seconds_ago| {
+                self.observe_single(extension, block_timestamp, *seconds_ago, tick_current)
+            })
+            .collect()
+    }
+
+    /// Returns the tick cumulative at `block_timestamp - seconds_ago`, the value between two observations
+    /// is interpolated and the value after the most recent observation is extrapolated with `tick_current`,
+    /// which is in effect since the most recent observation as every change of the tick is written first.
+    pub fn observe_single(
+        &self,
+        extension: &[Observation],
+        block_timestamp: u32,
+        seconds_ago: u32,
+        tick_current: i32,
+    ) -> Result<i64> {
+        require!(self.initialized, ErrorCode::InvalidObservationWindow);
+        let target = block_timestamp
+            .checked_sub(seconds_ago)
+            .ok_or(ErrorCode::InvalidObservationWindow)?;
+        let cardinality = self.get_cardinality();
+        let observation_at = |index: usize| {
+            self.get_observation(extension, index % cardinality)
+                .ok_or(ErrorCode::InvalidObservationWindow)
+        };
+
+        let newest = observation_at(self.observation_index as usize)?;
+        if target >= newest.block_timestamp {
+            let delta_time = target - newest.block_timestamp;
+            return Ok(newest
+                .tick_cumulative
+                .wrapping_add(i64::from(tick_current) * i64::from(delta_time)));
+        }
+
+        // the observations after the newest one are empty until the ring buffer is full
+        let next_index = (self.observation_index as usize + 1) % cardinality;
+        let (oldest_index, count) = if observation_at(next_index)?.block_timestamp == 0 {
+            (0, self.observation_index as usize + 1)
+        } else {
+            (next_index, cardinality)
+        };
+        let oldest = observation_at(oldest_index)?;
+        require_gte!(
+            target,
+            oldest.block_timestamp,
+            ErrorCode::InvalidObservationWindow
+        );
+
+        // binary search the last observation at or before the target
+        let (mut low, mut high) = (0, count - 1);
+        while low < high {
+            let mid = (low + high + 1) / 2;
+            let observation = observation_at(oldest_index + mid)?;
+            if observation.block_timestamp <= target {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        let before = observation_at(oldest_index + low)?;
+        if before.block_timestamp == target {
+            return Ok(before.tick_cumulative);
+        }
+        let after = observation_at(oldest_index + low + 1)?;
+        let observation_time_delta = i64::from(after.block_timestamp - before.block_timestamp);
+        let target_delta = i64::from(target - before.block_timestamp);
+        Ok(before.tick_cumulative.wrapping_add(
+            after.tick_cumulative.wrapping_sub(before.tick_cumulative) / observation_time_delta
+                * target_delta,
+        ))
+    }
+
+    /// Returns the time weighted average tick over the last `seconds_ago` seconds, rounded to negative infinity
+    pub fn get_twap_tick(
+        &self,
+        extension: &[Observation],
+        block_timestamp: u32,
+        seconds_ago: u32,
+        tick_current: i32,
+    ) -> Result<i32> {
+        require_gt!(seconds_ago, 0, ErrorCode::InvalidObservationWindow);
+        let tick_cumulatives =
+            self.observe(extension, block_timestamp, &[seconds_ago, 0], tick_current)?;
+        let tick_cumulative_delta = tick_cumulatives[1].wrapping_sub(tick_cumulatives[0]);
+        let mut twap_tick = tick_cumulative_delta / i64::from(seconds_ago);
+        if tick_cumulative_delta < 0 && tick_cumulative_delta % i64::from(seconds_ago) != 0 {
+            twap_tick -= 1;
         }
+        Ok(twap_tick as i32)
     }
 }
 
+/// Loads the observation state with the observations stored after the account struct
+pub fn load_observation_state<'a>(
+    observation_loader: &'a AccountLoader<ObservationState>,
+) -> Result<(Ref<'a, ObservationState>, Ref<'a, [Observation]>)> {
+    // check the discriminator
+    drop(observation_loader.load()?);
+    let data = observation_loader.as_ref().try_borrow_data()?;
+    let (state, extension) = Ref::map_split(data, |data| data.split_at(ObservationState::LEN));
+    Ok((
+        Ref::map(state, |state| bytemuck::from_bytes(&state[8..])),
+        Ref::map(extension, |extension| {
+            let len = extension.len() / Observation::LEN * Observation::LEN;
+            bytemuck::cast_slice(&extension[..len])
+        }),
+    ))
+}
+
+/// Loads the mutable observation state with the observations stored after the account struct
+pub fn load_observation_state_mut<'a>(
+    observation_loader: &'a AccountLoader<ObservationState>,
+) -> Result<(RefMut<'a, ObservationState>, RefMut<'a, [Observation]>)> {
+    // check the discriminator and the account is writable
+    drop(observation_loader.load_mut()?);
+    let data = observation_loader.as_ref().try_borrow_mut_data()?;
+    let (state, extension) =
+        RefMut::map_split(data, |data| data.split_at_mut(ObservationState::LEN));
+    Ok((
+        RefMut::map(state, |state| bytemuck::from_bytes_mut(&mut state[8..])),
+        RefMut::map(extension, |extension| {
+            let len = extension.len() / Observation::LEN * Observation::LEN;
+            bytemuck::cast_slice_mut(&mut extension[..len])
+        }),
+    ))
+}
+
 /// Returns the block timestamp truncated to 32 bits, i.e. mod 2**32
 ///
 pub fn block_timestamp() -> u32 {
@@ -132,12 +400,10 @@ pub mod oracle_layout_test {
         let recent_epoch: u64 = 0x123456789abcdef0;
         let observation_index: u16 = 0x1122;
         let pool_id: Pubkey = Pubkey::new_unique();
-        let padding: [u64; 4] = [
-            0x123456789abcde0f,
-            0x123456789abcd0ef,
-            0x123456789abc0def,
-            0x123456789ab0cdef,
-        ];
+        let update_duration: u32 = 0x12345678;
+        let observation_cardinality: u16 = 0x1234;
+        let observation_cardinality_next: u16 = 0x5678;
+        let padding: [u64; 3] = [0x123456789abcde0f, 0x123456789abcd0ef, 0x123456789abc0def];
 
         let mut observation_datas = [0u8; Observation::LEN * OBSERVATION_NUM];
         let mut observations = [Observation::default(); OBSERVATION_NUM];
@@ -146,27 +412,24 @@ pub mod oracle_layout_test {
             let index = i + 1;
             let block_timestamp: u32 = u32::MAX - 3 * index as u32;
             let tick_cumulative: i64 = i64::MAX - 3 * index as i64;
-            let padding: [u64; 4] = [
-                u64::MAX - index as u64,
-                u64::MAX - 2 * index as u64,
-                u64::MAX - 3 * index as u64,
-                u64::MAX - 4 * index as u64,
-            ];
+            let seconds_per_liquidity_cumulative_x64: u128 = u128::MAX - 5 * index as u128;
+            let padding: [u64; 2] = [u64::MAX - index as u64, u64::MAX - 2 * index as u64];
             observations[i].block_timestamp = block_timestamp;
             observations[i].tick_cumulative = tick_cumulative;
+            observations[i].seconds_per_liquidity_cumulative_x64 =
+                seconds_per_liquidity_cumulative_x64;
             observations[i].padding = padding;
             observation_datas[offset..offset + 4].copy_from_slice(&block_timestamp.to_le_bytes());
             offset += 4;
             observation_datas[offset..offset + 8].copy_from_slice(&tick_cumulative.to_le_bytes());
             offset += 8;
+            observation_datas[offset..offset + 16]
+                .copy_from_slice(&seconds_per_liquidity_cumulative_x64.to_le_bytes());
+            offset += 16;
             observation_datas[offset..offset + 8].copy_from_slice(&padding[0].to_le_bytes());
             offset += 8;
             observation_datas[offset..offset + 8].copy_from_slice(&padding[1].to_le_bytes());
             offset += 8;
-            observation_datas[offset..offset + 8].copy_from_slice(&padding[2].to_le_bytes());
-            offset += 8;
-            observation_datas[offset..offset + 8].copy_from_slice(&padding[3].to_le_bytes());
-            offset += 8;
         }
 
         // serialize original data
@@ -188,14 +451,20 @@ pub mod oracle_layout_test {
         observation_state_data[offset..offset + Observation::LEN * OBSERVATION_NUM]
             .copy_from_slice(&observation_datas);
         offset += Observation::LEN * OBSERVATION_NUM;
+        observation_state_data[offset..offset + 4].copy_from_slice(&update_duration.to_le_bytes());
+        offset += 4;
+        observation_state_data[offset..offset + 2]
+            .copy_from_slice(&observation_cardinality.to_le_bytes());
+        offset += 2;
+        observation_state_data[offset..offset + 2]
+            .copy_from_slice(&observation_cardinality_next.to_le_bytes());
+        offset += 2;
         observation_state_data[offset..offset + 8].copy_from_slice(&padding[0].to_le_bytes());
         offset += 8;
         observation_state_data[offset..offset + 8].copy_from_slice(&padding[1].to_le_bytes());
         offset += 8;
         observation_state_data[offset..offset + 8].copy_from_slice(&padding[2].to_le_bytes());
         offset += 8;
-        observation_state_data[offset..offset + 8].copy_from_slice(&padding[3].to_le_bytes());
-        offset += 8;
         // len check
         assert_eq!(offset, observation_state_data.len());
         assert_eq!(
@@ -217,6 +486,15 @@ pub mod oracle_layout_test {
         assert_eq!(unpack_observation_index, observation_index);
         let unpack_pool_id = unpack_data.pool_id;
         assert_eq!(unpack_pool_id, pool_id);
+        let unpack_update_duration = unpack_data.update_duration;
+        assert_eq!(unpack_update_duration, update_duration);
+        let unpack_observation_cardinality = unpack_data.observation_cardinality;
+        assert_eq!(unpack_observation_cardinality, observation_cardinality);
+        let unpack_observation_cardinality_next = unpack_data.observation_cardinality_next;
+        assert_eq!(
+            unpack_observation_cardinality_next,
+            observation_cardinality_next
+        );
         let unpack_padding = unpack_data.padding;
         assert_eq!(unpack_padding, padding);
         for (observation, unpack_observation) in
@@ -224,14 +502,191 @@ pub mod oracle_layout_test {
         {
             let block_timestamp = observation.block_timestamp;
             let tick_cumulative = observation.tick_cumulative;
+            let seconds_per_liquidity_cumulative_x64 =
+                observation.seconds_per_liquidity_cumulative_x64;
             let padding = observation.padding;
 
             let unpack_block_timestamp = unpack_observation.block_timestamp;
             let unpack_tick_cumulative = unpack_observation.tick_cumulative;
+            let unpack_seconds_per_liquidity_cumulative_x64 =
+                unpack_observation.seconds_per_liquidity_cumulative_x64;
             let unpack_padding = unpack_observation.padding;
             assert_eq!(block_timestamp, unpack_block_timestamp);
             assert_eq!(tick_cumulative, unpack_tick_cumulative);
+            assert_eq!(
+                seconds_per_liquidity_cumulative_x64,
+                unpack_seconds_per_liquidity_cumulative_x64
+            );
             assert_eq!(padding, unpack_padding);
         }
     }
 }
+
+#[cfg(test)]
+pub mod observe_test {
+    use super::*;
+
+    fn build_observation_state(start_time: u32, ticks: &[i32], duration: u32) -> ObservationState {
+        let mut observation_state = ObservationState::default();
+        observation_state.update_duration = duration;
+        let mut block_timestamp = start_time;
+        for tick in ticks {
+            observation_state.update(&mut [], block_timestamp, *tick, 0);
+            block_timestamp += duration;
+        }
+        observation_state
+    }
+
+    #[test]
+    fn observe_interpolate_test() {
+        // tick is 10 during [1000, 1015), 20 during [1015, 1030)
+        let observation_state = build_observation_state(1000, &[0, 10, 20], 15);
+        let observation_index = observation_state.observation_index;
+        assert_eq!(observation_index, 2);
+
+        let tick_cumulatives = observation_state
+            .observe(&[], 1030, &[30, 20, 15, 5, 0], 20)
+            .unwrap();
+        assert_eq!(tick_cumulatives, vec![0, 100, 150, 350, 450]);
+        // extrapolate after the most recent observation with the current tick
+        assert_eq!(
+            observation_state.observe_single(&[], 1040, 0, 30).unwrap(),
+            750
+        );
+    }
+
+    #[test]
+    fn observe_out_of_window_test() {
+        let observation_state = build_observation_state(1000, &[0, 10, 20], 15);
+        assert!(observation_state.observe_single(&[], 1030, 31, 20).is_err());
+        assert!(ObservationState::default()
+            .observe_single(&[], 1030, 0, 20)
+            .is_err());
+    }
+
+    #[test]
+    fn observe_ring_buffer_wrapped_test() {
+        let ticks: Vec<i32> = (0..(OBSERVATION_NUM + 10) as i32).collect();
+        let observation_state = build_observation_state(1000, &ticks, 15);
+        let observation_index = observation_state.observation_index;
+        assert_eq!(observation_index, 9);
+        let newest_time = 1000 + 15 * (OBSERVATION_NUM as u32 + 9);
+        let oldest_time = newest_time - 15 * (OBSERVATION_NUM as u32 - 1);
+        assert!(observation_state
+            .observe_single(&[], newest_time, newest_time - oldest_time, 0)
+            .is_ok());
+        assert!(observation_state
+            .observe_single(&[], newest_time, newest_time - oldest_time + 1, 0)
+            .is_err());
+    }
+
+    #[test]
+    fn observe_cardinality_grow_test() {
+        let mut observation_state = ObservationState::default();
+        observation_state.update_duration = 15;
+        observation_state.observation_cardinality_next = OBSERVATION_NUM as u16 + 20;
+        let mut extension = vec![Observation::default(); 20];
+
+        let mut block_timestamp = 1000;
+        for tick in 0..(OBSERVATION_NUM + 10) as i32 {
+            observation_state.update(&mut extension, block_timestamp, tick, 0);
+            block_timestamp += 15;
+        }
+        // the ring buffer grows instead of wrapping around
+        let observation_index = observation_state.observation_index;
+        assert_eq!(observation_index as usize, OBSERVATION_NUM + 9);
+        assert_eq!(observation_state.get_cardinality(), OBSERVATION_NUM + 20);
+        let newest_time = block_timestamp - 15;
+        assert!(observation_state
+            .observe_single(&extension, newest_time, newest_time - 1000, 0)
+            .is_ok());
+        assert!(observation_state
+            .observe_single(&extension, newest_time, newest_time - 1000 + 1, 0)
+            .is_err());
+        // tick is 102 during [2515, 2530)
+        let tick_cumulatives = observation_state
+            .observe(
+                &extension,
+                newest_time,
+                &[newest_time - 2515, newest_time - 2530],
+                0,
+            )
+            .unwrap();
+        assert_eq!(tick_cumulatives[1] - tick_cumulatives[0], 102 * 15);
+
+        // wraps around after the extension is full
+        for tick in 0..11 {
+            observation_state.update(&mut extension, block_timestamp, tick, 0);
+            block_timestamp += 15;
+        }
+        let observation_index = observation_state.observation_index;
+        assert_eq!(observation_index, 0);
+        let newest_time = block_timestamp - 15;
+        let oldest_time = newest_time - 15 * (OBSERVATION_NUM as u32 + 19);
+        assert!(observation_state
+            .observe_single(&extension, newest_time, newest_time - oldest_time, 0)
+            .is_ok());
+        assert!(observation_state
+            .observe_single(&extension, newest_time, newest_time - oldest_time + 1, 0)
+            .is_err());
+    }
+
+    #[test]
+    fn update_within_duration_test() {
+        let mut observation_state = ObservationState::default();
+        observation_state.update_duration = 15;
+        // tick is 0 during [1000, 1005), 10 during [1005, 1020), 20 during [1020, 1040)
+        observation_state.update(&mut [], 1000, 0, 0);
+        observation_state.update(&mut [], 1005, 0, 0);
+        observation_state.update(&mut [], 1020, 10, 0);
+        // the newest observation is moved forward until it is 15s after the one before it
+        let observation_index = observation_state.observation_index;
+        assert_eq!(observation_index, 1);
+        observation_state.update(&mut [], 1040, 20, 0);
+        let observation_index = observation_state.observation_index;
+        assert_eq!(observation_index, 2);
+
+        let tick_cumulatives = observation_state
+            .observe(&[], 1040, &[40, 20, 0], 20)
+            .unwrap();
+        assert_eq!(tick_cumulatives, vec![0, 150, 550]);
+        // no update in the same timestamp
+        observation_state.update(&mut [], 1040, 30, 0);
+        assert_eq!(
+            observation_state.observe_single(&[], 1040, 0, 20).unwrap(),
+            550
+        );
+    }
+
+    #[test]
+    fn twap_tick_manipulated_in_same_timestamp_test() {
+        let mut observation_state = build_observation_state(1000, &[0, 10, 10], 15);
+        let twap_tick = observation_state.get_twap_tick(&[], 1035, 30, 10).unwrap();
+        assert_eq!(twap_tick, 10);
+        // a swap moves the tick far away and the twap is read in the same timestamp,
+        // the moved tick has no weight in the average
+        observation_state.update(&mut [], 1035, 10, 0);
+        assert_eq!(
+            observation_state
+                .get_twap_tick(&[], 1035, 30, 100_000)
+                .unwrap(),
+            twap_tick
+        );
+    }
+
+    #[test]
+    fn twap_tick_test() {
+        let observation_state = build_observation_state(1000, &[0, -10, -21], 15);
+        // tick cumulative: 1000 => 0, 1015 => -150, 1030 => -465
+        assert_eq!(
+            observation_state.get_twap_tick(&[], 1030, 15, -21).unwrap(),
+            -21
+        );
+        // (-465 - 0) / 30 = -15.5, rounded to negative infinity
+        assert_eq!(
+            observation_state.get_twap_tick(&[], 1030, 30, -21).unwrap(),
+            -16
+        );
+        assert!(observation_state.get_twap_tick(&[], 1030, 0, -21).is_err());
+    }
+}
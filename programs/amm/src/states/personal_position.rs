@@ -42,13 +42,30 @@ pub struct PersonalPositionState {
     pub reward_infos: [PositionRewardInfo; REWARD_NUM],
     // account update recent epoch
     pub recent_epoch: u64,
+    /// The pool reward generations the reward infos are recorded for
+    pub reward_generations: [u16; REWARD_NUM],
+    pub padding1: u16,
     // Unused bytes for future upgrades.
-    pub padding: [u64; 7],
+    pub padding: [u64; 6],
 }
 
 impl PersonalPositionState {
-    pub const LEN: usize =
-        8 + 1 + 32 + 32 + 4 + 4 + 16 + 16 + 16 + 8 + 8 + PositionRewardInfo::LEN * REWARD_NUM + 64;
+    pub const LEN: usize = 8
+        + 1
+        + 32
+        + 32
+        + 4
+        + 4
+        + 16
+        + 16
+        + 16
+        + 8
+        + 8
+        + PositionRewardInfo::LEN * REWARD_NUM
+        + 8
+        + 2 * REWARD_NUM
+        + 2
+        + 48;
 
     pub fn seeds(&self) -> [&[u8]; 3] {
         [
@@ -61,10 +78,17 @@ impl PersonalPositionState {
     pub fn update_rewards(
         &mut self,
         reward_growths_inside: [u128; REWARD_NUM],
+        reward_generations: [u16; REWARD_NUM],
         add_delta: bool,
     ) -> Result<()> {
         for i in 0..REWARD_NUM {
             let reward_growth_inside = reward_growths_inside[i];
+            if self.reward_generations[i] != reward_generations[i] {
+                // The slot is recycled after the claim period of the previous reward, the reward
+                // owed is forfeited and the growth starts over from zero like a new reward.
+                self.reward_infos[i] = PositionRewardInfo::default();
+                self.reward_generations[i] = reward_generations[i];
+            }
             let curr_reward_info = self.reward_infos[i];
 
             if add_delta {
@@ -188,6 +212,41 @@ pub struct DecreaseLiquidityEvent {
     pub transfer_fee_1: u64,
 }
 
+/// Emitted when a position is moved to a new range
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct RepositionEvent {
+    /// The pool of the position
+    pub pool_state: Pubkey,
+    /// The ID of the token for which the position was moved
+    pub position_nft_mint: Pubkey,
+    /// The range and liquidity of the position before moved
+    pub tick_lower_index_before: i32,
+    pub tick_upper_index_before: i32,
+    pub liquidity_before: u128,
+    /// The range and liquidity of the position after moved
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    pub liquidity: u128,
+    /// The amount of token_0 and token_1 withdrawn from the previous range
+    pub decrease_amount_0: u64,
+    pub decrease_amount_1: u64,
+    /// The amount of token_0 and token_1 fee collected
+    pub fee_amount_0: u64,
+    pub fee_amount_1: u64,
+    /// The amount of rewards
+    pub reward_amounts: [u64; REWARD_NUM],
+    /// The amount of token_0 and token_1 deposited into the new range
+    pub deposit_amount_0: u64,
+    pub deposit_amount_1: u64,
+    /// The amount of token_0 and token_1 left over and returned to the owner
+    pub refund_amount_0: u64,
+    pub refund_amount_1: u64,
+    /// The transfer fee of the returned token_0 and token_1
+    pub transfer_fee_0: u64,
+    pub transfer_fee_1: u64,
+}
+
 /// Emitted when liquidity decreased or increase.
 #[event]
 #[cfg_attr(feature = "client", derive(Debug))]
@@ -232,6 +291,17 @@ pub struct CollectPersonalFeeEvent {
     pub amount_1: u64,
 }
 
+/// Emitted when rewards are collected for a position
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct CollectPersonalRewardEvent {
+    /// The ID of the token for which rewards were collected
+    pub position_nft_mint: Pubkey,
+
+    /// The amount of each reward that was collected
+    pub reward_amounts: [u64; REWARD_NUM],
+}
+
 /// Emitted when Reward are updated for a pool
 #[event]
 #[cfg_attr(feature = "client", derive(Debug))]
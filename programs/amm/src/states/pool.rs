@@ -27,12 +27,15 @@ pub mod reward_period_limit {
     pub const MIN_REWARD_PERIOD: u64 = 1 * 60 * 60;
     pub const MAX_REWARD_PERIOD: u64 = 2 * 60 * 60;
     pub const INCREASE_EMISSIONES_PERIOD: u64 = 30 * 60;
+    pub const REWARD_CLAIM_PERIOD: u64 = 1 * 60 * 60;
 }
 #[cfg(not(feature = "paramset"))]
 pub mod reward_period_limit {
     pub const MIN_REWARD_PERIOD: u64 = 7 * 24 * 60 * 60;
     pub const MAX_REWARD_PERIOD: u64 = 90 * 24 * 60 * 60;
     pub const INCREASE_EMISSIONES_PERIOD: u64 = 72 * 60 * 60;
+    /// The period after the reward end for the positions to claim, the slot can be recycled after that
+    pub const REWARD_CLAIM_PERIOD: u64 = 30 * 24 * 60 * 60;
 }
 
 pub enum PoolStatusBitIndex {
@@ -41,6 +44,7 @@ pub enum PoolStatusBitIndex {
     CollectFee,
     CollectReward,
     Swap,
+    Flash,
 }
 
 #[derive(PartialEq, Eq)]
@@ -112,9 +116,12 @@ pub struct PoolState {
     /// bit2, 1: disable collect fee, 0: normal
     /// bit3, 1: disable collect reward, 0: normal
     /// bit4, 1: disable swap, 0: normal
+    /// bit5, 1: disable flash loan, 0: normal
     pub status: u8,
+    /// 1: only the signers allowed by the pool permission account can swap and open positions, 0: normal
+    pub permissioned: u8,
     /// Leave blank for future use
-    pub padding: [u8; 7],
+    pub padding: [u8; 6],
 
     pub reward_infos: [RewardInfo; REWARD_NUM],
 
@@ -131,15 +138,45 @@ pub struct PoolState {
     pub fund_fees_token_0: u64,
     pub fund_fees_token_1: u64,
 
-    // The timestamp allowed for swap in the pool.
-    // Note: The open_time is disabled for now.
+    // The timestamp allowed for swap in the pool, the liquidity can be added before it.
     pub open_time: u64,
     // account recent update epoch
     pub recent_epoch: u64,
 
+    /// Parameters and volatility state of the dynamic fee mode
+    pub dynamic_fee_info: DynamicFeeInfo,
+
+    /// The seconds per unit of liquidity as a Q64.64, i.e. seconds elapsed divided by
+    /// the in range liquidity, accumulated for the entire life of the pool.
+    pub seconds_per_liquidity_global_x64: u128,
+    /// The block timestamp of the last seconds_per_liquidity_global_x64 update
+    pub seconds_per_liquidity_update_time: u64,
+
+    /// The fee rates of the pool overriding the amm config
+    pub fee_override: FeeOverride,
+
+    /// The decaying fee schedule applied right after the open time
+    pub launch_fee: LaunchFeeSchedule,
+
+    /// The generation of each reward slot, increased every time a finished slot is recycled
+    /// so ticks and positions reset the growth recorded for the previous reward lazily
+    pub reward_generations: [u16; REWARD_NUM],
+    pub padding5: u16,
+
+    /// The total amount funded to each reward, the remaining reward is refunded pro-rata to the funded amounts
+    pub reward_total_funded: [u64; REWARD_NUM],
+    /// The amount added by the third-party funders to each reward and not refunded yet
+    pub reward_pending_contributed: [u64; REWARD_NUM],
+    /// The remaining reward of each reward paid out to the funders after the reward ended
+    pub reward_refunded: [u64; REWARD_NUM],
+
     // Unused bytes for future upgrades.
-    pub padding1: [u64; 24],
-    pub padding2: [u64; 32],
+    pub padding1: [u64; 1],
+
+    /// The emission phases of each reward, committed when the reward is initialized
+    pub reward_schedules: [RewardSchedule; REWARD_NUM],
+
+    pub padding2: [u64; 5],
 }
 
 impl PoolState {
@@ -220,7 +257,8 @@ impl PoolState {
         self.swap_in_amount_token_1 = 0;
         self.swap_out_amount_token_0 = 0;
         self.status = 0;
-        self.padding = [0; 7];
+        self.permissioned = 0;
+        self.padding = [0; 6];
         self.tick_array_bitmap = [0; 16];
         self.total_fees_token_0 = 0;
         self.total_fees_claimed_token_0 = 0;
@@ -230,8 +268,19 @@ impl PoolState {
         self.fund_fees_token_1 = 0;
         self.open_time = open_time;
         self.recent_epoch = get_recent_epoch()?;
-        self.padding1 = [0; 24];
-        self.padding2 = [0; 32];
+        self.dynamic_fee_info = DynamicFeeInfo::default();
+        self.seconds_per_liquidity_global_x64 = 0;
+        self.seconds_per_liquidity_update_time = 0;
+        self.fee_override = FeeOverride::default();
+        self.launch_fee = LaunchFeeSchedule::default();
+        self.reward_generations = [0; REWARD_NUM];
+        self.padding5 = 0;
+        self.reward_total_funded = [0; REWARD_NUM];
+        self.reward_pending_contributed = [0; REWARD_NUM];
+        self.reward_refunded = [0; REWARD_NUM];
+        self.padding1 = [0; 1];
+        self.reward_schedules = [RewardSchedule::default(); REWARD_NUM];
+        self.padding2 = [0; 5];
         self.observation_key = observation_state_key;
 
         Ok(())
@@ -242,13 +291,14 @@ impl PoolState {
         open_time: u64,
         end_time: u64,
         reward_per_second_x64: u128,
+        reward_schedule: RewardSchedule,
         token_mint: &Pubkey,
         token_mint_freeze_authority: COption<Pubkey>,
         token_vault: &Pubkey,
         authority: &Pubkey,
         authority_is_reward_manager: bool,
         operation_state: &OperationState,
-    ) -> Result<()> {
+    ) -> Result<usize> {
         let reward_infos = self.reward_infos;
         let lowest_index = match reward_infos.iter().position(|r| !r.initialized()) {
             Some(lowest_index) => lowest_index,
@@ -322,6 +372,7 @@ impl PoolState {
         self.reward_infos[lowest_index].token_mint = *token_mint;
         self.reward_infos[lowest_index].token_vault = *token_vault;
         self.reward_infos[lowest_index].authority = *authority;
+        self.reward_schedules[lowest_index] = reward_schedule;
         #[cfg(feature = "enable-log")]
         msg!(
             "reward_index:{}, reward_infos:{:?}",
@@ -329,7 +380,7 @@ impl PoolState {
             self.reward_infos[lowest_index],
         );
         self.recent_epoch = get_recent_epoch()?;
-        Ok(())
+        Ok(lowest_index)
     }
 
     // Calculates the next global reward growth variables based on the given timestamp.
@@ -352,16 +403,14 @@ impl PoolState {
 
             if self.liquidity != 0 {
                 require_gte!(latest_update_timestamp, reward_info.last_update_time);
-                let time_delta = latest_update_timestamp
-                    .checked_sub(reward_info.last_update_time)
-                    .unwrap();
+                let emissions_x64 = self.reward_schedules[i].get_emissions_x64(
+                    reward_info.emissions_per_second_x64,
+                    reward_info.open_time,
+                    reward_info.last_update_time,
+                    latest_update_timestamp,
+                );
 
-                let reward_growth_delta = U256::from(time_delta)
-                    .mul_div_floor(
-                        U256::from(reward_info.emissions_per_second_x64),
-                        U256::from(self.liquidity),
-                    )
-                    .unwrap();
+                let reward_growth_delta = emissions_x64 / U256::from(self.liquidity);
 
                 reward_info.reward_growth_global_x64 = reward_info
                     .reward_growth_global_x64
@@ -371,22 +420,19 @@ impl PoolState {
                 reward_info.reward_total_emissioned = reward_info
                     .reward_total_emissioned
                     .checked_add(
-                        U128::from(time_delta)
-                            .mul_div_ceil(
-                                U128::from(reward_info.emissions_per_second_x64),
-                                U128::from(fixed_point_64::Q64),
-                            )
+                        emissions_x64
+                            .mul_div_ceil(U256::one(), U256::from(fixed_point_64::Q64))
                             .unwrap()
                             .as_u64(),
                     )
                     .unwrap();
                 #[cfg(feature = "enable-log")]
                 msg!(
-                    "reward_index:{},latest_update_timestamp:{},reward_info.reward_last_update_time:{},time_delta:{},reward_emission_per_second_x64:{},reward_growth_delta:{},reward_info.reward_growth_global_x64:{}, reward_info.reward_claim:{}",
+                    "reward_index:{},latest_update_timestamp:{},reward_info.reward_last_update_time:{},emissions_x64:{},reward_emission_per_second_x64:{},reward_growth_delta:{},reward_info.reward_growth_global_x64:{}, reward_info.reward_claim:{}",
                     i,
                     latest_update_timestamp,
                     identity(reward_info.last_update_time),
-                    time_delta,
+                    emissions_x64,
                     identity(reward_info.emissions_per_second_x64),
                     reward_growth_delta,
                     identity(reward_info.reward_growth_global_x64),
@@ -430,6 +476,77 @@ impl PoolState {
         Ok(())
     }
 
+    /// Resets a finished reward slot so it can be initialized by a new reward, the reward growth
+    /// recorded by the ticks and positions for the previous reward is reset lazily by the generation.
+    pub fn recycle_reward(&mut self, index: usize) {
+        assert!(index < REWARD_NUM);
+        self.reward_infos[index] = RewardInfo::default();
+        self.reward_schedules[index] = RewardSchedule::default();
+        self.reward_total_funded[index] = 0;
+        self.reward_pending_contributed[index] = 0;
+        self.reward_refunded[index] = 0;
+        self.reward_generations[index] = self.reward_generations[index].wrapping_add(1);
+    }
+
+    /// Records the amount added by a third-party funder, the amount funded before the totals
+    /// were tracked is taken from the reward vault.
+    pub fn add_reward_contribution(
+        &mut self,
+        index: usize,
+        amount: u64,
+        reward_vault_amount: u64,
+    ) -> Result<()> {
+        assert!(index < REWARD_NUM);
+        if self.reward_total_funded[index] == 0 {
+            self.reward_total_funded[index] = reward_vault_amount
+                .checked_add(self.reward_infos[index].reward_claimed)
+                .ok_or(ErrorCode::CalculateOverflow)?;
+        }
+        self.reward_total_funded[index] = self.reward_total_funded[index]
+            .checked_add(amount)
+            .ok_or(ErrorCode::CalculateOverflow)?;
+        self.reward_pending_contributed[index] = self.reward_pending_contributed[index]
+            .checked_add(amount)
+            .ok_or(ErrorCode::CalculateOverflow)?;
+        Ok(())
+    }
+
+    /// Resets the funded totals of an ended reward before it restarts, the remaining reward left
+    /// in the vault and not owed to the positions is carried over as funded by the reward authority.
+    pub fn restart_reward_funds(&mut self, index: usize, reward_vault_amount: u64) -> Result<()> {
+        assert!(index < REWARD_NUM);
+        let reward_info = self.reward_infos[index];
+        let amount_owed = reward_info
+            .reward_total_emissioned
+            .checked_sub(reward_info.reward_claimed)
+            .ok_or(ErrorCode::CalculateOverflow)?;
+        self.reward_total_funded[index] = reward_vault_amount
+            .checked_sub(amount_owed)
+            .ok_or(ErrorCode::CalculateOverflow)?;
+        self.reward_refunded[index] = 0;
+        Ok(())
+    }
+
+    /// Get the share of the remaining reward of an ended reward for the funded amount.
+    /// The remaining reward when the reward ended is split pro-rata to the funded amounts,
+    /// `amount_remaining` is the part not paid out to the funders yet.
+    pub fn get_remaining_reward_share(
+        &self,
+        index: usize,
+        amount_remaining: u64,
+        funded_amount: u64,
+    ) -> u64 {
+        assert!(index < REWARD_NUM);
+        let total_funded = self.reward_total_funded[index];
+        if total_funded == 0 {
+            return 0;
+        }
+        let share = (u128::from(amount_remaining) + u128::from(self.reward_refunded[index]))
+            * u128::from(funded_amount)
+            / u128::from(total_funded);
+        (share as u64).min(amount_remaining)
+    }
+
     pub fn get_tick_array_offset(&self, tick_array_start_index: i32) -> Result<usize> {
         require!(
             TickArrayState::check_is_valid_start_index(tick_array_start_index, self.tick_spacing),
@@ -565,12 +682,203 @@ impl PoolState {
         }
     }
 
+    pub fn is_permissioned(&self) -> bool {
+        self.permissioned != 0
+    }
+
     /// Get status by bit, if it is `noraml` status, return true
     pub fn get_status_by_bit(&self, bit: PoolStatusBitIndex) -> bool {
         let status = u8::from(1) << (bit as u8);
         self.status.bitand(status) == 0
     }
 
+    /// Get the base trade fee rate, the rate of the amm config is replaced by the fee override of the pool if enabled.
+    pub fn get_base_fee_rate(&self, config_trade_fee_rate: u32) -> u32 {
+        if self.fee_override.is_enabled() {
+            self.fee_override.trade_fee_rate
+        } else {
+            config_trade_fee_rate
+        }
+    }
+
+    /// Get the trade fee rate applied to a swap at `block_timestamp`, include the launch fee.
+    pub fn get_trade_fee_rate(&self, config_trade_fee_rate: u32, block_timestamp: u64) -> u32 {
+        self.get_trade_fee_rate_at_tick(config_trade_fee_rate, self.tick_current, block_timestamp)
+    }
+
+    /// Get the trade fee rate applied to the step of a swap which moved the price from the current tick to `tick`.
+    pub fn get_trade_fee_rate_at_tick(
+        &self,
+        config_trade_fee_rate: u32,
+        tick: i32,
+        block_timestamp: u64,
+    ) -> u32 {
+        let normal_fee_rate =
+            self.get_normal_fee_rate_at_tick(config_trade_fee_rate, tick, block_timestamp);
+        normal_fee_rate
+            + self
+                .launch_fee
+                .get_excess_fee_rate(normal_fee_rate, self.open_time, block_timestamp)
+    }
+
+    /// Get the part of the trade fee rate at `block_timestamp` above the normal fee rate, charged by the launch fee schedule.
+    pub fn get_launch_fee_rate(&self, config_trade_fee_rate: u32, block_timestamp: u64) -> u32 {
+        self.get_launch_fee_rate_at_tick(config_trade_fee_rate, self.tick_current, block_timestamp)
+    }
+
+    /// Same as `get_launch_fee_rate`, for the step of a swap which moved the price from the current tick to `tick`.
+    pub fn get_launch_fee_rate_at_tick(
+        &self,
+        config_trade_fee_rate: u32,
+        tick: i32,
+        block_timestamp: u64,
+    ) -> u32 {
+        let normal_fee_rate =
+            self.get_normal_fee_rate_at_tick(config_trade_fee_rate, tick, block_timestamp);
+        self.launch_fee
+            .get_excess_fee_rate(normal_fee_rate, self.open_time, block_timestamp)
+    }
+
+    /// Get the trade fee rate without the launch fee.
+    /// If the dynamic fee mode is enabled, the volatility-based fee is added on top of the base fee rate.
+    pub fn get_normal_fee_rate(&self, config_trade_fee_rate: u32, block_timestamp: u64) -> u32 {
+        self.get_normal_fee_rate_at_tick(config_trade_fee_rate, self.tick_current, block_timestamp)
+    }
+
+    /// Same as `get_normal_fee_rate`, the volatility includes the move of the price from the current tick to `tick`.
+    pub fn get_normal_fee_rate_at_tick(
+        &self,
+        config_trade_fee_rate: u32,
+        tick: i32,
+        block_timestamp: u64,
+    ) -> u32 {
+        let base_fee_rate = self.get_base_fee_rate(config_trade_fee_rate);
+        if !self.dynamic_fee_info.is_enabled() {
+            return base_fee_rate;
+        }
+        let volatility_accumulator = self.dynamic_fee_info.get_volatility_accumulator(
+            self.tick_current,
+            tick,
+            self.tick_spacing,
+            block_timestamp,
+        );
+        let dynamic_fee_rate = self
+            .dynamic_fee_info
+            .get_dynamic_fee_rate(volatility_accumulator, self.tick_spacing);
+        base_fee_rate
+            .saturating_add(dynamic_fee_rate)
+            .min(FEE_RATE_DENOMINATOR_VALUE - 1)
+    }
+
+    /// Get the protocol fee rate, the rate of the amm config is replaced by the fee override of the pool if enabled.
+    pub fn get_protocol_fee_rate(&self, config_protocol_fee_rate: u32) -> u32 {
+        if self.fee_override.is_enabled() {
+            self.fee_override.protocol_fee_rate
+        } else {
+            config_protocol_fee_rate
+        }
+    }
+
+    /// Get the fund fee rate, the rate of the amm config is replaced by the fee override of the pool if enabled.
+    pub fn get_fund_fee_rate(&self, config_fund_fee_rate: u32) -> u32 {
+        if self.fee_override.is_enabled() {
+            self.fee_override.fund_fee_rate
+        } else {
+            config_fund_fee_rate
+        }
+    }
+
+    /// Accumulate the tick movement of a swap into the volatility state of the dynamic fee mode.
+    pub fn update_volatility_accumulator(&mut self, tick_after: i32, block_timestamp: u64) {
+        if !self.dynamic_fee_info.is_enabled() {
+            return;
+        }
+        let tick_before = self.tick_current;
+        let tick_spacing = self.tick_spacing;
+        self.dynamic_fee_info
+            .update(tick_before, tick_after, tick_spacing, block_timestamp);
+    }
+
+    /// Get the seconds per liquidity accumulated up to `block_timestamp`, without updating the state.
+    pub fn get_seconds_per_liquidity_global_x64(&self, block_timestamp: u64) -> u128 {
+        let last_update_time = self.seconds_per_liquidity_update_time;
+        if self.liquidity == 0 || last_update_time == 0 || block_timestamp <= last_update_time {
+            return self.seconds_per_liquidity_global_x64;
+        }
+        let time_delta = block_timestamp - last_update_time;
+        self.seconds_per_liquidity_global_x64
+            .wrapping_add(((time_delta as u128) << fixed_point_64::RESOLUTION) / self.liquidity)
+    }
+
+    /// Accumulate the seconds per liquidity with the in range liquidity before it changes.
+    pub fn update_seconds_per_liquidity(&mut self, block_timestamp: u64) {
+        if block_timestamp <= self.seconds_per_liquidity_update_time {
+            return;
+        }
+        self.seconds_per_liquidity_global_x64 =
+            self.get_seconds_per_liquidity_global_x64(block_timestamp);
+        self.seconds_per_liquidity_update_time = block_timestamp;
+    }
+
+    /// Credit the fee paid for a flash loan of token_0 or token_1, the protocol and fund fee are
+    /// split by the pool rates as in swap, the rest is distributed to the in range liquidity.
+    /// If there is no in range liquidity, the rest goes to the protocol fee.
+    pub fn add_flash_fee(&mut self, amm_config: &AmmConfig, fee_amount: u64, is_token_0: bool) {
+        if fee_amount == 0 {
+            return;
+        }
+        let mut protocol_fee = U128::from(fee_amount)
+            .checked_mul(
+                self.get_protocol_fee_rate(amm_config.protocol_fee_rate)
+                    .into(),
+            )
+            .unwrap()
+            .checked_div(FEE_RATE_DENOMINATOR_VALUE.into())
+            .unwrap()
+            .as_u64();
+        let fund_fee = U128::from(fee_amount)
+            .checked_mul(self.get_fund_fee_rate(amm_config.fund_fee_rate).into())
+            .unwrap()
+            .checked_div(FEE_RATE_DENOMINATOR_VALUE.into())
+            .unwrap()
+            .as_u64();
+        let mut lp_fee = fee_amount - protocol_fee - fund_fee;
+        let mut fee_growth_global_x64_delta = 0;
+        if self.liquidity > 0 {
+            fee_growth_global_x64_delta = U128::from(lp_fee)
+                .mul_div_floor(U128::from(fixed_point_64::Q64), U128::from(self.liquidity))
+                .unwrap()
+                .as_u128();
+        } else {
+            protocol_fee += lp_fee;
+            lp_fee = 0;
+        }
+
+        if is_token_0 {
+            self.fee_growth_global_0_x64 = self
+                .fee_growth_global_0_x64
+                .checked_add(fee_growth_global_x64_delta)
+                .unwrap();
+            self.total_fees_token_0 = self.total_fees_token_0.checked_add(lp_fee).unwrap();
+            self.protocol_fees_token_0 = self
+                .protocol_fees_token_0
+                .checked_add(protocol_fee)
+                .unwrap();
+            self.fund_fees_token_0 = self.fund_fees_token_0.checked_add(fund_fee).unwrap();
+        } else {
+            self.fee_growth_global_1_x64 = self
+                .fee_growth_global_1_x64
+                .checked_add(fee_growth_global_x64_delta)
+                .unwrap();
+            self.total_fees_token_1 = self.total_fees_token_1.checked_add(lp_fee).unwrap();
+            self.protocol_fees_token_1 = self
+                .protocol_fees_token_1
+                .checked_add(protocol_fee)
+                .unwrap();
+            self.fund_fees_token_1 = self.fund_fees_token_1.checked_add(fund_fee).unwrap();
+        }
+    }
+
     pub fn is_overflow_default_tickarray_bitmap(&self, tick_indexs: Vec<i32>) -> bool {
         let (min_tick_array_start_index_boundary, max_tick_array_index_boundary) =
             self.tick_array_start_index_range();
@@ -675,6 +983,334 @@ impl RewardInfo {
     }
 }
 
+pub mod dynamic_fee_limit {
+    /// Scale of the volatility accumulator, crossing one tick spacing adds this value
+    pub const VOLATILITY_ACCUMULATOR_SCALE_FACTOR: u32 = 10_000;
+    /// Denominator of `reduction_factor`
+    pub const REDUCTION_FACTOR_DENOMINATOR: u16 = 10_000;
+    /// Denominator of `dynamic_fee_control`
+    pub const DYNAMIC_FEE_CONTROL_DENOMINATOR: u32 = 100_000;
+    /// The max dynamic fee rate added on top of the base fee rate, 10%
+    pub const MAX_DYNAMIC_FEE_RATE: u32 = 100_000;
+}
+
+/// Dynamic fee mode of the pool.
+///
+/// The volatility is measured by how many tick spacings the price moved since the reference tick,
+/// and the dynamic fee rate grows with the square of it. The volatility reference decays by
+/// `reduction_factor` once `filter_period` elapsed, and is reset after `decay_period` without swaps.
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct DynamicFeeInfo {
+    /// Swaps within this period (seconds) share the same references
+    pub filter_period: u16,
+    /// The volatility reference is reset if no swap happened within this period (seconds)
+    pub decay_period: u16,
+    /// Decay of the volatility reference, in units of 1/10000
+    pub reduction_factor: u16,
+    /// Scales the dynamic fee rate, the dynamic fee mode is disabled if it is zero
+    pub dynamic_fee_control: u32,
+    /// Upper bound of the volatility accumulator
+    pub max_volatility_accumulator: u32,
+    /// The tick spacing group index where the current volatility measurement started
+    pub tick_group_index_reference: i32,
+    /// The decayed volatility carried over from previous swaps
+    pub volatility_reference: u32,
+    /// The volatility after the last swap
+    pub volatility_accumulator: u32,
+    /// The block timestamp of the last swap
+    pub last_update_timestamp: u64,
+    /// Leave blank for future use
+    pub padding: [u8; 14],
+}
+
+impl DynamicFeeInfo {
+    pub const LEN: usize = 2 + 2 + 2 + 4 + 4 + 4 + 4 + 4 + 8 + 14;
+
+    pub fn is_enabled(&self) -> bool {
+        self.dynamic_fee_control != 0
+    }
+
+    fn tick_group_index(tick: i32, tick_spacing: u16) -> i32 {
+        tick.div_euclid(i32::from(tick_spacing))
+    }
+
+    /// Returns the tick group index reference and the volatility reference at `block_timestamp`
+    fn get_references(&self, tick_group_index: i32, block_timestamp: u64) -> (i32, u32) {
+        let elapsed = block_timestamp.saturating_sub(self.last_update_timestamp);
+        if elapsed < u64::from(self.filter_period) {
+            (self.tick_group_index_reference, self.volatility_reference)
+        } else if elapsed < u64::from(self.decay_period) {
+            let volatility_reference = u64::from(self.volatility_accumulator)
+                * u64::from(self.reduction_factor)
+                / u64::from(dynamic_fee_limit::REDUCTION_FACTOR_DENOMINATOR);
+            (tick_group_index, volatility_reference as u32)
+        } else {
+            (tick_group_index, 0)
+        }
+    }
+
+    fn compute_volatility_accumulator(
+        &self,
+        tick_group_index_reference: i32,
+        volatility_reference: u32,
+        tick_group_index: i32,
+    ) -> u32 {
+        let delta = u64::from(tick_group_index_reference.abs_diff(tick_group_index));
+        let volatility_accumulator =
+            u64::from(volatility_reference).saturating_add(delta.saturating_mul(u64::from(
+                dynamic_fee_limit::VOLATILITY_ACCUMULATOR_SCALE_FACTOR,
+            )));
+        volatility_accumulator.min(u64::from(self.max_volatility_accumulator)) as u32
+    }
+
+    /// Get the volatility accumulator of a swap starting at `tick_before` and `block_timestamp`,
+    /// after the price moved to `tick`
+    pub fn get_volatility_accumulator(
+        &self,
+        tick_before: i32,
+        tick: i32,
+        tick_spacing: u16,
+        block_timestamp: u64,
+    ) -> u32 {
+        let (tick_group_index_reference, volatility_reference) = self.get_references(
+            Self::tick_group_index(tick_before, tick_spacing),
+            block_timestamp,
+        );
+        self.compute_volatility_accumulator(
+            tick_group_index_reference,
+            volatility_reference,
+            Self::tick_group_index(tick, tick_spacing),
+        )
+    }
+
+    /// Dynamic fee rate = dynamic_fee_control * (volatility_accumulator * tick_spacing)^2,
+    /// scaled by the denominators and capped at `MAX_DYNAMIC_FEE_RATE`.
+    pub fn get_dynamic_fee_rate(&self, volatility_accumulator: u32, tick_spacing: u16) -> u32 {
+        let square_volatility =
+            (u128::from(volatility_accumulator) * u128::from(tick_spacing)).saturating_pow(2);
+        let denominator = u128::from(dynamic_fee_limit::DYNAMIC_FEE_CONTROL_DENOMINATOR)
+            * u128::from(dynamic_fee_limit::VOLATILITY_ACCUMULATOR_SCALE_FACTOR).pow(2);
+        let dynamic_fee_rate = u128::from(self.dynamic_fee_control)
+            .saturating_mul(square_volatility)
+            .div_ceil(denominator);
+        dynamic_fee_rate.min(u128::from(dynamic_fee_limit::MAX_DYNAMIC_FEE_RATE)) as u32
+    }
+
+    /// Update the references and the volatility accumulator after a swap moved the tick from
+    /// `tick_before` to `tick_after`.
+    pub fn update(
+        &mut self,
+        tick_before: i32,
+        tick_after: i32,
+        tick_spacing: u16,
+        block_timestamp: u64,
+    ) {
+        let (tick_group_index_reference, volatility_reference) = self.get_references(
+            Self::tick_group_index(tick_before, tick_spacing),
+            block_timestamp,
+        );
+        self.volatility_accumulator = self.compute_volatility_accumulator(
+            tick_group_index_reference,
+            volatility_reference,
+            Self::tick_group_index(tick_after, tick_spacing),
+        );
+        self.tick_group_index_reference = tick_group_index_reference;
+        self.volatility_reference = volatility_reference;
+        self.last_update_timestamp = block_timestamp;
+    }
+}
+
+/// The fee rates of a pool replacing the rates of its amm config, so the fee of a single pool
+/// can be tuned without moving its liquidity to a pool of another config.
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct FeeOverride {
+    /// 1: the rates below replace the rates of the amm config, 0: the amm config is used
+    pub enabled: u8,
+    /// The trade fee, denominated in hundredths of a bip (10^-6)
+    pub trade_fee_rate: u32,
+    /// The protocol fee, as a share of the trade fee
+    pub protocol_fee_rate: u32,
+    /// The fund fee, as a share of the trade fee
+    pub fund_fee_rate: u32,
+    /// Leave blank for future use
+    pub padding: [u8; 3],
+}
+
+impl FeeOverride {
+    pub const LEN: usize = 1 + 4 + 4 + 4 + 3;
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled != 0
+    }
+}
+
+/// The number of half-lives of the excess fee over the duration of an exponential launch fee schedule
+pub const LAUNCH_FEE_HALF_LIVES: u64 = 8;
+
+/// 2^(-1/2^k) in Q64.64 for k = 1..=16, the decay of the fractional part of a half-life
+const FRACTIONAL_HALF_LIFE_DECAY_X64: [u128; 16] = [
+    0xb504f333f9de6484,
+    0xd744fccad69d6af4,
+    0xeac0c6e7dd24392e,
+    0xf5257d152486cc2c,
+    0xfa83b2db722a033a,
+    0xfd3e0c0cf486c174,
+    0xfe9e115c7b8f884b,
+    0xff4ecb59511ec8a5,
+    0xffa756521c8daed1,
+    0xffd3a751c0f7e10b,
+    0xffe9d2b2f7db2755,
+    0xfff4e91bff1b8c3d,
+    0xfffa747ea0040664,
+    0xfffd3a3b7814eb53,
+    0xfffe9d1cc60ddab1,
+    0xffff4e8e25879bfa,
+];
+
+/// A trade fee decaying from `start_fee_rate` at the open time of the pool to the normal fee rate,
+/// so the bots trading in the first blocks of a launch pay most of their profit as fee.
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct LaunchFeeSchedule {
+    /// The trade fee rate at the open time, denominated in hundredths of a bip (10^-6), 0 disables the schedule
+    pub start_fee_rate: u32,
+    /// The seconds after the open time until the fee is back to the normal fee rate
+    pub duration: u32,
+    /// 0: the excess fee decays linearly, 1: the excess fee decays exponentially with a half-life of
+    /// `duration / LAUNCH_FEE_HALF_LIVES` seconds and drops to zero at the end of the duration
+    pub decay_mode: u8,
+    /// The receiver of the fee above the normal fee rate, 0: liquidity providers, 1: fund fee, 2: protocol fee.
+    /// The excess fee is never split, the liquidity providers get all of it without the protocol and fund share.
+    pub excess_fee_destination: u8,
+    /// Leave blank for future use
+    pub padding: [u8; 6],
+}
+
+impl LaunchFeeSchedule {
+    pub const LEN: usize = 4 + 4 + 1 + 1 + 6;
+
+    pub const DECAY_MODE_LINEAR: u8 = 0;
+    pub const DECAY_MODE_EXPONENTIAL: u8 = 1;
+
+    pub const DESTINATION_LIQUIDITY_PROVIDER: u8 = 0;
+    pub const DESTINATION_FUND_FEE: u8 = 1;
+    pub const DESTINATION_PROTOCOL_FEE: u8 = 2;
+
+    pub fn is_enabled(&self) -> bool {
+        self.start_fee_rate != 0
+    }
+
+    /// Get the fee rate charged above `normal_fee_rate` at `block_timestamp`
+    pub fn get_excess_fee_rate(
+        &self,
+        normal_fee_rate: u32,
+        open_time: u64,
+        block_timestamp: u64,
+    ) -> u32 {
+        if !self.is_enabled() || self.start_fee_rate <= normal_fee_rate {
+            return 0;
+        }
+        let duration = u64::from(self.duration);
+        let elapsed = block_timestamp.saturating_sub(open_time);
+        if elapsed >= duration {
+            return 0;
+        }
+        let start_excess_fee_rate = u64::from(self.start_fee_rate - normal_fee_rate);
+        let excess_fee_rate = if self.decay_mode == Self::DECAY_MODE_EXPONENTIAL {
+            // start_excess_fee_rate * 2^(-elapsed * LAUNCH_FEE_HALF_LIVES / duration),
+            // the whole half-lives are shifted out and the fractional half-life is applied in Q64.64
+            let half_lives = elapsed * LAUNCH_FEE_HALF_LIVES;
+            let fraction_x16 = ((half_lives % duration) << 16) / duration;
+            let mut decay_x64 = 1u128 << 64;
+            for (i, factor_x64) in FRACTIONAL_HALF_LIFE_DECAY_X64.iter().enumerate() {
+                if fraction_x16 & (1 << (15 - i)) != 0 {
+                    decay_x64 = (decay_x64 * factor_x64) >> 64;
+                }
+            }
+            ((u128::from(start_excess_fee_rate) * decay_x64) >> 64) as u64
+                >> (half_lives / duration)
+        } else {
+            start_excess_fee_rate * (duration - elapsed) / duration
+        };
+        excess_fee_rate as u32
+    }
+}
+
+/// The max number of phases of a reward schedule
+pub const REWARD_SCHEDULE_PHASE_NUM: usize = 8;
+
+/// The emission phases of a reward committed up front, such as a front-loaded decay or weekly steps.
+/// The phases run one after another from the open time of the reward and the reward ends with the last phase,
+/// each phase emits the `emissions_per_second_x64` of the reward scaled by the rate of the phase.
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct RewardSchedule {
+    /// The number of phases, 0 if the reward emits at a constant rate
+    pub phase_count: u8,
+    /// Leave blank for future use
+    pub padding: [u8; 7],
+    /// The seconds of each phase
+    pub phase_durations: [u32; REWARD_SCHEDULE_PHASE_NUM],
+    /// The emission rate of each phase relative to the reward emissions, denominated in 10^-6
+    pub phase_rates: [u32; REWARD_SCHEDULE_PHASE_NUM],
+}
+
+impl RewardSchedule {
+    pub const LEN: usize = 1 + 7 + 4 * REWARD_SCHEDULE_PHASE_NUM + 4 * REWARD_SCHEDULE_PHASE_NUM;
+
+    pub const RATE_DENOMINATOR: u64 = 1_000_000;
+
+    pub fn is_enabled(&self) -> bool {
+        self.phase_count != 0
+    }
+
+    /// The seconds from the open time to the end of the last phase
+    pub fn get_duration(&self) -> u64 {
+        let phase_durations = self.phase_durations;
+        phase_durations[..self.phase_count as usize]
+            .iter()
+            .map(|duration| u64::from(*duration))
+            .sum()
+    }
+
+    /// Get the emissions as a Q64.64 between `from` and `to`, integrated across the phases they overlap
+    pub fn get_emissions_x64(
+        &self,
+        emissions_per_second_x64: u128,
+        open_time: u64,
+        from: u64,
+        to: u64,
+    ) -> U256 {
+        if !self.is_enabled() {
+            return U256::from(to.saturating_sub(from)) * U256::from(emissions_per_second_x64);
+        }
+        let mut emissions_x64 = U256::zero();
+        let mut phase_start = open_time;
+        for i in 0..self.phase_count as usize {
+            let phase_end = phase_start + u64::from(self.phase_durations[i]);
+            let start = from.max(phase_start);
+            let end = to.min(phase_end);
+            if start < end {
+                let phase_emissions_per_second_x64 = U256::from(emissions_per_second_x64)
+                    .mul_div_floor(
+                        U256::from(self.phase_rates[i]),
+                        U256::from(Self::RATE_DENOMINATOR),
+                    )
+                    .unwrap();
+                emissions_x64 += U256::from(end - start) * phase_emissions_per_second_x64;
+            }
+            phase_start = phase_end;
+        }
+        emissions_x64
+    }
+}
+
 /// Emitted when a pool is created and initialized with a starting price
 ///
 #[event]
@@ -765,6 +1401,107 @@ pub struct SwapEvent {
 
     /// The log base 1.0001 of price of the pool after the swap
     pub tick: i32,
+
+    /// The trade fee rate at the start of the swap, denominated in hundredths of a bip (10^-6).
+    /// With the dynamic fee mode the rate of every step grows with the volatility of the price moved by the swap.
+    pub fee_rate: u32,
+}
+
+/// A single hop of a routed swap
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq)]
+pub struct SwapRouterHop {
+    /// The pool in which the hop was performed
+    pub pool_state: Pubkey,
+    /// The amount paid by the user token account of the hop input, include the transfer fee
+    pub amount_in: u64,
+    /// The amount received by the user token account of the hop output
+    pub amount_out: u64,
+}
+
+/// Emitted once by when a swap is routed across multiple pools
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct SwapRouterEvent {
+    /// The address that initiated the swap
+    pub sender: Pubkey,
+    /// The mint of the token paid by the first hop
+    pub input_token_mint: Pubkey,
+    /// The mint of the token received by the last hop
+    pub output_token_mint: Pubkey,
+    /// The total amount paid by the user
+    pub amount_in: u64,
+    /// The total amount received by the user
+    pub amount_out: u64,
+    /// if true, the route is exact input, otherwise exact output
+    pub is_base_input: bool,
+    /// The hops of the route, in the order the tokens flow
+    pub hops: Vec<SwapRouterHop>,
+}
+
+/// Emitted when a flash loan is performed for a pool
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct FlashEvent {
+    /// The pool lent the tokens
+    pub pool_state: Pubkey,
+    /// The address that borrowed the tokens
+    pub sender: Pubkey,
+    /// The amount of token_0 and token_1 lent
+    pub amount_0: u64,
+    pub amount_1: u64,
+    /// The amount of token_0 and token_1 paid as fee, greater than or equal to the required fee
+    pub paid_0: u64,
+    pub paid_1: u64,
+}
+
+/// Emitted when the fee override of a pool is changed
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolFeeOverrideChangeEvent {
+    /// The pool whose fee override is changed
+    pub pool_state: Pubkey,
+    pub enabled: bool,
+    pub trade_fee_rate: u32,
+    pub protocol_fee_rate: u32,
+    pub fund_fee_rate: u32,
+}
+
+/// Emitted when the open time of a pool is rescheduled
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolOpenTimeChangeEvent {
+    /// The pool whose open time is changed
+    pub pool_state: Pubkey,
+    pub open_time_before: u64,
+    pub open_time: u64,
+}
+
+/// Emitted when a finished reward slot is recycled
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct RecycleRewardEvent {
+    /// The pool whose reward slot is recycled
+    pub pool_state: Pubkey,
+    pub reward_index: u8,
+    /// The reward mint of the slot before recycled
+    pub reward_mint: Pubkey,
+    /// The amount left in the vault returned to the funder
+    pub amount_remaining: u64,
+    /// The generation of the slot after recycled
+    pub reward_generation: u16,
+}
+
+/// Emitted when the dynamic fee config of a pool is changed
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct DynamicFeeConfigChangeEvent {
+    /// The pool whose dynamic fee config is changed
+    pub pool_state: Pubkey,
+    pub filter_period: u16,
+    pub decay_period: u16,
+    pub reduction_factor: u16,
+    pub dynamic_fee_control: u32,
+    pub max_volatility_accumulator: u32,
 }
 
 /// Emitted pool liquidity change when increase and decrease liquidity
@@ -985,6 +1722,10 @@ pub mod pool_test {
                 pool_state.get_status_by_bit(PoolStatusBitIndex::CollectReward),
                 true
             );
+            assert_eq!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Flash),
+                true
+            );
 
             // disable -> disable, nothing to change
             pool_state.set_status_by_bit(PoolStatusBitIndex::Swap, PoolStatusBitFlag::Disable);
@@ -1018,6 +1759,51 @@ pub mod pool_test {
         }
     }
 
+    mod add_flash_fee_test {
+        use super::*;
+        use std::convert::identity;
+
+        #[test]
+        fn add_flash_fee_split_test() {
+            let amm_config = AmmConfig {
+                protocol_fee_rate: 120000,
+                fund_fee_rate: 40000,
+                ..Default::default()
+            };
+            let mut pool_state = PoolState {
+                liquidity: 1 << 64,
+                ..Default::default()
+            };
+            pool_state.add_flash_fee(&amm_config, 10000, true);
+            assert_eq!(identity(pool_state.protocol_fees_token_0), 1200);
+            assert_eq!(identity(pool_state.fund_fees_token_0), 400);
+            assert_eq!(identity(pool_state.total_fees_token_0), 8400);
+            assert_eq!(identity(pool_state.fee_growth_global_0_x64), 8400);
+            assert_eq!(identity(pool_state.total_fees_token_1), 0);
+
+            pool_state.add_flash_fee(&amm_config, 10000, false);
+            assert_eq!(identity(pool_state.protocol_fees_token_1), 1200);
+            assert_eq!(identity(pool_state.fund_fees_token_1), 400);
+            assert_eq!(identity(pool_state.total_fees_token_1), 8400);
+            assert_eq!(identity(pool_state.fee_growth_global_1_x64), 8400);
+        }
+
+        #[test]
+        fn add_flash_fee_without_liquidity_test() {
+            let amm_config = AmmConfig {
+                protocol_fee_rate: 120000,
+                fund_fee_rate: 40000,
+                ..Default::default()
+            };
+            let mut pool_state = PoolState::default();
+            pool_state.add_flash_fee(&amm_config, 10000, true);
+            assert_eq!(identity(pool_state.protocol_fees_token_0), 9600);
+            assert_eq!(identity(pool_state.fund_fees_token_0), 400);
+            assert_eq!(identity(pool_state.total_fees_token_0), 0);
+            assert_eq!(identity(pool_state.fee_growth_global_0_x64), 0);
+        }
+    }
+
     mod update_reward_infos_test {
         use super::*;
         use anchor_lang::prelude::Pubkey;
@@ -1037,6 +1823,7 @@ pub mod pool_test {
                     1665982800,
                     1666069200,
                     10,
+                    RewardSchedule::default(),
                     &Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap(),
                     COption::None,
                     &Pubkey::default(),
@@ -1075,6 +1862,429 @@ pub mod pool_test {
                 1666069200
             );
         }
+
+        #[test]
+        fn reward_schedule_test() {
+            let pool_state = &mut PoolState::default();
+            let operation_state = OperationState {
+                bump: 0,
+                operation_owners: [Pubkey::default(); OPERATION_SIZE_USIZE],
+                whitelist_mints: [Pubkey::default(); WHITE_MINT_SIZE_USIZE],
+            };
+            // full rate for 100s, half rate for 100s, then a tenth for 100s
+            let reward_schedule = RewardSchedule {
+                phase_count: 3,
+                phase_durations: [100, 100, 100, 0, 0, 0, 0, 0],
+                phase_rates: [1_000_000, 500_000, 100_000, 0, 0, 0, 0, 0],
+                ..Default::default()
+            };
+            assert_eq!(reward_schedule.get_duration(), 300);
+
+            let emissions_per_second_x64 = 100 * fixed_point_64::Q64;
+            pool_state
+                .initialize_reward(
+                    1000,
+                    1300,
+                    emissions_per_second_x64,
+                    reward_schedule,
+                    &Pubkey::new_unique(),
+                    COption::None,
+                    &Pubkey::default(),
+                    &Pubkey::default(),
+                    false,
+                    &operation_state,
+                )
+                .unwrap();
+            assert_eq!(
+                reward_schedule
+                    .get_emissions_x64(emissions_per_second_x64, 1000, 1000, 1300)
+                    .mul_div_ceil(U256::one(), U256::from(fixed_point_64::Q64))
+                    .unwrap()
+                    .as_u64(),
+                16000
+            );
+
+            pool_state.liquidity = 100;
+            // 50s of the first phase
+            let updated_reward_infos = pool_state.update_reward_infos(1050).unwrap();
+            assert_eq!(
+                identity(updated_reward_infos[0].reward_total_emissioned),
+                5000
+            );
+            assert_eq!(
+                identity(updated_reward_infos[0].reward_growth_global_x64),
+                50 * fixed_point_64::Q64
+            );
+            // 50s of the first phase and 100s of the second phase
+            let updated_reward_infos = pool_state.update_reward_infos(1200).unwrap();
+            assert_eq!(
+                identity(updated_reward_infos[0].reward_total_emissioned),
+                15000
+            );
+            assert_eq!(
+                identity(updated_reward_infos[0].reward_growth_global_x64),
+                150 * fixed_point_64::Q64
+            );
+            // the last phase ends with the reward
+            let updated_reward_infos = pool_state.update_reward_infos(1400).unwrap();
+            assert_eq!(
+                identity(updated_reward_infos[0].reward_total_emissioned),
+                16000
+            );
+            assert_eq!(identity(updated_reward_infos[0].last_update_time), 1300);
+
+            pool_state.recycle_reward(0);
+            assert_eq!(pool_state.reward_schedules[0], RewardSchedule::default());
+        }
+
+        #[test]
+        fn remaining_reward_share_test() {
+            let pool_state = &mut PoolState::default();
+            // 600 funded by the reward authority, 100 of it claimed by the positions
+            pool_state.reward_infos[0].reward_claimed = 100;
+            pool_state.add_reward_contribution(0, 300, 500).unwrap();
+            pool_state.add_reward_contribution(0, 100, 800).unwrap();
+            assert_eq!(identity(pool_state.reward_total_funded[0]), 1000);
+            assert_eq!(identity(pool_state.reward_pending_contributed[0]), 400);
+
+            // 200 left when the reward ended, the authority collects first
+            let reserved = pool_state.get_remaining_reward_share(0, 200, 400);
+            assert_eq!(reserved, 80);
+            pool_state.reward_refunded[0] = 200 - reserved;
+            // the funders are refunded their shares of the 200 afterwards
+            let share = pool_state.get_remaining_reward_share(0, reserved, 300);
+            assert_eq!(share, 60);
+            pool_state.reward_refunded[0] += share;
+            assert_eq!(
+                pool_state.get_remaining_reward_share(0, reserved - share, 100),
+                20
+            );
+        }
+
+        #[test]
+        fn restarted_reward_share_test() {
+            let pool_state = &mut PoolState::default();
+            // the first round, 100 funded by the reward authority and 100 by a funder
+            pool_state.add_reward_contribution(0, 100, 100).unwrap();
+            // 150 emitted and claimed, 50 left when the reward ended
+            pool_state.reward_infos[0].reward_total_emissioned = 150;
+            pool_state.reward_infos[0].reward_claimed = 150;
+            let reserved = pool_state.get_remaining_reward_share(0, 50, 100);
+            assert_eq!(reserved, 25);
+            pool_state.reward_refunded[0] = 50 - reserved;
+            let share = pool_state.get_remaining_reward_share(0, reserved, 100);
+            assert_eq!(share, 25);
+            pool_state.reward_refunded[0] += share;
+            pool_state.reward_pending_contributed[0] -= 100;
+
+            // the second round, nothing left in the vault, 100 funded by the reward authority
+            // and 100 by another funder
+            pool_state.restart_reward_funds(0, 0).unwrap();
+            assert_eq!(identity(pool_state.reward_total_funded[0]), 0);
+            assert_eq!(identity(pool_state.reward_refunded[0]), 0);
+            pool_state.reward_total_funded[0] += 100;
+            pool_state.add_reward_contribution(0, 100, 100).unwrap();
+            assert_eq!(identity(pool_state.reward_total_funded[0]), 200);
+
+            // 120 emitted and claimed, 80 left when the reward ended
+            pool_state.reward_infos[0].reward_total_emissioned = 270;
+            pool_state.reward_infos[0].reward_claimed = 270;
+            let reserved = pool_state.get_remaining_reward_share(0, 80, 100);
+            assert_eq!(reserved, 40);
+            pool_state.reward_refunded[0] = 80 - reserved;
+            assert_eq!(pool_state.get_remaining_reward_share(0, reserved, 100), 40);
+
+            // the remaining reward not collected is carried over to the next round
+            pool_state.restart_reward_funds(0, 80).unwrap();
+            assert_eq!(identity(pool_state.reward_total_funded[0]), 80);
+            assert_eq!(identity(pool_state.reward_refunded[0]), 0);
+        }
+
+        #[test]
+        fn recycle_reward_test() {
+            let pool_state = &mut PoolState::default();
+            let operation_state = OperationState {
+                bump: 0,
+                operation_owners: [Pubkey::default(); OPERATION_SIZE_USIZE],
+                whitelist_mints: [Pubkey::default(); WHITE_MINT_SIZE_USIZE],
+            };
+            let reward_mints: Vec<Pubkey> =
+                (0..REWARD_NUM + 1).map(|_| Pubkey::new_unique()).collect();
+            for reward_mint in reward_mints.iter().take(REWARD_NUM) {
+                pool_state
+                    .initialize_reward(
+                        1665982800,
+                        1666069200,
+                        10,
+                        RewardSchedule::default(),
+                        reward_mint,
+                        COption::None,
+                        &Pubkey::default(),
+                        &Pubkey::default(),
+                        true,
+                        &operation_state,
+                    )
+                    .unwrap();
+            }
+            pool_state.liquidity = 100;
+            pool_state.update_reward_infos(1666069300).unwrap();
+            assert!(pool_state
+                .initialize_reward(
+                    1666069300,
+                    1666155700,
+                    10,
+                    RewardSchedule::default(),
+                    &reward_mints[REWARD_NUM],
+                    COption::None,
+                    &Pubkey::default(),
+                    &Pubkey::default(),
+                    true,
+                    &operation_state,
+                )
+                .is_err());
+
+            pool_state.recycle_reward(1);
+            assert_eq!(pool_state.reward_infos[1], RewardInfo::default());
+            assert_eq!(identity(pool_state.reward_generations), [0, 1, 0]);
+
+            // the recycled slot is taken by the next reward
+            pool_state
+                .initialize_reward(
+                    1666069300,
+                    1666155700,
+                    10,
+                    RewardSchedule::default(),
+                    &reward_mints[REWARD_NUM],
+                    COption::None,
+                    &Pubkey::default(),
+                    &Pubkey::default(),
+                    true,
+                    &operation_state,
+                )
+                .unwrap();
+            assert_eq!(
+                identity(pool_state.reward_infos[1].token_mint),
+                reward_mints[REWARD_NUM]
+            );
+            assert_eq!(
+                identity(pool_state.reward_infos[1].reward_growth_global_x64),
+                0
+            );
+        }
+    }
+
+    mod dynamic_fee_test {
+        use super::*;
+
+        fn build_dynamic_fee_pool(tick_spacing: u16) -> PoolState {
+            let mut pool_state = PoolState::default();
+            pool_state.tick_spacing = tick_spacing;
+            pool_state.dynamic_fee_info = DynamicFeeInfo {
+                filter_period: 30,
+                decay_period: 600,
+                reduction_factor: 5000,
+                dynamic_fee_control: 40000,
+                max_volatility_accumulator: 350000,
+                ..Default::default()
+            };
+            pool_state
+        }
+
+        #[test]
+        fn disabled_dynamic_fee_test() {
+            let mut pool_state = PoolState::default();
+            pool_state.tick_spacing = 10;
+            pool_state.update_volatility_accumulator(1000, 1000);
+            assert_eq!(pool_state.dynamic_fee_info, DynamicFeeInfo::default());
+            assert_eq!(pool_state.get_trade_fee_rate(500, 1000), 500);
+        }
+
+        #[test]
+        fn dynamic_fee_rise_and_decay_test() {
+            let mut pool_state = build_dynamic_fee_pool(10);
+            // no volatility yet
+            assert_eq!(pool_state.get_trade_fee_rate(500, 1000), 500);
+
+            // the price moves 5 tick spacings
+            pool_state.update_volatility_accumulator(50, 1000);
+            pool_state.tick_current = 50;
+            let volatility_accumulator = pool_state.dynamic_fee_info.volatility_accumulator;
+            assert_eq!(volatility_accumulator, 50000);
+
+            // within filter period, the reference tick is kept
+            assert_eq!(pool_state.get_trade_fee_rate(500, 1010), 500 + 1000);
+            // within decay period, the volatility reference is reduced by half
+            assert_eq!(pool_state.get_trade_fee_rate(500, 1100), 500 + 250);
+            // after decay period, the fee goes back to base fee
+            assert_eq!(pool_state.get_trade_fee_rate(500, 1600), 500);
+
+            // movement in the opposite direction within filter period still accumulates from the reference
+            pool_state.update_volatility_accumulator(-50, 1010);
+            pool_state.tick_current = -50;
+            let volatility_accumulator = pool_state.dynamic_fee_info.volatility_accumulator;
+            assert_eq!(volatility_accumulator, 50000);
+        }
+
+        #[test]
+        fn dynamic_fee_rate_at_tick_test() {
+            let mut pool_state = build_dynamic_fee_pool(10);
+            assert_eq!(pool_state.get_trade_fee_rate_at_tick(500, 0, 1000), 500);
+            // the steps of a swap pay the dynamic fee of the price moved so far
+            assert_eq!(
+                pool_state.get_trade_fee_rate_at_tick(500, 50, 1000),
+                500 + 1000
+            );
+            assert_eq!(
+                pool_state.get_trade_fee_rate_at_tick(500, -50, 1000),
+                500 + 1000
+            );
+
+            // the volatility after the swap is the one of its last step
+            pool_state.update_volatility_accumulator(50, 1000);
+            pool_state.tick_current = 50;
+            assert_eq!(pool_state.get_trade_fee_rate(500, 1000), 500 + 1000);
+            // the next swap in the filter period accumulates from the same reference
+            assert_eq!(
+                pool_state.get_trade_fee_rate_at_tick(500, 100, 1010),
+                500 + 4000
+            );
+        }
+
+        #[test]
+        fn dynamic_fee_capped_test() {
+            let mut pool_state = build_dynamic_fee_pool(60);
+            pool_state.update_volatility_accumulator(60 * 1000, 1000);
+            pool_state.tick_current = 60 * 1000;
+            let volatility_accumulator = pool_state.dynamic_fee_info.volatility_accumulator;
+            assert_eq!(volatility_accumulator, 350000);
+            assert_eq!(
+                pool_state.get_trade_fee_rate(500, 1010),
+                500 + dynamic_fee_limit::MAX_DYNAMIC_FEE_RATE
+            );
+        }
+    }
+
+    mod fee_override_test {
+        use super::*;
+        use std::convert::identity;
+
+        fn build_fee_override_pool() -> PoolState {
+            PoolState {
+                tick_spacing: 10,
+                liquidity: 1 << 64,
+                fee_override: FeeOverride {
+                    enabled: 1,
+                    trade_fee_rate: 3000,
+                    protocol_fee_rate: 200000,
+                    fund_fee_rate: 0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn fee_override_rates_test() {
+            let mut pool_state = build_fee_override_pool();
+            assert_eq!(pool_state.get_base_fee_rate(500), 3000);
+            assert_eq!(pool_state.get_trade_fee_rate(500, 1000), 3000);
+            assert_eq!(pool_state.get_protocol_fee_rate(120000), 200000);
+            assert_eq!(pool_state.get_fund_fee_rate(40000), 0);
+
+            pool_state.fee_override.enabled = 0;
+            assert_eq!(pool_state.get_trade_fee_rate(500, 1000), 500);
+            assert_eq!(pool_state.get_protocol_fee_rate(120000), 120000);
+            assert_eq!(pool_state.get_fund_fee_rate(40000), 40000);
+        }
+
+        #[test]
+        fn fee_override_with_dynamic_fee_test() {
+            let mut pool_state = build_fee_override_pool();
+            pool_state.dynamic_fee_info = DynamicFeeInfo {
+                filter_period: 30,
+                decay_period: 600,
+                reduction_factor: 5000,
+                dynamic_fee_control: 40000,
+                max_volatility_accumulator: 350000,
+                ..Default::default()
+            };
+            pool_state.update_volatility_accumulator(50, 1000);
+            pool_state.tick_current = 50;
+            // the dynamic fee is added on top of the overridden base fee
+            assert_eq!(pool_state.get_trade_fee_rate(500, 1010), 3000 + 1000);
+        }
+
+        #[test]
+        fn fee_override_flash_fee_test() {
+            let amm_config = AmmConfig {
+                protocol_fee_rate: 120000,
+                fund_fee_rate: 40000,
+                ..Default::default()
+            };
+            let mut pool_state = build_fee_override_pool();
+            pool_state.add_flash_fee(&amm_config, 10000, true);
+            assert_eq!(identity(pool_state.protocol_fees_token_0), 2000);
+            assert_eq!(identity(pool_state.fund_fees_token_0), 0);
+            assert_eq!(identity(pool_state.total_fees_token_0), 8000);
+        }
+    }
+
+    mod launch_fee_test {
+        use super::*;
+
+        fn build_launch_fee_pool(decay_mode: u8) -> PoolState {
+            PoolState {
+                tick_spacing: 10,
+                open_time: 1000,
+                launch_fee: LaunchFeeSchedule {
+                    start_fee_rate: 500000,
+                    duration: 800,
+                    decay_mode,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn linear_launch_fee_test() {
+            let pool_state = build_launch_fee_pool(LaunchFeeSchedule::DECAY_MODE_LINEAR);
+            assert_eq!(pool_state.get_trade_fee_rate(2500, 1000), 500000);
+            assert_eq!(pool_state.get_launch_fee_rate(2500, 1000), 497500);
+            assert_eq!(pool_state.get_trade_fee_rate(2500, 1200), 2500 + 373125);
+            assert_eq!(pool_state.get_trade_fee_rate(2500, 1600), 2500 + 124375);
+            assert_eq!(pool_state.get_trade_fee_rate(2500, 1800), 2500);
+            assert_eq!(pool_state.get_launch_fee_rate(2500, 1800), 0);
+        }
+
+        #[test]
+        fn exponential_launch_fee_test() {
+            let pool_state = build_launch_fee_pool(LaunchFeeSchedule::DECAY_MODE_EXPONENTIAL);
+            assert_eq!(pool_state.get_trade_fee_rate(2500, 1000), 500000);
+            // halved every 100 seconds, decaying smoothly within a half-life
+            assert_eq!(pool_state.get_trade_fee_rate(2500, 1099), 2500 + 250481);
+            assert_eq!(pool_state.get_trade_fee_rate(2500, 1100), 2500 + 248750);
+            assert_eq!(pool_state.get_trade_fee_rate(2500, 1150), 2500 + 175892);
+            assert_eq!(pool_state.get_trade_fee_rate(2500, 1350), 2500 + 43973);
+            assert_eq!(pool_state.get_trade_fee_rate(2500, 1799), 2500 + 1956);
+            assert_eq!(pool_state.get_trade_fee_rate(2500, 1800), 2500);
+            // never increases over the duration
+            let mut last_fee_rate = pool_state.get_trade_fee_rate(2500, 1000);
+            for block_timestamp in 1001..=1800 {
+                let fee_rate = pool_state.get_trade_fee_rate(2500, block_timestamp);
+                assert!(fee_rate <= last_fee_rate);
+                last_fee_rate = fee_rate;
+            }
+        }
+
+        #[test]
+        fn launch_fee_below_normal_fee_test() {
+            let mut pool_state = build_launch_fee_pool(LaunchFeeSchedule::DECAY_MODE_LINEAR);
+            assert_eq!(pool_state.get_trade_fee_rate(600000, 1000), 600000);
+            assert_eq!(pool_state.get_launch_fee_rate(600000, 1000), 0);
+            pool_state.launch_fee = LaunchFeeSchedule::default();
+            assert_eq!(pool_state.get_trade_fee_rate(2500, 1000), 2500);
+        }
     }
 
     mod use_tickarray_bitmap_extension_test {
@@ -1611,7 +2821,8 @@ pub mod pool_test {
             let swap_in_amount_token_1: u128 = 0x11223344556677008899aabbccddeeff;
             let swap_out_amount_token_0: u128 = 0x11223344556677880099aabbccddeeff;
             let status: u8 = 0x1b;
-            let padding: [u8; 7] = [0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18];
+            let permissioned: u8 = 0x01;
+            let padding: [u8; 6] = [0x13, 0x14, 0x15, 0x16, 0x17, 0x18];
             // RewardInfo
             let reward_state: u8 = 0x1c;
             let open_time: u64 = 0x123456789abc0def;
@@ -1682,20 +2893,174 @@ pub mod pool_test {
             let fund_fees_token_1: u64 = 0x1230456789abcdef;
             let pool_open_time: u64 = 0x1203456789abcdef;
             let recent_epoch: u64 = 0x1023456789abcdef;
-            let mut padding1: [u64; 24] = [0u64; 24];
-            let mut padding1_data = [0u8; 8 * 24];
+            // DynamicFeeInfo
+            let filter_period: u16 = 0x1d1e;
+            let decay_period: u16 = 0x1f20;
+            let reduction_factor: u16 = 0x2122;
+            let dynamic_fee_control: u32 = 0x12345678;
+            let max_volatility_accumulator: u32 = 0x12345687;
+            let tick_group_index_reference: i32 = -0x12345876;
+            let volatility_reference: u32 = 0x12348765;
+            let volatility_accumulator: u32 = 0x12387654;
+            let last_update_timestamp: u64 = 0x123456789abcdfe0;
+            let dynamic_fee_padding: [u8; 14] = [0x23; 14];
+            let mut dynamic_fee_info_data = [0u8; DynamicFeeInfo::LEN];
+            let mut offset = 0;
+            dynamic_fee_info_data[offset..offset + 2].copy_from_slice(&filter_period.to_le_bytes());
+            offset += 2;
+            dynamic_fee_info_data[offset..offset + 2].copy_from_slice(&decay_period.to_le_bytes());
+            offset += 2;
+            dynamic_fee_info_data[offset..offset + 2]
+                .copy_from_slice(&reduction_factor.to_le_bytes());
+            offset += 2;
+            dynamic_fee_info_data[offset..offset + 4]
+                .copy_from_slice(&dynamic_fee_control.to_le_bytes());
+            offset += 4;
+            dynamic_fee_info_data[offset..offset + 4]
+                .copy_from_slice(&max_volatility_accumulator.to_le_bytes());
+            offset += 4;
+            dynamic_fee_info_data[offset..offset + 4]
+                .copy_from_slice(&tick_group_index_reference.to_le_bytes());
+            offset += 4;
+            dynamic_fee_info_data[offset..offset + 4]
+                .copy_from_slice(&volatility_reference.to_le_bytes());
+            offset += 4;
+            dynamic_fee_info_data[offset..offset + 4]
+                .copy_from_slice(&volatility_accumulator.to_le_bytes());
+            offset += 4;
+            dynamic_fee_info_data[offset..offset + 8]
+                .copy_from_slice(&last_update_timestamp.to_le_bytes());
+            offset += 8;
+            dynamic_fee_info_data[offset..offset + 14].copy_from_slice(&dynamic_fee_padding);
+            offset += 14;
+            assert_eq!(offset, core::mem::size_of::<DynamicFeeInfo>());
+
+            let seconds_per_liquidity_global_x64: u128 = 0x11223344556677889900aabbccddeeff;
+            let seconds_per_liquidity_update_time: u64 = 0x123456789abcde0f;
+            // FeeOverride
+            let fee_override_enabled: u8 = 0x01;
+            let fee_override_trade_fee_rate: u32 = 0x2a2b2c2d;
+            let fee_override_protocol_fee_rate: u32 = 0x2e2f3031;
+            let fee_override_fund_fee_rate: u32 = 0x32333435;
+            let fee_override_padding: [u8; 3] = [0x36; 3];
+            let mut fee_override_data = [0u8; FeeOverride::LEN];
             let mut offset = 0;
-            for i in 0..24 {
+            fee_override_data[offset] = fee_override_enabled;
+            offset += 1;
+            fee_override_data[offset..offset + 4]
+                .copy_from_slice(&fee_override_trade_fee_rate.to_le_bytes());
+            offset += 4;
+            fee_override_data[offset..offset + 4]
+                .copy_from_slice(&fee_override_protocol_fee_rate.to_le_bytes());
+            offset += 4;
+            fee_override_data[offset..offset + 4]
+                .copy_from_slice(&fee_override_fund_fee_rate.to_le_bytes());
+            offset += 4;
+            fee_override_data[offset..offset + 3].copy_from_slice(&fee_override_padding);
+            offset += 3;
+            assert_eq!(offset, core::mem::size_of::<FeeOverride>());
+            // LaunchFeeSchedule
+            let launch_fee_start_fee_rate: u32 = 0x37383940;
+            let launch_fee_duration: u32 = 0x41424344;
+            let launch_fee_decay_mode: u8 = 0x01;
+            let launch_fee_excess_fee_destination: u8 = 0x02;
+            let launch_fee_padding: [u8; 6] = [0x45; 6];
+            let mut launch_fee_data = [0u8; LaunchFeeSchedule::LEN];
+            let mut offset = 0;
+            launch_fee_data[offset..offset + 4]
+                .copy_from_slice(&launch_fee_start_fee_rate.to_le_bytes());
+            offset += 4;
+            launch_fee_data[offset..offset + 4].copy_from_slice(&launch_fee_duration.to_le_bytes());
+            offset += 4;
+            launch_fee_data[offset] = launch_fee_decay_mode;
+            offset += 1;
+            launch_fee_data[offset] = launch_fee_excess_fee_destination;
+            offset += 1;
+            launch_fee_data[offset..offset + 6].copy_from_slice(&launch_fee_padding);
+            offset += 6;
+            assert_eq!(offset, core::mem::size_of::<LaunchFeeSchedule>());
+
+            let mut reward_generations: [u16; REWARD_NUM] = [0u16; REWARD_NUM];
+            let mut reward_generations_data = [0u8; 2 * REWARD_NUM];
+            let mut offset = 0;
+            for i in 0..REWARD_NUM {
+                reward_generations[i] = u16::MAX - i as u16;
+                reward_generations_data[offset..offset + 2]
+                    .copy_from_slice(&reward_generations[i].to_le_bytes());
+                offset += 2;
+            }
+            let padding5: u16 = 0x4647;
+
+            let mut reward_total_funded: [u64; REWARD_NUM] = [0u64; REWARD_NUM];
+            let mut reward_pending_contributed: [u64; REWARD_NUM] = [0u64; REWARD_NUM];
+            let mut reward_refunded: [u64; REWARD_NUM] = [0u64; REWARD_NUM];
+            let mut reward_funding_data = [0u8; 8 * 3 * REWARD_NUM];
+            let mut offset = 0;
+            for i in 0..REWARD_NUM {
+                reward_total_funded[i] = 0x7071727374757677 + i as u64;
+                reward_funding_data[offset..offset + 8]
+                    .copy_from_slice(&reward_total_funded[i].to_le_bytes());
+                offset += 8;
+            }
+            for i in 0..REWARD_NUM {
+                reward_pending_contributed[i] = 0x7879808182838485 + i as u64;
+                reward_funding_data[offset..offset + 8]
+                    .copy_from_slice(&reward_pending_contributed[i].to_le_bytes());
+                offset += 8;
+            }
+            for i in 0..REWARD_NUM {
+                reward_refunded[i] = 0x8687888990919293 + i as u64;
+                reward_funding_data[offset..offset + 8]
+                    .copy_from_slice(&reward_refunded[i].to_le_bytes());
+                offset += 8;
+            }
+
+            let mut padding1: [u64; 1] = [0u64; 1];
+            let mut padding1_data = [0u8; 8];
+            let mut offset = 0;
+            for i in 0..1 {
                 padding1[i] = u64::MAX - i as u64;
                 padding1_data[offset..offset + 8].copy_from_slice(&padding1[i].to_le_bytes());
                 offset += 8;
             }
-            let mut padding2: [u64; 32] = [0u64; 32];
-            let mut padding2_data = [0u8; 8 * 32];
+            // RewardSchedule
+            let mut reward_schedules = [RewardSchedule::default(); REWARD_NUM];
+            let mut reward_schedules_data = [0u8; RewardSchedule::LEN * REWARD_NUM];
             let mut offset = 0;
-            for i in 24..(24 + 32) {
-                padding2[i - 24] = u64::MAX - i as u64;
-                padding2_data[offset..offset + 8].copy_from_slice(&padding2[i - 24].to_le_bytes());
+            for i in 0..REWARD_NUM {
+                let mut phase_durations = [0u32; REWARD_SCHEDULE_PHASE_NUM];
+                let mut phase_rates = [0u32; REWARD_SCHEDULE_PHASE_NUM];
+                reward_schedules_data[offset] = i as u8 + 1;
+                offset += 1;
+                reward_schedules_data[offset..offset + 7].copy_from_slice(&[0x48 + i as u8; 7]);
+                offset += 7;
+                for j in 0..REWARD_SCHEDULE_PHASE_NUM {
+                    phase_durations[j] = 0x50515253 + (i * REWARD_SCHEDULE_PHASE_NUM + j) as u32;
+                    reward_schedules_data[offset..offset + 4]
+                        .copy_from_slice(&phase_durations[j].to_le_bytes());
+                    offset += 4;
+                }
+                for j in 0..REWARD_SCHEDULE_PHASE_NUM {
+                    phase_rates[j] = 0x60616263 + (i * REWARD_SCHEDULE_PHASE_NUM + j) as u32;
+                    reward_schedules_data[offset..offset + 4]
+                        .copy_from_slice(&phase_rates[j].to_le_bytes());
+                    offset += 4;
+                }
+                reward_schedules[i] = RewardSchedule {
+                    phase_count: i as u8 + 1,
+                    padding: [0x48 + i as u8; 7],
+                    phase_durations,
+                    phase_rates,
+                };
+            }
+            assert_eq!(offset, core::mem::size_of::<RewardSchedule>() * REWARD_NUM);
+
+            let mut padding2: [u64; 5] = [0u64; 5];
+            let mut padding2_data = [0u8; 8 * 5];
+            let mut offset = 0;
+            for i in 18..(18 + 5) {
+                padding2[i - 18] = u64::MAX - i as u64;
+                padding2_data[offset..offset + 8].copy_from_slice(&padding2[i - 18].to_le_bytes());
                 offset += 8;
             }
             // serialize original data
@@ -1753,8 +3118,10 @@ pub mod pool_test {
             offset += 16;
             pool_data[offset..offset + 1].copy_from_slice(&status.to_le_bytes());
             offset += 1;
-            pool_data[offset..offset + 7].copy_from_slice(&padding);
-            offset += 7;
+            pool_data[offset..offset + 1].copy_from_slice(&permissioned.to_le_bytes());
+            offset += 1;
+            pool_data[offset..offset + 6].copy_from_slice(&padding);
+            offset += 6;
             pool_data[offset..offset + RewardInfo::LEN * REWARD_NUM]
                 .copy_from_slice(&reward_info_datas);
             offset += RewardInfo::LEN * REWARD_NUM;
@@ -1778,10 +3145,31 @@ pub mod pool_test {
             offset += 8;
             pool_data[offset..offset + 8].copy_from_slice(&recent_epoch.to_le_bytes());
             offset += 8;
-            pool_data[offset..offset + 8 * 24].copy_from_slice(&padding1_data);
-            offset += 8 * 24;
-            pool_data[offset..offset + 8 * 32].copy_from_slice(&padding2_data);
-            offset += 8 * 32;
+            pool_data[offset..offset + DynamicFeeInfo::LEN].copy_from_slice(&dynamic_fee_info_data);
+            offset += DynamicFeeInfo::LEN;
+            pool_data[offset..offset + 16]
+                .copy_from_slice(&seconds_per_liquidity_global_x64.to_le_bytes());
+            offset += 16;
+            pool_data[offset..offset + 8]
+                .copy_from_slice(&seconds_per_liquidity_update_time.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + FeeOverride::LEN].copy_from_slice(&fee_override_data);
+            offset += FeeOverride::LEN;
+            pool_data[offset..offset + LaunchFeeSchedule::LEN].copy_from_slice(&launch_fee_data);
+            offset += LaunchFeeSchedule::LEN;
+            pool_data[offset..offset + 2 * REWARD_NUM].copy_from_slice(&reward_generations_data);
+            offset += 2 * REWARD_NUM;
+            pool_data[offset..offset + 2].copy_from_slice(&padding5.to_le_bytes());
+            offset += 2;
+            pool_data[offset..offset + 8 * 3 * REWARD_NUM].copy_from_slice(&reward_funding_data);
+            offset += 8 * 3 * REWARD_NUM;
+            pool_data[offset..offset + 8].copy_from_slice(&padding1_data);
+            offset += 8;
+            pool_data[offset..offset + RewardSchedule::LEN * REWARD_NUM]
+                .copy_from_slice(&reward_schedules_data);
+            offset += RewardSchedule::LEN * REWARD_NUM;
+            pool_data[offset..offset + 8 * 5].copy_from_slice(&padding2_data);
+            offset += 8 * 5;
 
             // len check
             assert_eq!(offset, pool_data.len());
@@ -1842,6 +3230,8 @@ pub mod pool_test {
             assert_eq!(unpack_swap_out_amount_token_0, swap_out_amount_token_0);
             let unpack_status = unpack_data.status;
             assert_eq!(unpack_status, status);
+            let unpack_permissioned = unpack_data.permissioned;
+            assert_eq!(unpack_permissioned, permissioned);
             let unpack_padding = unpack_data.padding;
             assert_eq!(unpack_padding, padding);
 
@@ -1892,8 +3282,97 @@ pub mod pool_test {
             assert_eq!(unpack_open_time, pool_open_time);
             let unpack_recent_epoch = unpack_data.recent_epoch;
             assert_eq!(unpack_recent_epoch, recent_epoch);
+            let unpack_dynamic_fee_info = unpack_data.dynamic_fee_info;
+            let unpack_filter_period = unpack_dynamic_fee_info.filter_period;
+            assert_eq!(unpack_filter_period, filter_period);
+            let unpack_decay_period = unpack_dynamic_fee_info.decay_period;
+            assert_eq!(unpack_decay_period, decay_period);
+            let unpack_reduction_factor = unpack_dynamic_fee_info.reduction_factor;
+            assert_eq!(unpack_reduction_factor, reduction_factor);
+            let unpack_dynamic_fee_control = unpack_dynamic_fee_info.dynamic_fee_control;
+            assert_eq!(unpack_dynamic_fee_control, dynamic_fee_control);
+            let unpack_max_volatility_accumulator =
+                unpack_dynamic_fee_info.max_volatility_accumulator;
+            assert_eq!(
+                unpack_max_volatility_accumulator,
+                max_volatility_accumulator
+            );
+            let unpack_tick_group_index_reference =
+                unpack_dynamic_fee_info.tick_group_index_reference;
+            assert_eq!(
+                unpack_tick_group_index_reference,
+                tick_group_index_reference
+            );
+            let unpack_volatility_reference = unpack_dynamic_fee_info.volatility_reference;
+            assert_eq!(unpack_volatility_reference, volatility_reference);
+            let unpack_volatility_accumulator = unpack_dynamic_fee_info.volatility_accumulator;
+            assert_eq!(unpack_volatility_accumulator, volatility_accumulator);
+            let unpack_last_update_timestamp = unpack_dynamic_fee_info.last_update_timestamp;
+            assert_eq!(unpack_last_update_timestamp, last_update_timestamp);
+            let unpack_dynamic_fee_padding = unpack_dynamic_fee_info.padding;
+            assert_eq!(unpack_dynamic_fee_padding, dynamic_fee_padding);
+            let unpack_seconds_per_liquidity_global_x64 =
+                unpack_data.seconds_per_liquidity_global_x64;
+            assert_eq!(
+                unpack_seconds_per_liquidity_global_x64,
+                seconds_per_liquidity_global_x64
+            );
+            let unpack_seconds_per_liquidity_update_time =
+                unpack_data.seconds_per_liquidity_update_time;
+            assert_eq!(
+                unpack_seconds_per_liquidity_update_time,
+                seconds_per_liquidity_update_time
+            );
+            let unpack_fee_override = unpack_data.fee_override;
+            let unpack_fee_override_enabled = unpack_fee_override.enabled;
+            assert_eq!(unpack_fee_override_enabled, fee_override_enabled);
+            let unpack_fee_override_trade_fee_rate = unpack_fee_override.trade_fee_rate;
+            assert_eq!(
+                unpack_fee_override_trade_fee_rate,
+                fee_override_trade_fee_rate
+            );
+            let unpack_fee_override_protocol_fee_rate = unpack_fee_override.protocol_fee_rate;
+            assert_eq!(
+                unpack_fee_override_protocol_fee_rate,
+                fee_override_protocol_fee_rate
+            );
+            let unpack_fee_override_fund_fee_rate = unpack_fee_override.fund_fee_rate;
+            assert_eq!(
+                unpack_fee_override_fund_fee_rate,
+                fee_override_fund_fee_rate
+            );
+            let unpack_fee_override_padding = unpack_fee_override.padding;
+            assert_eq!(unpack_fee_override_padding, fee_override_padding);
+            let unpack_launch_fee = unpack_data.launch_fee;
+            let unpack_launch_fee_start_fee_rate = unpack_launch_fee.start_fee_rate;
+            assert_eq!(unpack_launch_fee_start_fee_rate, launch_fee_start_fee_rate);
+            let unpack_launch_fee_duration = unpack_launch_fee.duration;
+            assert_eq!(unpack_launch_fee_duration, launch_fee_duration);
+            assert_eq!(unpack_launch_fee.decay_mode, launch_fee_decay_mode);
+            assert_eq!(
+                unpack_launch_fee.excess_fee_destination,
+                launch_fee_excess_fee_destination
+            );
+            assert_eq!(unpack_launch_fee.padding, launch_fee_padding);
+            let unpack_reward_generations = unpack_data.reward_generations;
+            assert_eq!(unpack_reward_generations, reward_generations);
+            let unpack_padding5 = unpack_data.padding5;
+            assert_eq!(unpack_padding5, padding5);
+            let unpack_reward_total_funded = unpack_data.reward_total_funded;
+            assert_eq!(unpack_reward_total_funded, reward_total_funded);
+            let unpack_reward_pending_contributed = unpack_data.reward_pending_contributed;
+            assert_eq!(
+                unpack_reward_pending_contributed,
+                reward_pending_contributed
+            );
+            let unpack_reward_refunded = unpack_data.reward_refunded;
+            assert_eq!(unpack_reward_refunded, reward_refunded);
             let unpack_padding1 = unpack_data.padding1;
             assert_eq!(unpack_padding1, padding1);
+            for i in 0..REWARD_NUM {
+                let unpack_reward_schedule = unpack_data.reward_schedules[i];
+                assert_eq!(unpack_reward_schedule, reward_schedules[i]);
+            }
             let unpack_padding2 = unpack_data.padding2;
             assert_eq!(unpack_padding2, padding2);
         }
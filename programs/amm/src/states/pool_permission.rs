@@ -0,0 +1,236 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_interface::TokenAccount;
+use std::collections::HashSet;
+
+pub const POOL_PERMISSION_SEED: &str = "pool_permission";
+pub const ALLOW_LIST_SIZE_USIZE: usize = 100;
+
+/// The signers allowed to swap and open positions in a permissioned pool
+#[account(zero_copy(unsafe))]
+#[repr(C, packed)]
+#[derive(Debug)]
+pub struct PoolPermissionState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the permission belongs to
+    pub pool_id: Pubkey,
+    /// The holders of a non zero balance of the gating mint are allowed as well,
+    /// default pubkey if the pool has no gating token
+    pub gating_mint: Pubkey,
+    /// The signers allowed by the permission manager
+    pub allow_list: [Pubkey; ALLOW_LIST_SIZE_USIZE],
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 16],
+}
+
+impl PoolPermissionState {
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 32 * ALLOW_LIST_SIZE_USIZE + 8 * 16;
+
+    pub fn key(pool_id: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[POOL_PERMISSION_SEED.as_bytes(), pool_id.as_ref()],
+            &crate::id(),
+        )
+        .0
+    }
+
+    pub fn initialize(&mut self, bump: u8, pool_id: Pubkey, gating_mint: Pubkey) {
+        self.bump = bump;
+        self.pool_id = pool_id;
+        self.gating_mint = gating_mint;
+        self.allow_list = [Pubkey::default(); ALLOW_LIST_SIZE_USIZE];
+        self.padding = [0; 16];
+    }
+
+    pub fn is_allowed(&self, signer: &Pubkey) -> bool {
+        *signer != Pubkey::default() && self.allow_list.contains(signer)
+    }
+
+    pub fn add_allow_list(&mut self, keys: Vec<Pubkey>) -> Result<()> {
+        let mut allow_list = self.allow_list.to_vec();
+        allow_list.extend(keys.iter());
+        allow_list.retain(|&item| item != Pubkey::default());
+        let allow_set: HashSet<Pubkey> = HashSet::from_iter(allow_list.iter().cloned());
+        let mut updated_list: Vec<Pubkey> = allow_set.into_iter().collect();
+        require_gte!(
+            ALLOW_LIST_SIZE_USIZE,
+            updated_list.len(),
+            ErrorCode::AllowListFull
+        );
+        updated_list.sort();
+        // clear
+        self.allow_list = [Pubkey::default(); ALLOW_LIST_SIZE_USIZE];
+        // update
+        self.allow_list[0..updated_list.len()].copy_from_slice(updated_list.as_slice());
+        Ok(())
+    }
+
+    pub fn remove_allow_list(&mut self, keys: Vec<Pubkey>) {
+        let mut allow_list = self.allow_list.to_vec();
+        allow_list.retain(|x| *x != Pubkey::default() && !keys.contains(x));
+        // clear
+        self.allow_list = [Pubkey::default(); ALLOW_LIST_SIZE_USIZE];
+        // update
+        self.allow_list[0..allow_list.len()].copy_from_slice(allow_list.as_slice());
+    }
+
+    /// Whether the account is a token account of the gating mint owned by the signer with a non zero balance
+    fn is_gating_token_account(&self, account_info: &AccountInfo, signer: &Pubkey) -> bool {
+        let gating_mint = self.gating_mint;
+        if gating_mint == Pubkey::default()
+            || (*account_info.owner != spl_token::ID && *account_info.owner != spl_token_2022::ID)
+        {
+            return false;
+        }
+        let Ok(data) = account_info.try_borrow_data() else {
+            return false;
+        };
+        TokenAccount::try_deserialize(&mut data.as_ref()).is_ok_and(|token_account| {
+            token_account.mint == gating_mint
+                && token_account.owner == *signer
+                && token_account.amount > 0
+        })
+    }
+}
+
+/// Whether the remaining account is one of the accounts used by `check_pool_permission`,
+/// the pool permission account or the gating token account proving the holding of the gating token
+pub fn is_pool_permission_account(
+    account_info: &AccountInfo,
+    pool_permission_accounts: &[Pubkey],
+) -> bool {
+    pool_permission_accounts.contains(account_info.key)
+}
+
+/// Checks the user is allowed to trade or provide liquidity in the pool if the pool is permissioned.
+/// The pool permission account and the gating token account of the user are found in the remaining
+/// accounts by key regardless the sequence.
+/// Returns the keys of the remaining accounts used by the check, empty if the pool is not permissioned.
+pub fn check_pool_permission<'info>(
+    permissioned: bool,
+    pool_id: Pubkey,
+    user: &Pubkey,
+    remaining_accounts: &'info [AccountInfo<'info>],
+) -> Result<Vec<Pubkey>> {
+    if !permissioned {
+        return Ok(Vec::new());
+    }
+    let pool_permission_key = PoolPermissionState::key(pool_id);
+    let pool_permission_info = remaining_accounts
+        .iter()
+        .find(|account_info| account_info.key() == pool_permission_key)
+        .ok_or(ErrorCode::MissingPoolPermissionAccount)?;
+    let pool_permission_loader =
+        AccountLoader::<PoolPermissionState>::try_from(pool_permission_info)?;
+    let pool_permission = pool_permission_loader.load()?;
+    if pool_permission.is_allowed(user) {
+        return Ok(vec![pool_permission_key]);
+    }
+    match remaining_accounts
+        .iter()
+        .find(|account_info| pool_permission.is_gating_token_account(account_info, user))
+    {
+        Some(gating_token_account) => Ok(vec![pool_permission_key, gating_token_account.key()]),
+        None => err!(ErrorCode::SignerNotAllowed),
+    }
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolPermissionChangeEvent {
+    pub pool_state: Pubkey,
+    pub permissioned: bool,
+    pub gating_mint: Pubkey,
+    pub allow_list_len: u32,
+}
+
+#[cfg(test)]
+mod pool_permission_test {
+    use super::*;
+
+    fn new_pool_permission() -> PoolPermissionState {
+        PoolPermissionState {
+            bump: 0,
+            pool_id: Pubkey::new_unique(),
+            gating_mint: Pubkey::default(),
+            allow_list: [Pubkey::default(); ALLOW_LIST_SIZE_USIZE],
+            padding: [0; 16],
+        }
+    }
+
+    #[test]
+    fn add_and_remove_allow_list_test() {
+        let mut pool_permission = new_pool_permission();
+        let keys: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        pool_permission.add_allow_list(keys.clone()).unwrap();
+        // repeat keys are added once
+        pool_permission.add_allow_list(vec![keys[0]]).unwrap();
+        assert!(keys.iter().all(|key| pool_permission.is_allowed(key)));
+        assert_eq!(
+            pool_permission
+                .allow_list
+                .iter()
+                .filter(|key| **key != Pubkey::default())
+                .count(),
+            3
+        );
+        assert!(!pool_permission.is_allowed(&Pubkey::new_unique()));
+        assert!(!pool_permission.is_allowed(&Pubkey::default()));
+
+        pool_permission.remove_allow_list(vec![keys[1]]);
+        assert!(pool_permission.is_allowed(&keys[0]));
+        assert!(!pool_permission.is_allowed(&keys[1]));
+        assert!(pool_permission.is_allowed(&keys[2]));
+    }
+
+    #[test]
+    fn add_allow_list_overflow_test() {
+        let mut pool_permission = new_pool_permission();
+        let keys: Vec<Pubkey> = (0..ALLOW_LIST_SIZE_USIZE)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+        pool_permission.add_allow_list(keys).unwrap();
+        assert!(pool_permission
+            .add_allow_list(vec![Pubkey::new_unique()])
+            .is_err());
+    }
+
+    #[test]
+    fn is_pool_permission_account_test() {
+        let pool_permission_key = PoolPermissionState::key(Pubkey::new_unique());
+        let token_account_key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = vec![0u8; 165];
+        // a token account is only skipped if it's used by the permission check
+        let token_account_info = AccountInfo::new(
+            &token_account_key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &spl_token::ID,
+            false,
+            0,
+        );
+        assert!(!is_pool_permission_account(
+            &token_account_info,
+            &[pool_permission_key]
+        ));
+        assert!(is_pool_permission_account(
+            &token_account_info,
+            &[pool_permission_key, token_account_key]
+        ));
+        assert!(!is_pool_permission_account(&token_account_info, &[]));
+    }
+
+    #[test]
+    fn pool_permission_layout_test() {
+        assert_eq!(
+            core::mem::size_of::<PoolPermissionState>() + 8,
+            PoolPermissionState::LEN
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+
+pub const REWARD_FUNDER_SEED: &str = "reward_funder";
+
+/// The amount a third-party funder added to a reward, the funder is refunded its share
+/// of the remaining reward after the reward ended
+#[account]
+#[derive(Default, Debug)]
+pub struct RewardFunderState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the reward belongs to
+    pub pool_state: Pubkey,
+    /// The index of the reward
+    pub reward_index: u8,
+    /// The generation of the reward slot the amount was added to
+    pub reward_generation: u16,
+    /// The address who added the reward funds
+    pub funder: Pubkey,
+    /// The token account the remaining reward is refunded to
+    pub funder_token_account: Pubkey,
+    /// The amount added and not refunded yet
+    pub contributed_amount: u64,
+    // Unused bytes for future upgrades.
+    pub padding: [u64; 4],
+}
+
+impl RewardFunderState {
+    pub const LEN: usize = 8 + 1 + 32 + 1 + 2 + 32 + 32 + 8 + 8 * 4;
+
+    /// Resets the record when it is created or the reward slot was recycled
+    pub fn initialize_if_needed(
+        &mut self,
+        bump: u8,
+        pool_state: Pubkey,
+        reward_index: u8,
+        reward_generation: u16,
+        funder: Pubkey,
+    ) {
+        if self.pool_state == Pubkey::default() || self.reward_generation != reward_generation {
+            self.bump = bump;
+            self.pool_state = pool_state;
+            self.reward_index = reward_index;
+            self.reward_generation = reward_generation;
+            self.funder = funder;
+            self.contributed_amount = 0;
+        }
+    }
+}
+
+/// Emitted when a third-party funder adds funds to a reward
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct AddRewardFundsEvent {
+    pub pool_state: Pubkey,
+    pub reward_index: u8,
+    pub funder: Pubkey,
+    /// The amount received by the reward vault
+    pub amount: u64,
+    /// The end time of the reward after the extension
+    pub end_time: u64,
+    /// The amount added by the funder and not refunded yet
+    pub contributed_amount: u64,
+}
+
+/// Emitted when the share of the remaining reward is refunded to a third-party funder
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct RefundRewardFundsEvent {
+    pub pool_state: Pubkey,
+    pub reward_index: u8,
+    pub funder: Pubkey,
+    pub contributed_amount: u64,
+    pub amount_refunded: u64,
+}
@@ -1,10 +1,12 @@
 use super::pool::PoolState;
+use super::LimitOrderFill;
 use crate::error::ErrorCode;
 use crate::libraries::{liquidity_math, tick_math};
 use crate::pool::{RewardInfo, REWARD_NUM};
 use crate::util::*;
 use crate::Result;
 use anchor_lang::{prelude::*, system_program};
+use std::cell::RefMut;
 #[cfg(feature = "enable-log")]
 use std::convert::identity;
 
@@ -40,6 +42,15 @@ impl TickArrayState {
         )
         .0
     }
+
+    /// Whether the account is a tick array account of the program
+    pub fn is_tick_array(account_info: &AccountInfo) -> bool {
+        account_info.owner == &crate::id()
+            && account_info
+                .try_borrow_data()
+                .is_ok_and(|data| data.starts_with(TickArrayState::DISCRIMINATOR))
+    }
+
     /// Load a TickArrayState of type AccountLoader from tickarray account info, if tickarray account does not exist, then create it.
     pub fn get_or_create_tick_array<'info>(
         payer: AccountInfo<'info>,
@@ -130,6 +141,11 @@ impl TickArrayState {
         Ok(&mut self.ticks[offset_in_array])
     }
 
+    pub fn get_tick_state(&self, tick_index: i32, tick_spacing: u16) -> Result<&TickState> {
+        let offset_in_array = self.get_tick_offset_in_array(tick_index, tick_spacing)?;
+        Ok(&self.ticks[offset_in_array])
+    }
+
     pub fn update_tick_state(
         &mut self,
         tick_index: i32,
@@ -281,8 +297,21 @@ pub struct TickState {
 
     // Reward growth per unit of liquidity like fee, array of Q64.64
     pub reward_growths_outside_x64: [u128; REWARD_NUM],
+
+    /// Seconds per unit of liquidity on the _other_ side of this tick, as a Q64.64
+    /// only has relative meaning like fee growth outside
+    pub seconds_per_liquidity_outside_x64: u128,
+    /// Seconds spent on the _other_ side of this tick, only has relative meaning
+    pub seconds_outside: u64,
+
+    /// The liquidity of the unfilled limit orders which are filled when this tick is crossed
+    pub order_liquidity: u128,
+    /// Increased every time the limit orders of this tick are filled
+    pub order_fill_epoch: u32,
+    /// The pool reward generations the reward growths outside are recorded for
+    pub reward_generations: [u16; REWARD_NUM],
     // Unused bytes for future upgrades.
-    pub padding: [u32; 13],
+    pub padding: [u8; 2],
 }
 
 impl TickState {
@@ -308,6 +337,8 @@ impl TickState {
         fee_growth_global_1_x64: u128,
         upper: bool,
         reward_infos: &[RewardInfo; REWARD_NUM],
+        seconds_per_liquidity_global_x64: u128,
+        block_timestamp: u64,
     ) -> Result<bool> {
         let liquidity_gross_before = self.liquidity_gross;
         let liquidity_gross_after =
@@ -322,6 +353,8 @@ impl TickState {
                 self.fee_growth_outside_0_x64 = fee_growth_global_0_x64;
                 self.fee_growth_outside_1_x64 = fee_growth_global_1_x64;
                 self.reward_growths_outside_x64 = RewardInfo::get_reward_growths(reward_infos);
+                self.seconds_per_liquidity_outside_x64 = seconds_per_liquidity_global_x64;
+                self.seconds_outside = block_timestamp;
             }
         }
 
@@ -345,6 +378,8 @@ impl TickState {
         fee_growth_global_0_x64: u128,
         fee_growth_global_1_x64: u128,
         reward_infos: &[RewardInfo; REWARD_NUM],
+        seconds_per_liquidity_global_x64: u128,
+        block_timestamp: u64,
     ) -> i128 {
         self.fee_growth_outside_0_x64 = fee_growth_global_0_x64
             .checked_sub(self.fee_growth_outside_0_x64)
@@ -364,15 +399,75 @@ impl TickState {
                 .unwrap();
         }
 
+        self.seconds_per_liquidity_outside_x64 =
+            seconds_per_liquidity_global_x64.wrapping_sub(self.seconds_per_liquidity_outside_x64);
+        self.seconds_outside = block_timestamp.wrapping_sub(self.seconds_outside);
+
         self.liquidity_net
     }
 
+    /// Resets the reward growth outside of the recycled reward slots, must be called with the
+    /// pool reward generations before the tick is updated, crossed or read.
+    pub fn sync_reward_generations(&mut self, reward_generations: [u16; REWARD_NUM]) {
+        for i in 0..REWARD_NUM {
+            if self.reward_generations[i] != reward_generations[i] {
+                self.reward_growths_outside_x64[i] = 0;
+                self.reward_generations[i] = reward_generations[i];
+            }
+        }
+    }
+
     pub fn clear(&mut self) {
         self.liquidity_net = 0;
         self.liquidity_gross = 0;
         self.fee_growth_outside_0_x64 = 0;
         self.fee_growth_outside_1_x64 = 0;
         self.reward_growths_outside_x64 = [0; REWARD_NUM];
+        self.seconds_per_liquidity_outside_x64 = 0;
+        self.seconds_outside = 0;
+        self.order_liquidity = 0;
+        self.order_fill_epoch = 0;
+    }
+
+    /// Fills the limit orders of this tick when it is crossed by a swap, the order liquidity is
+    /// removed from liquidity_net so it won't be added back if the price reverts.
+    /// Returns the filled order liquidity, which must be removed from the other tick of the orders too.
+    pub fn fill_orders(&mut self, zero_for_one: bool) -> Result<u128> {
+        let order_liquidity = self.order_liquidity;
+        if order_liquidity == 0 {
+            return Ok(0);
+        }
+        // this is the lower tick of the orders when the price moves down, otherwise the upper tick
+        self.remove_order_liquidity_net(!zero_for_one, order_liquidity)?;
+        self.order_liquidity = 0;
+        self.order_fill_epoch = self.order_fill_epoch.wrapping_add(1);
+        Ok(order_liquidity)
+    }
+
+    /// Removes the liquidity of filled limit orders from liquidity_net, the liquidity_gross is kept
+    /// until the orders are closed by their owners.
+    pub fn remove_order_liquidity_net(&mut self, upper: bool, order_liquidity: u128) -> Result<()> {
+        let liquidity_delta = i128::try_from(order_liquidity).unwrap();
+        self.liquidity_net = if upper {
+            self.liquidity_net.checked_add(liquidity_delta)
+        } else {
+            self.liquidity_net.checked_sub(liquidity_delta)
+        }
+        .ok_or(ErrorCode::CalculateOverflow)?;
+        Ok(())
+    }
+
+    /// Releases the liquidity_gross of a filled limit order, returns true if the tick was flipped to uninitialized
+    pub fn release_filled_order(&mut self, order_liquidity: u128) -> Result<bool> {
+        self.liquidity_gross = self
+            .liquidity_gross
+            .checked_sub(order_liquidity)
+            .ok_or(ErrorCode::CalculateOverflow)?;
+        let flipped = self.liquidity_gross == 0;
+        if flipped {
+            self.clear();
+        }
+        Ok(flipped)
     }
 
     pub fn is_initialized(self) -> bool {
@@ -386,6 +481,23 @@ impl TickState {
     }
 }
 
+/// Loads the mutable tick array with the limit order fills stored after the account struct
+pub fn load_tick_array_mut<'a>(
+    account_info: &'a AccountInfo,
+) -> Result<(RefMut<'a, TickArrayState>, RefMut<'a, [LimitOrderFill]>)> {
+    // check the owner, the discriminator and the account is writable
+    drop(AccountLoad::<TickArrayState>::load_data_mut(account_info)?);
+    let data = account_info.try_borrow_mut_data()?;
+    let (state, extension) = RefMut::map_split(data, |data| data.split_at_mut(TickArrayState::LEN));
+    Ok((
+        RefMut::map(state, |state| bytemuck::from_bytes_mut(&mut state[8..])),
+        RefMut::map(extension, |extension| {
+            let len = extension.len() / LimitOrderFill::LEN * LimitOrderFill::LEN;
+            bytemuck::cast_slice_mut(&mut extension[..len])
+        }),
+    ))
+}
+
 // Calculates the fee growths inside of tick_lower and tick_upper based on their positions relative to tick_current.
 /// `fee_growth_inside = fee_growth_global - fee_growth_below(lower) - fee_growth_above(upper)`
 ///
@@ -488,6 +600,45 @@ pub fn get_reward_growths_inside(
     reward_growths_inside
 }
 
+/// Calculates the seconds per liquidity and the seconds spent inside of tick_lower and tick_upper,
+/// the values only have relative meaning and should be compared between two snapshots of the same range.
+pub fn get_seconds_inside(
+    tick_lower: &TickState,
+    tick_upper: &TickState,
+    tick_current: i32,
+    seconds_per_liquidity_global_x64: u128,
+    block_timestamp: u64,
+) -> (u128, u64) {
+    if tick_current < tick_lower.tick {
+        (
+            tick_lower
+                .seconds_per_liquidity_outside_x64
+                .wrapping_sub(tick_upper.seconds_per_liquidity_outside_x64),
+            tick_lower
+                .seconds_outside
+                .wrapping_sub(tick_upper.seconds_outside),
+        )
+    } else if tick_current < tick_upper.tick {
+        (
+            seconds_per_liquidity_global_x64
+                .wrapping_sub(tick_lower.seconds_per_liquidity_outside_x64)
+                .wrapping_sub(tick_upper.seconds_per_liquidity_outside_x64),
+            block_timestamp
+                .wrapping_sub(tick_lower.seconds_outside)
+                .wrapping_sub(tick_upper.seconds_outside),
+        )
+    } else {
+        (
+            tick_upper
+                .seconds_per_liquidity_outside_x64
+                .wrapping_sub(tick_lower.seconds_per_liquidity_outside_x64),
+            tick_upper
+                .seconds_outside
+                .wrapping_sub(tick_lower.seconds_outside),
+        )
+    }
+}
+
 pub fn check_tick_array_start_index(
     tick_array_start_index: i32,
     tick_index: i32,
@@ -823,12 +974,16 @@ pub mod tick_array_test {
                     fee_growth_global_0_x64,
                     fee_growth_global_1_x64,
                     &[RewardInfo::default(); 3],
+                    0,
+                    0,
                 );
             } else {
                 tick_upper.cross(
                     fee_growth_global_0_x64,
                     fee_growth_global_1_x64,
                     &[RewardInfo::default(); 3],
+                    0,
+                    0,
                 );
             }
 
@@ -1127,6 +1282,7 @@ pub mod tick_array_test {
             tick_array::{get_reward_growths_inside, TickState},
         };
         use anchor_lang::prelude::Pubkey;
+        use std::convert::identity;
 
         fn build_reward_infos(reward_growth_global_x64: u128) -> [RewardInfo; 3] {
             [
@@ -1159,9 +1315,9 @@ pub mod tick_array_test {
 
             reward_growth_global_x64 = reward_growth_global_x64 + reward_growth_global_delta;
             if cross_tick_lower {
-                tick_lower.cross(0, 0, &build_reward_infos(reward_growth_global_x64));
+                tick_lower.cross(0, 0, &build_reward_infos(reward_growth_global_x64), 0, 0);
             } else {
-                tick_upper.cross(0, 0, &build_reward_infos(reward_growth_global_x64));
+                tick_upper.cross(0, 0, &build_reward_infos(reward_growth_global_x64), 0, 0);
             }
 
             tick_current = target_tick_current;
@@ -1203,6 +1359,49 @@ pub mod tick_array_test {
             assert_eq!(reward_inside, [0; 3]);
         }
 
+        #[test]
+        fn recycled_reward_index_test() {
+            let tick_current = 0;
+            // the ticks recorded a large growth outside for the previous reward of the slot
+            let tick_lower = &mut TickState {
+                tick: -10,
+                reward_growths_outside_x64: [5000, 0, 0],
+                ..Default::default()
+            };
+            let tick_upper = &mut TickState {
+                tick: 10,
+                reward_growths_outside_x64: [6000, 0, 0],
+                ..Default::default()
+            };
+            let reward_generations = [1, 0, 0];
+            tick_lower.sync_reward_generations(reward_generations);
+            tick_upper.sync_reward_generations(reward_generations);
+            assert_eq!(identity(tick_lower.reward_growths_outside_x64), [0; 3]);
+            assert_eq!(identity(tick_upper.reward_growths_outside_x64), [0; 3]);
+            assert_eq!(identity(tick_lower.reward_generations), reward_generations);
+
+            // the growth of the new reward is below the old growth outside, crossing must not underflow
+            tick_upper.cross(0, 0, &build_reward_infos(1000), 0, 0);
+            assert_eq!(
+                identity(tick_upper.reward_growths_outside_x64),
+                [1000, 0, 0]
+            );
+            let reward_inside = get_reward_growths_inside(
+                tick_lower,
+                tick_upper,
+                tick_current,
+                &build_reward_infos(1500),
+            );
+            assert_eq!(reward_inside[0], 1500u128.wrapping_sub(1000));
+
+            // synced ticks are not reset again
+            tick_upper.sync_reward_generations(reward_generations);
+            assert_eq!(
+                identity(tick_upper.reward_growths_outside_x64),
+                [1000, 0, 0]
+            );
+        }
+
         #[test]
         fn price_in_tick_range_move_to_right_test() {
             // tick_lower and tick_upper all new create
@@ -1307,6 +1506,81 @@ pub mod tick_array_test {
             assert_eq!(reward_frowth_inside_delta, 500);
         }
     }
+    mod get_seconds_inside_test {
+        use super::*;
+        use crate::states::tick_array::{get_seconds_inside, TickState};
+
+        #[test]
+        fn seconds_inside_only_accumulate_while_price_in_range() {
+            let global_x64: u128 = 100 << 64;
+            let tick_lower = &mut TickState::default();
+            tick_lower.tick = -10;
+            let tick_upper = &mut TickState::default();
+            tick_upper.tick = 10;
+            // both ticks initialized while tick_current = 0, only tick_lower is below the price
+            tick_lower.seconds_per_liquidity_outside_x64 = global_x64;
+            tick_lower.seconds_outside = 1000;
+
+            let (spl_before, seconds_before) =
+                get_seconds_inside(tick_lower, tick_upper, 0, global_x64, 1000);
+
+            // 60 seconds in range, then price moves above tick_upper
+            let global_x64 = global_x64 + (6 << 64);
+            tick_upper.cross(0, 0, &[RewardInfo::default(); 3], global_x64, 1060);
+            let (spl_after_cross, seconds_after_cross) =
+                get_seconds_inside(tick_lower, tick_upper, 11, global_x64, 1060);
+            assert_eq!(spl_after_cross.wrapping_sub(spl_before), 6 << 64);
+            assert_eq!(seconds_after_cross.wrapping_sub(seconds_before), 60);
+
+            // another 100 seconds out of range, inside values are unchanged
+            let global_x64 = global_x64 + (10 << 64);
+            let (spl_later, seconds_later) =
+                get_seconds_inside(tick_lower, tick_upper, 11, global_x64, 1160);
+            assert_eq!(spl_later, spl_after_cross);
+            assert_eq!(seconds_later, seconds_after_cross);
+        }
+    }
+
+    mod limit_order_fill_test {
+        use super::*;
+        use std::convert::identity;
+
+        #[test]
+        fn filled_order_liquidity_is_not_added_back_test() {
+            let order_liquidity: u128 = 1000;
+            let lp_liquidity: u128 = 500;
+            // an order sells token_0 in [0, 10], and a position in [0, 20] shares the lower tick
+            let tick_lower = &mut build_tick(0, order_liquidity + lp_liquidity, 1500).take();
+            let tick_upper = &mut build_tick(10, order_liquidity, -1000).take();
+            tick_upper.order_liquidity = order_liquidity;
+
+            // price moves up and crosses the upper tick, the order is filled
+            let liquidity_net = tick_upper.cross(0, 0, &[RewardInfo::default(); 3], 0, 0);
+            assert_eq!(liquidity_net, -1000);
+            let filled_liquidity = tick_upper.fill_orders(false).unwrap();
+            assert_eq!(filled_liquidity, order_liquidity);
+            tick_lower
+                .remove_order_liquidity_net(false, filled_liquidity)
+                .unwrap();
+            assert_eq!(identity(tick_upper.order_liquidity), 0);
+            assert_eq!(identity(tick_upper.order_fill_epoch), 1);
+
+            // only the position liquidity is changed when the price reverts
+            assert_eq!(identity(tick_upper.liquidity_net), 0);
+            assert_eq!(identity(tick_lower.liquidity_net), 500);
+            // liquidity_gross is kept until the order is closed
+            assert!(tick_upper.is_initialized());
+            assert!(!tick_lower.release_filled_order(order_liquidity).unwrap());
+            assert!(tick_upper.release_filled_order(order_liquidity).unwrap());
+            assert!(!tick_upper.is_initialized());
+            assert_eq!(identity(tick_upper.order_fill_epoch), 0);
+
+            // no order to fill
+            assert_eq!(tick_lower.fill_orders(true).unwrap(), 0);
+            assert_eq!(identity(tick_lower.liquidity_net), 500);
+        }
+    }
+
     mod tick_array_layout_test {
         use super::*;
         use anchor_lang::Discriminator;
@@ -1333,15 +1607,20 @@ pub mod tick_array_test {
                 0x11223344556600778899aabbccddeeff,
                 0x11223344556677008899aabbccddeeff,
             ];
-            let mut tick_padding: [u32; 13] = [0u32; 13];
-            let mut tick_padding_data = [0u8; 4 * 13];
+            let seconds_per_liquidity_outside_x64: u128 = 0x11223344556677880099aabbccddeeff;
+            let seconds_outside: u64 = 0x1122334455667700;
+            let order_liquidity: u128 = 0x112233445566778899aa00bbccddeeff;
+            let order_fill_epoch: u32 = 0x12345600;
+            let mut reward_generations: [u16; REWARD_NUM] = [0u16; REWARD_NUM];
+            let mut reward_generations_data = [0u8; 2 * REWARD_NUM];
             let mut offset = 0;
-            for i in 0..13 {
-                tick_padding[i] = u32::MAX - 3 * i as u32;
-                tick_padding_data[offset..offset + 4]
-                    .copy_from_slice(&tick_padding[i].to_le_bytes());
-                offset += 4;
+            for i in 0..REWARD_NUM {
+                reward_generations[i] = u16::MAX - 3 * i as u16;
+                reward_generations_data[offset..offset + 2]
+                    .copy_from_slice(&reward_generations[i].to_le_bytes());
+                offset += 2;
             }
+            let tick_padding: [u8; 2] = [0x12, 0x34];
 
             let mut tick_data = [0u8; TickState::LEN];
             let mut offset = 0;
@@ -1360,8 +1639,19 @@ pub mod tick_array_test {
                     .copy_from_slice(&reward_growths_outside_x64[i].to_le_bytes());
                 offset += 16;
             }
-            tick_data[offset..offset + 4 * 13].copy_from_slice(&tick_padding_data);
-            offset += 4 * 13;
+            tick_data[offset..offset + 16]
+                .copy_from_slice(&seconds_per_liquidity_outside_x64.to_le_bytes());
+            offset += 16;
+            tick_data[offset..offset + 8].copy_from_slice(&seconds_outside.to_le_bytes());
+            offset += 8;
+            tick_data[offset..offset + 16].copy_from_slice(&order_liquidity.to_le_bytes());
+            offset += 16;
+            tick_data[offset..offset + 4].copy_from_slice(&order_fill_epoch.to_le_bytes());
+            offset += 4;
+            tick_data[offset..offset + 2 * REWARD_NUM].copy_from_slice(&reward_generations_data);
+            offset += 2 * REWARD_NUM;
+            tick_data[offset..offset + 2].copy_from_slice(&tick_padding);
+            offset += 2;
             assert_eq!(offset, tick_data.len());
             assert_eq!(tick_data.len(), core::mem::size_of::<TickState>());
 
@@ -1419,6 +1709,20 @@ pub mod tick_array_test {
                     unpack_reward_growths_outside_x64,
                     reward_growths_outside_x64
                 );
+                let unpack_seconds_per_liquidity_outside_x64 =
+                    tick_item.seconds_per_liquidity_outside_x64;
+                assert_eq!(
+                    unpack_seconds_per_liquidity_outside_x64,
+                    seconds_per_liquidity_outside_x64
+                );
+                let unpack_seconds_outside = tick_item.seconds_outside;
+                assert_eq!(unpack_seconds_outside, seconds_outside);
+                let unpack_order_liquidity = tick_item.order_liquidity;
+                assert_eq!(unpack_order_liquidity, order_liquidity);
+                let unpack_order_fill_epoch = tick_item.order_fill_epoch;
+                assert_eq!(unpack_order_fill_epoch, order_fill_epoch);
+                let unpack_reward_generations = tick_item.reward_generations;
+                assert_eq!(unpack_reward_generations, reward_generations);
                 let unpack_tick_padding = tick_item.padding;
                 assert_eq!(unpack_tick_padding, tick_padding);
             }
@@ -1,3 +1,4 @@
+use crate::error::ErrorCode;
 use anchor_lang::{prelude::*, system_program};
 
 pub fn create_or_allocate_account<'a>(
@@ -55,6 +56,18 @@ pub fn create_or_allocate_account<'a>(
     Ok(())
 }
 
+/// Checks the transaction is not executed after the deadline unix timestamp, no check if it is `None`
+pub fn check_deadline(deadline: Option<i64>) -> Result<()> {
+    if let Some(deadline) = deadline {
+        require_gte!(
+            deadline,
+            Clock::get()?.unix_timestamp,
+            ErrorCode::TransactionTooOld
+        );
+    }
+    Ok(())
+}
+
 #[cfg(not(any(test, feature = "client")))]
 pub fn get_recent_epoch() -> Result<u64> {
     Ok(Clock::get()?.epoch)
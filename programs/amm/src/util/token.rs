@@ -1,13 +1,13 @@
-use super::get_recent_epoch;
+use super::{create_or_allocate_account, get_recent_epoch};
 use crate::error::ErrorCode;
 use crate::states::*;
 use anchor_lang::{
     prelude::*,
-    solana_program,
-    system_program::{create_account, CreateAccount},
+    solana_program::{self, program_pack::Pack},
+    system_program::{self, create_account, CreateAccount},
 };
 use anchor_spl::memo::spl_memo;
-use anchor_spl::token::{self, Token};
+use anchor_spl::token::{self, spl_token, Token};
 use anchor_spl::token_2022::{
     self,
     spl_token_2022::{
@@ -15,12 +15,13 @@ use anchor_spl::token_2022::{
         extension::{
             metadata_pointer,
             transfer_fee::{TransferFeeConfig, MAX_FEE_BASIS_POINTS},
-            BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+            transfer_hook, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
         },
     },
     Token2022,
 };
 use anchor_spl::token_interface::{initialize_mint2, InitializeMint2, Mint};
+use spl_transfer_hook_interface::onchain::add_extra_accounts_for_execute_cpi;
 use std::collections::HashSet;
 
 const MINT_WHITELIST: [&'static str; 6] = [
@@ -49,6 +50,7 @@ pub fn transfer_from_user_to_pool_vault<'info>(
     token_program: &AccountInfo<'info>,
     token_program_2022: Option<AccountInfo<'info>>,
     amount: u64,
+    transfer_hook_accounts: &[AccountInfo<'info>],
 ) -> Result<()> {
     if amount == 0 {
         return Ok(());
@@ -60,6 +62,18 @@ pub fn transfer_from_user_to_pool_vault<'info>(
             if from_token_info.owner == token_program_2022.key {
                 token_program_info = token_program_2022.to_account_info()
             }
+            if is_transfer_hook_mint(&mint)? {
+                return transfer_checked_with_transfer_hook(
+                    &token_program_info,
+                    from_token_info,
+                    &mint,
+                    to_vault.to_account_info(),
+                    signer.to_account_info(),
+                    transfer_hook_accounts,
+                    amount,
+                    &[],
+                );
+            }
             token_2022::transfer_checked(
                 CpiContext::new(
                     token_program_info,
@@ -96,6 +110,7 @@ pub fn transfer_from_pool_vault_to_user<'info>(
     token_program: &AccountInfo<'info>,
     token_program_2022: Option<AccountInfo<'info>>,
     amount: u64,
+    transfer_hook_accounts: &[AccountInfo<'info>],
 ) -> Result<()> {
     if amount == 0 {
         return Ok(());
@@ -107,6 +122,18 @@ pub fn transfer_from_pool_vault_to_user<'info>(
             if from_vault_info.owner == token_program_2022.key {
                 token_program_info = token_program_2022.to_account_info()
             }
+            if is_transfer_hook_mint(&mint)? {
+                return transfer_checked_with_transfer_hook(
+                    &token_program_info,
+                    from_vault_info,
+                    &mint,
+                    to.to_account_info(),
+                    pool_state_loader.to_account_info(),
+                    transfer_hook_accounts,
+                    amount,
+                    &[&pool_state_loader.load()?.seeds()],
+                );
+            }
             token_2022::transfer_checked(
                 CpiContext::new_with_signer(
                     token_program_info,
@@ -137,6 +164,351 @@ pub fn transfer_from_pool_vault_to_user<'info>(
     }
 }
 
+/// Seed of the account holding the extra accounts required by a transfer hook program
+pub const EXTRA_ACCOUNT_METAS_SEED: &str = "extra-account-metas";
+
+/// The hook program of the mint, `None` if the mint has no transfer hook extension with a hook program
+pub fn get_transfer_hook_program_id(
+    mint_account: &InterfaceAccount<Mint>,
+) -> Result<Option<Pubkey>> {
+    let mint_info = mint_account.to_account_info();
+    if *mint_info.owner == Token::id() {
+        return Ok(None);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    Ok(transfer_hook::get_program_id(&mint))
+}
+
+/// Whether the mint has the transfer hook extension with a hook program
+pub fn is_transfer_hook_mint(mint_account: &InterfaceAccount<Mint>) -> Result<bool> {
+    Ok(get_transfer_hook_program_id(mint_account)?.is_some())
+}
+
+/// The hook program of the mint and the account holding the extra accounts required by it,
+/// empty if the mint has no transfer hook
+pub fn get_transfer_hook_keys(mint_account: &InterfaceAccount<Mint>) -> Result<Vec<Pubkey>> {
+    let Some(program_id) = get_transfer_hook_program_id(mint_account)? else {
+        return Ok(Vec::new());
+    };
+    let extra_account_metas = Pubkey::find_program_address(
+        &[
+            EXTRA_ACCOUNT_METAS_SEED.as_bytes(),
+            mint_account.key().as_ref(),
+        ],
+        &program_id,
+    )
+    .0;
+    Ok(vec![program_id, extra_account_metas])
+}
+
+/// Transfer a mint with the transfer hook extension. The hook program, the extra account metas
+/// account and the extra accounts resolved from it are found in the transfer hook accounts by key,
+/// only these accounts are passed to the token program and the hook program.
+fn transfer_checked_with_transfer_hook<'info>(
+    token_program: &AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    transfer_hook_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let (cpi_instruction, cpi_account_infos) = transfer_checked_with_transfer_hook_instruction(
+        token_program.key,
+        from,
+        mint,
+        to,
+        authority,
+        transfer_hook_accounts,
+        amount,
+    )?;
+    solana_program::program::invoke_signed(&cpi_instruction, &cpi_account_infos, signer_seeds)?;
+    Ok(())
+}
+
+/// The transfer_checked instruction of a mint with the transfer hook extension, with the accounts
+/// required by the hook program resolved from the transfer hook accounts.
+fn transfer_checked_with_transfer_hook_instruction<'info>(
+    token_program_id: &Pubkey,
+    from: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    transfer_hook_accounts: &[AccountInfo<'info>],
+    amount: u64,
+) -> Result<(
+    solana_program::instruction::Instruction,
+    Vec<AccountInfo<'info>>,
+)> {
+    let Some(program_id) = get_transfer_hook_program_id(mint)? else {
+        return err!(ErrorCode::NotSupportMint);
+    };
+    let mint_info = mint.to_account_info();
+    let mut cpi_instruction = spl_token_2022::instruction::transfer_checked(
+        token_program_id,
+        from.key,
+        mint_info.key,
+        to.key,
+        authority.key,
+        &[],
+        amount,
+        mint.decimals,
+    )?;
+    let mut cpi_account_infos = vec![
+        from.clone(),
+        mint_info.clone(),
+        to.clone(),
+        authority.clone(),
+    ];
+    add_extra_accounts_for_execute_cpi(
+        &mut cpi_instruction,
+        &mut cpi_account_infos,
+        &program_id,
+        from,
+        mint_info,
+        to,
+        authority,
+        amount,
+        transfer_hook_accounts,
+    )?;
+    Ok((cpi_instruction, cpi_account_infos))
+}
+
+/// The accounts passed to the hook program by a transfer of the mint, including the hook program
+/// and the extra account metas account, empty if the mint has no transfer hook.
+/// Fails if an account required by the hook program is missing in the transfer hook accounts.
+pub fn get_transfer_hook_account_keys<'info>(
+    from: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    transfer_hook_accounts: &[AccountInfo<'info>],
+    amount: u64,
+) -> Result<Vec<Pubkey>> {
+    if !is_transfer_hook_mint(mint)? {
+        return Ok(Vec::new());
+    }
+    let (cpi_instruction, _) = transfer_checked_with_transfer_hook_instruction(
+        &spl_token_2022::id(),
+        from,
+        mint,
+        to,
+        authority,
+        transfer_hook_accounts,
+        amount,
+    )?;
+    // the first 4 accounts are the accounts of the transfer itself
+    Ok(cpi_instruction.accounts[4..]
+        .iter()
+        .map(|account_meta| account_meta.pubkey)
+        .collect())
+}
+
+/// Seed of the temporary token account used to unwrap SOL to a wallet
+pub const NATIVE_TEMP_ACCOUNT_SEED: &str = "native_temp";
+
+/// The accounts to wrap and unwrap SOL when the signer's wallet is passed in place of
+/// a token account of the native mint. The system program and the temporary account are
+/// found in the remaining accounts by key, the temporary account is only needed to unwrap.
+#[derive(Clone)]
+pub struct NativeAccounts<'info> {
+    pub wallet: Signer<'info>,
+    pub system_program: Option<AccountInfo<'info>>,
+    /// The temporary token account and its bump
+    pub temp_account: Option<(AccountInfo<'info>, u8)>,
+}
+
+impl<'info> NativeAccounts<'info> {
+    pub fn temp_account_address(wallet: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[NATIVE_TEMP_ACCOUNT_SEED.as_bytes(), wallet.as_ref()],
+            &crate::id(),
+        )
+    }
+
+    /// The system program can be given by the instruction itself, otherwise it is looked up
+    /// in the remaining accounts together with the temporary account.
+    pub fn load(
+        wallet: &Signer<'info>,
+        system_program: Option<AccountInfo<'info>>,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Self {
+        let mut native_accounts = NativeAccounts {
+            wallet: wallet.clone(),
+            system_program,
+            temp_account: None,
+        };
+        if let Some(system_program_info) = remaining_accounts
+            .iter()
+            .find(|account_info| account_info.key() == system_program::ID)
+        {
+            native_accounts.system_program = Some(system_program_info.clone());
+            let (temp_account_key, bump) = Self::temp_account_address(wallet.key);
+            native_accounts.temp_account = remaining_accounts
+                .iter()
+                .find(|account_info| account_info.key() == temp_account_key)
+                .map(|account_info| (account_info.clone(), bump));
+        }
+        native_accounts
+    }
+
+    /// Whether the remaining account is one of the native accounts
+    pub fn contains(&self, key: &Pubkey) -> bool {
+        *key == system_program::ID
+            || self
+                .temp_account
+                .as_ref()
+                .is_some_and(|(temp_account, _)| temp_account.key == key)
+    }
+
+    /// Whether the user account is the wallet itself, in place of a token account of the native mint
+    pub fn is_wallet(
+        &self,
+        user_account: &AccountInfo<'info>,
+        mint: &Option<Box<InterfaceAccount<'info, Mint>>>,
+    ) -> bool {
+        user_account.key == self.wallet.key
+            && mint
+                .as_ref()
+                .is_some_and(|mint| mint.key() == spl_token::native_mint::ID)
+    }
+
+    /// Transfer from the user to the pool vault, the SOL of the wallet is wrapped into the vault directly
+    pub fn transfer_from_user_to_pool_vault(
+        &self,
+        from: &AccountInfo<'info>,
+        to_vault: &AccountInfo<'info>,
+        mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+        token_program: &AccountInfo<'info>,
+        token_program_2022: Option<AccountInfo<'info>>,
+        amount: u64,
+        transfer_hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        if !self.is_wallet(from, &mint) {
+            return transfer_from_user_to_pool_vault(
+                &self.wallet,
+                from,
+                to_vault,
+                mint,
+                token_program,
+                token_program_2022,
+                amount,
+                transfer_hook_accounts,
+            );
+        }
+        if amount == 0 {
+            return Ok(());
+        }
+        let system_program = self
+            .system_program
+            .clone()
+            .ok_or(ErrorCode::MissingNativeAccount)?;
+        system_program::transfer(
+            CpiContext::new(
+                system_program,
+                system_program::Transfer {
+                    from: self.wallet.to_account_info(),
+                    to: to_vault.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        token::sync_native(CpiContext::new(
+            token_program.to_account_info(),
+            token::SyncNative {
+                account: to_vault.to_account_info(),
+            },
+        ))
+    }
+
+    /// Transfer from the pool vault to the user. To unwrap SOL to the wallet, the tokens are
+    /// moved to the temporary token account, which is then closed to the wallet.
+    pub fn transfer_from_pool_vault_to_user(
+        &self,
+        pool_state_loader: &AccountLoader<'info, PoolState>,
+        from_vault: &AccountInfo<'info>,
+        to: &AccountInfo<'info>,
+        mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+        token_program: &AccountInfo<'info>,
+        token_program_2022: Option<AccountInfo<'info>>,
+        amount: u64,
+        transfer_hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        if !self.is_wallet(to, &mint) {
+            return transfer_from_pool_vault_to_user(
+                pool_state_loader,
+                from_vault,
+                to,
+                mint,
+                token_program,
+                token_program_2022,
+                amount,
+                transfer_hook_accounts,
+            );
+        }
+        if amount == 0 {
+            return Ok(());
+        }
+        let (system_program, (temp_account, bump)) =
+            match (self.system_program.clone(), self.temp_account.clone()) {
+                (Some(system_program), Some(temp_account)) => (system_program, temp_account),
+                _ => return err!(ErrorCode::MissingNativeAccount),
+            };
+        create_or_allocate_account(
+            &token_program.key(),
+            self.wallet.to_account_info(),
+            system_program,
+            temp_account.clone(),
+            &[
+                NATIVE_TEMP_ACCOUNT_SEED.as_bytes(),
+                self.wallet.key.as_ref(),
+                &[bump],
+            ],
+            spl_token::state::Account::LEN,
+        )?;
+        token::initialize_account3(CpiContext::new(
+            token_program.to_account_info(),
+            token::InitializeAccount3 {
+                account: temp_account.clone(),
+                mint: mint.as_ref().unwrap().to_account_info(),
+                authority: self.wallet.to_account_info(),
+            },
+        ))?;
+        transfer_from_pool_vault_to_user(
+            pool_state_loader,
+            from_vault,
+            &temp_account,
+            mint,
+            token_program,
+            None,
+            amount,
+            &[],
+        )?;
+        close_spl_account(
+            &self.wallet.to_account_info(),
+            &self.wallet.to_account_info(),
+            &temp_account,
+            token_program,
+            &[],
+        )
+    }
+}
+
+/// The token amount of the user account, or the lamports if it is a wallet of the native mint
+pub fn get_user_token_amount(user_account: &AccountInfo) -> Result<u64> {
+    if *user_account.owner == system_program::ID {
+        return Ok(user_account.lamports());
+    }
+    if *user_account.owner != Token::id() && *user_account.owner != Token2022::id() {
+        return err!(ErrorCode::IllegalAccountOwner);
+    }
+    let data = user_account.try_borrow_data()?;
+    let token_account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+    Ok(token_account.base.amount)
+}
+
 pub fn close_spl_account<'a, 'b, 'c, 'info>(
     owner: &AccountInfo<'info>,
     destination: &AccountInfo<'info>,
@@ -285,10 +657,19 @@ pub fn is_supported_mint(
     let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
     let extensions = mint.get_extension_types()?;
     for e in extensions {
+        // The hook program runs on every transfer of the vaults, so the transfer hook mints
+        // must be whitelisted by the support mint associated account.
+        //
+        // The confidential transfer mints are supported with public balances only, the vaults are
+        // initialized without the confidential transfer account extension, so the vault can't be
+        // configured for confidential transfers and accepts the public transfers only.
+        // The confidential transfer fee mints are not supported, their token accounts require
+        // the ConfidentialTransferFeeAmount extension which the vaults are not initialized with.
         if e != ExtensionType::TransferFeeConfig
             && e != ExtensionType::MetadataPointer
             && e != ExtensionType::TokenMetadata
             && e != ExtensionType::InterestBearingConfig
+            && e != ExtensionType::ConfidentialTransferMint
         {
             return Ok(false);
         }
@@ -384,3 +765,77 @@ pub fn create_position_nft_mint_with_extensions<'info>(
         None,
     )
 }
+
+#[cfg(test)]
+mod token_test {
+    use super::*;
+    use anchor_spl::token_2022::spl_token_2022::extension::{
+        confidential_transfer::ConfidentialTransferMint,
+        confidential_transfer_fee::ConfidentialTransferFeeConfig, StateWithExtensionsMut,
+    };
+
+    fn new_mint_data(with_fee_config: bool) -> Vec<u8> {
+        let mut extensions = vec![ExtensionType::ConfidentialTransferMint];
+        if with_fee_config {
+            extensions.push(ExtensionType::ConfidentialTransferFeeConfig);
+        }
+        let space =
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&extensions)
+                .unwrap();
+        let mut data = vec![0u8; space];
+        let mut mint =
+            StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(&mut data)
+                .unwrap();
+        mint.init_extension::<ConfidentialTransferMint>(true)
+            .unwrap();
+        if with_fee_config {
+            mint.init_extension::<ConfidentialTransferFeeConfig>(true)
+                .unwrap();
+        }
+        mint.base = spl_token_2022::state::Mint {
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        mint.pack_base();
+        mint.init_account_type().unwrap();
+        data
+    }
+
+    #[test]
+    fn is_supported_mint_confidential_transfer_test() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = new_mint_data(false);
+        let mint_info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &token_2022::ID,
+            false,
+            0,
+        );
+        let mint_account = InterfaceAccount::<Mint>::try_from(&mint_info).unwrap();
+        assert!(is_supported_mint(&mint_account, false).unwrap());
+
+        // the vaults can't hold the withheld confidential transfer fees
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = new_mint_data(true);
+        let mint_info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &token_2022::ID,
+            false,
+            0,
+        );
+        let mint_account = InterfaceAccount::<Mint>::try_from(&mint_info).unwrap();
+        assert!(!is_supported_mint(&mint_account, false).unwrap());
+        assert!(is_supported_mint(&mint_account, true).unwrap());
+    }
+}